@@ -0,0 +1,123 @@
+//! Bearer token authentication.
+//!
+//! With `[api] require_token = true` every route except `/version` needs an
+//! `Authorization: Bearer <token>` header. The token is generated into the
+//! data directory on first start and only readable by the current user, so
+//! commands generated by `wisprarch keybind` and `wisprarch waybar` read it
+//! from there when they run.
+
+use super::error::ApiError;
+use anyhow::{bail, Context, Result};
+use axum::{
+    extract::{Request, State},
+    http::header::AUTHORIZATION,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::Path;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Read the token file, creating it with a new random token if missing.
+pub fn load_or_create_token(path: &Path) -> Result<String> {
+    if path.exists() {
+        let token = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let token = token.trim();
+        if token.is_empty() {
+            bail!(
+                "{} is empty; delete it to generate a new token",
+                path.display()
+            );
+        }
+        return Ok(token.to_string());
+    }
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let token = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(path)
+        .with_context(|| format!("Failed to create {}", path.display()))?;
+    writeln!(file, "{}", token).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(token)
+}
+
+/// Middleware rejecting requests without the expected bearer token.
+pub async fn require_token(
+    State(token): State<Arc<str>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let provided = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    match provided {
+        Some(provided) if constant_time_eq(provided.trim().as_bytes(), token.as_bytes()) => {
+            next.run(request).await
+        }
+        Some(_) => ApiError::unauthorized("Invalid API token").into_response(),
+        None => ApiError::unauthorized("Missing API token").into_response(),
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::StatusCode, middleware, routing::get, Router};
+    use std::os::unix::fs::PermissionsExt;
+    use tower::ServiceExt;
+
+    #[test]
+    fn test_token_is_generated_once_and_user_only() {
+        let path = std::env::temp_dir().join(format!("wisprarch-token-{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let token = load_or_create_token(&path).unwrap();
+        assert_eq!(token.len(), 64);
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert_eq!(load_or_create_token(&path).unwrap(), token);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_requests_need_the_token() {
+        let app = Router::new()
+            .route("/status", get(|| async { "ok" }))
+            .route_layer(middleware::from_fn_with_state(
+                Arc::<str>::from("secret"),
+                require_token,
+            ));
+        let request = |auth: Option<&str>| {
+            let mut builder = Request::builder().uri("/status");
+            if let Some(auth) = auth {
+                builder = builder.header(AUTHORIZATION, auth);
+            }
+            builder.body(Body::empty()).unwrap()
+        };
+
+        let status = |auth| {
+            let app = app.clone();
+            async move { app.oneshot(request(auth)).await.unwrap().status() }
+        };
+        assert_eq!(status(None).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(status(Some("Bearer wrong")).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(status(Some("Bearer secret")).await, StatusCode::OK);
+    }
+}
@@ -0,0 +1,303 @@
+//! Origin checks and CORS headers for browser clients.
+//!
+//! Browsers send an `Origin` header with cross-site requests. Requests from
+//! origins missing from `[api] allowed_origins` are rejected before any
+//! handler or token check runs, so a web page can't start the microphone or
+//! read history. Allowed origins get CORS headers, and their preflight
+//! `OPTIONS` requests are answered directly. Requests without an `Origin`
+//! header, such as curl from keybinds and Waybar, pass through unchanged.
+//!
+//! The `Host` header is checked as well: a page whose domain was rebound to
+//! 127.0.0.1 talks to the API as its own origin, but still names its domain
+//! as the host. Only `localhost`, `127.0.0.1`, the configured listen
+//! address and `[api] allowed_hosts` are accepted.
+
+use super::error::ApiError;
+use crate::config::ApiConfig;
+use axum::{
+    extract::{Request, State},
+    http::{
+        header::{
+            ACCESS_CONTROL_ALLOW_HEADERS, ACCESS_CONTROL_ALLOW_METHODS,
+            ACCESS_CONTROL_ALLOW_ORIGIN, ACCESS_CONTROL_MAX_AGE, HOST, ORIGIN, VARY,
+        },
+        HeaderMap, HeaderValue, Method, StatusCode,
+    },
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::sync::Arc;
+
+const ALLOWED_METHODS: &str = "GET, POST, PUT, DELETE, OPTIONS";
+const ALLOWED_HEADERS: &str = "Authorization, Content-Type";
+const PREFLIGHT_MAX_AGE_SECS: &str = "600";
+
+/// Origins allowed to call the API from a browser.
+#[derive(Debug, Clone, Default)]
+pub struct OriginAllowlist(Arc<[String]>);
+
+impl OriginAllowlist {
+    pub fn new(origins: &[String]) -> Self {
+        Self(
+            origins
+                .iter()
+                .map(|origin| normalize(origin))
+                .filter(|origin| !origin.is_empty())
+                .collect(),
+        )
+    }
+
+    pub fn allows(&self, origin: &str) -> bool {
+        self.0.contains(&normalize(origin))
+    }
+}
+
+fn normalize(origin: &str) -> String {
+    origin.trim().trim_end_matches('/').to_ascii_lowercase()
+}
+
+/// Host names requests may be addressed to.
+#[derive(Debug, Clone, Default)]
+pub struct HostAllowlist(Arc<[String]>);
+
+impl HostAllowlist {
+    pub fn new(api: &ApiConfig) -> Self {
+        let configured = api
+            .host
+            .trim()
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .to_ascii_lowercase();
+        let mut hosts = vec!["localhost".to_string(), "127.0.0.1".to_string()];
+        // A wildcard listen address is never what clients connect to
+        if !matches!(configured.as_str(), "0.0.0.0" | "::") {
+            hosts.push(configured);
+        }
+        hosts.extend(api.allowed_hosts.iter().map(|host| host_name(host)));
+        hosts.retain(|host| !host.is_empty());
+        Self(hosts.into())
+    }
+
+    pub fn allows(&self, host: &str) -> bool {
+        self.0.contains(&host_name(host))
+    }
+}
+
+/// The host name of a `Host` header, without port or IPv6 brackets.
+fn host_name(host: &str) -> String {
+    let host = host.trim().to_ascii_lowercase();
+    match host.strip_prefix('[') {
+        Some(ipv6) => ipv6.split(']').next().unwrap_or_default().to_string(),
+        None => host.split(':').next().unwrap_or_default().to_string(),
+    }
+}
+
+/// What `check_origin` lets through.
+#[derive(Debug, Clone, Default)]
+pub struct BrowserPolicy {
+    pub origins: OriginAllowlist,
+    pub hosts: HostAllowlist,
+}
+
+impl BrowserPolicy {
+    pub fn new(api: &ApiConfig) -> Self {
+        Self {
+            origins: OriginAllowlist::new(&api.allowed_origins),
+            hosts: HostAllowlist::new(api),
+        }
+    }
+}
+
+/// Middleware rejecting foreign hosts and origins and adding CORS headers for
+/// allowed origins.
+pub async fn check_origin(
+    State(policy): State<BrowserPolicy>,
+    request: Request,
+    next: Next,
+) -> Response {
+    // HTTP/1.0 clients may leave the header out; browsers always send it
+    if let Some(host) = request.headers().get(HOST) {
+        if !host.to_str().is_ok_and(|h| policy.hosts.allows(h)) {
+            return ApiError::new(
+                StatusCode::FORBIDDEN,
+                format!(
+                    "Host {} is not allowed (see [api] allowed_hosts)",
+                    String::from_utf8_lossy(host.as_bytes())
+                ),
+            )
+            .into_response();
+        }
+    }
+
+    let Some(origin) = request.headers().get(ORIGIN).cloned() else {
+        return next.run(request).await;
+    };
+
+    let allowed = origin.to_str().is_ok_and(|o| policy.origins.allows(o));
+    if !allowed {
+        return ApiError::new(
+            StatusCode::FORBIDDEN,
+            format!(
+                "Origin {} is not allowed (see [api] allowed_origins)",
+                String::from_utf8_lossy(origin.as_bytes())
+            ),
+        )
+        .into_response();
+    }
+
+    let mut response = if request.method() == Method::OPTIONS {
+        let mut response = StatusCode::NO_CONTENT.into_response();
+        let headers = response.headers_mut();
+        headers.insert(
+            ACCESS_CONTROL_ALLOW_METHODS,
+            HeaderValue::from_static(ALLOWED_METHODS),
+        );
+        headers.insert(
+            ACCESS_CONTROL_ALLOW_HEADERS,
+            HeaderValue::from_static(ALLOWED_HEADERS),
+        );
+        headers.insert(
+            ACCESS_CONTROL_MAX_AGE,
+            HeaderValue::from_static(PREFLIGHT_MAX_AGE_SECS),
+        );
+        // Chrome asks before letting a public page reach a local address
+        if request
+            .headers()
+            .get("access-control-request-private-network")
+            .is_some()
+        {
+            headers.insert(
+                "access-control-allow-private-network",
+                HeaderValue::from_static("true"),
+            );
+        }
+        response
+    } else {
+        next.run(request).await
+    };
+
+    allow_origin(response.headers_mut(), origin);
+    response
+}
+
+fn allow_origin(headers: &mut HeaderMap, origin: HeaderValue) {
+    headers.insert(ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+    headers.append(VARY, HeaderValue::from_static("Origin"));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, middleware, routing::post, Router};
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        let api = ApiConfig {
+            host: "::1".to_string(),
+            allowed_origins: vec!["moz-extension://abc-123/".to_string()],
+            ..ApiConfig::default()
+        };
+        Router::new()
+            .route("/toggle", post(|| async { "ok" }))
+            .layer(middleware::from_fn_with_state(
+                BrowserPolicy::new(&api),
+                check_origin,
+            ))
+    }
+
+    async fn send(method: Method, origin: Option<&str>) -> Response {
+        send_to(method, None, origin).await
+    }
+
+    async fn send_to(method: Method, host: Option<&str>, origin: Option<&str>) -> Response {
+        let mut builder = Request::builder().method(method).uri("/toggle");
+        if let Some(host) = host {
+            builder = builder.header(HOST, host);
+        }
+        if let Some(origin) = origin {
+            builder = builder.header(ORIGIN, origin);
+        }
+        app()
+            .oneshot(builder.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_foreign_hosts_are_rejected() {
+        for host in [
+            "localhost:3737",
+            "127.0.0.1:3737",
+            "[::1]:3737",
+            "LOCALHOST",
+        ] {
+            let response = send_to(Method::POST, Some(host), None).await;
+            assert_eq!(response.status(), StatusCode::OK, "{}", host);
+        }
+
+        // A rebound domain is same-origin to the page, so it sends no Origin
+        for host in ["evil.example:3737", "localhost.evil.example", "[::2]:3737"] {
+            let response = send_to(Method::POST, Some(host), None).await;
+            assert_eq!(response.status(), StatusCode::FORBIDDEN, "{}", host);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_foreign_origins_are_rejected() {
+        let response = send(Method::POST, Some("https://example.com")).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let response = send(Method::OPTIONS, Some("https://example.com")).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let response = send(Method::POST, None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response
+            .headers()
+            .get(ACCESS_CONTROL_ALLOW_ORIGIN)
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_allowed_origin_gets_cors_headers() {
+        let response = send(Method::OPTIONS, Some("moz-extension://abc-123")).await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert_eq!(
+            response.headers()[ACCESS_CONTROL_ALLOW_ORIGIN],
+            "moz-extension://abc-123"
+        );
+        assert!(response.headers()[ACCESS_CONTROL_ALLOW_HEADERS]
+            .to_str()
+            .unwrap()
+            .contains("Authorization"));
+
+        let response = send(Method::POST, Some("moz-extension://abc-123")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[ACCESS_CONTROL_ALLOW_ORIGIN],
+            "moz-extension://abc-123"
+        );
+    }
+
+    #[test]
+    fn test_wildcard_host_needs_allowed_hosts() {
+        let api = ApiConfig {
+            host: "0.0.0.0".to_string(),
+            ..ApiConfig::default()
+        };
+        let hosts = HostAllowlist::new(&api);
+        assert!(hosts.allows("localhost:3737"));
+        assert!(!hosts.allows("0.0.0.0:3737"));
+        assert!(!hosts.allows("192.168.1.20:3737"));
+
+        let api = ApiConfig {
+            host: "::".to_string(),
+            allowed_hosts: vec!["192.168.1.20".to_string(), "desk.lan:3737".to_string()],
+            ..ApiConfig::default()
+        };
+        let hosts = HostAllowlist::new(&api);
+        assert!(hosts.allows("192.168.1.20:3737"));
+        assert!(hosts.allows("DESK.lan:3737"));
+        assert!(!hosts.allows("[::]:3737"));
+    }
+}
@@ -1,19 +1,33 @@
+pub mod auth;
+pub mod cors;
 pub mod error;
 pub mod routes;
 
-use crate::config::Config;
+use crate::audio::JobOptions;
+use crate::config::{ApiConfig, Config};
+use crate::global;
+use crate::sinks::Sinks;
 use crate::text_io::TextIoService;
-use anyhow::Result;
-use axum::{response::Json, routing::get, Router};
+use crate::transcription::{ProviderCapabilities, TranscriptionService};
+use anyhow::{bail, Context, Result};
+use axum::{middleware, response::Json, routing::get, Router};
+use hyper_util::rt::TokioIo;
+use hyper_util::service::TowerToHyperService;
 use serde_json::{json, Value};
+use std::os::unix::fs::{DirBuilderExt, FileTypeExt, PermissionsExt};
+use std::path::Path;
+use std::sync::Arc;
+use tokio::net::{UnixListener, UnixStream};
 use tower::ServiceBuilder;
-use tracing::info;
+use tracing::{debug, info};
+use uuid::Uuid;
 
 pub use routes::recording::{ApiCommand, RecordingState, ToggleRequest};
 
 pub struct ApiServer {
-    port: u16,
+    api: ApiConfig,
     recording_state: RecordingState,
+    transcribe_state: routes::transcribe::TranscribeState,
 }
 
 impl ApiServer {
@@ -22,43 +36,99 @@ impl ApiServer {
         status: crate::audio::RecordingStatusHandle,
         config: &Config,
         text_io: TextIoService,
+        capabilities: ProviderCapabilities,
+        sinks: Sinks,
+        transcription: Arc<TranscriptionService>,
     ) -> Self {
         Self {
-            port: 3737,
+            api: config.api.clone(),
             recording_state: RecordingState {
                 tx,
                 status,
                 waybar_config: config.ui.waybar.clone(),
                 text_io,
+                capabilities,
+                sinks,
+                job_defaults: JobOptions::from_behavior(
+                    config.behavior.auto_paste,
+                    config.behavior.restore_clipboard,
+                ),
+            },
+            transcribe_state: routes::transcribe::TranscribeState {
+                transcription,
+                delete_audio_files: config.behavior.delete_audio_files,
             },
         }
     }
 
     pub async fn start(self) -> Result<()> {
-        let app = Router::new()
-            // Root and version endpoints
+        let mut app = Router::new()
+            // Root endpoint
             .route("/", get(status))
-            .route("/version", get(version))
             // Recording control endpoints
+            .merge(routes::events::router(self.recording_state.status.clone()))
             .nest("", routes::recording::router(self.recording_state))
+            .merge(routes::transcribe::router(self.transcribe_state))
             // Other API routes
             .nest("/history", routes::history::router())
             .nest("/keybind", routes::keybind::router())
             .nest("/logs", routes::logs::router())
             .nest("/provider", routes::provider::router())
             .nest("/update", routes::update::router())
-            .layer(ServiceBuilder::new());
+            .nest("/usage", routes::usage::router());
+
+        if self.api.require_token {
+            let token_file = global::api_token_file()?;
+            let token = auth::load_or_create_token(&token_file)?;
+            app = app.route_layer(middleware::from_fn_with_state(
+                Arc::<str>::from(token),
+                auth::require_token,
+            ));
+            info!("API token required (stored in {})", token_file.display());
+        }
 
-        let listener = tokio::net::TcpListener::bind(&format!("127.0.0.1:{}", self.port)).await?;
+        // The version endpoint stays reachable without a token. Origins are
+        // checked on every route, before the token.
+        let app = app
+            .route("/version", get(version))
+            .layer(ServiceBuilder::new().layer(middleware::from_fn_with_state(
+                cors::BrowserPolicy::new(&self.api),
+                cors::check_origin,
+            )));
+
+        if !self.api.tcp && self.api.socket_path().is_none() {
+            bail!("[api] tcp = false needs unix_socket, or the API can't be reached");
+        }
+
+        let listener = if self.api.tcp {
+            let addr = self.api.tcp_addr();
+            let listener = tokio::net::TcpListener::bind(&addr)
+                .await
+                .with_context(|| format!("Failed to listen on {} (see [api] in config)", addr))?;
+            info!("API server listening on http://{}", addr);
+            Some(listener)
+        } else {
+            None
+        };
+
+        let unix_listener = match self.api.socket_path() {
+            Some(path) => {
+                let unix_listener = bind_unix_socket(&path).await?;
+                info!("API server listening on unix:{}", path.display());
+                Some(unix_listener)
+            }
+            None => None,
+        };
 
-        info!("API server listening on http://127.0.0.1:{}", self.port);
         info!("Endpoints:");
         info!("  GET  /              - Service info");
         info!("  POST /toggle        - Toggle recording");
         info!("  GET  /status        - Get recording status");
+        info!("  GET  /events        - Stream daemon events (SSE)");
+        info!("  POST /transcribe    - Transcribe an uploaded audio file");
         info!("  GET  /version       - Get version info");
         info!("  GET  /input-method  - Get current input method");
-        info!("  POST /input-method  - Set input method (clipboard/wtype/ydotool)");
+        info!("  POST /input-method  - Set input method (clipboard/wtype/ydotool/xdotool)");
         info!("  POST /input-method/cycle - Cycle through available methods");
         info!("  GET  /history       - List transcription history");
         info!("  GET  /history/:id   - Get single transcription");
@@ -71,13 +141,87 @@ impl ApiServer {
         info!("  GET  /update/check  - Check for updates");
         info!("  POST /update/install - Install update");
         info!("  PUT  /update/auto   - Toggle auto-update");
+        info!("  GET  /usage         - Get usage and cost rollups");
 
-        axum::serve(listener, app).await?;
+        match (listener, unix_listener) {
+            (Some(listener), Some(unix_listener)) => {
+                let tcp = axum::serve(listener, app.clone());
+                tokio::try_join!(
+                    async { tcp.await.map_err(anyhow::Error::from) },
+                    serve_unix(unix_listener, app),
+                )?;
+            }
+            (Some(listener), None) => axum::serve(listener, app).await?,
+            (None, Some(unix_listener)) => serve_unix(unix_listener, app).await?,
+            (None, None) => unreachable!("checked above"),
+        }
 
         Ok(())
     }
 }
 
+/// Bind the API socket, readable and writable only by the current user.
+async fn bind_unix_socket(path: &Path) -> Result<UnixListener> {
+    if let Ok(metadata) = std::fs::symlink_metadata(path) {
+        if !metadata.file_type().is_socket() {
+            bail!("{} exists and is not a socket", path.display());
+        }
+        if UnixStream::connect(path).await.is_ok() {
+            bail!(
+                "{} is in use by another wisprarch instance (see [api] in config)",
+                path.display()
+            );
+        }
+        // Left behind by an instance that didn't shut down cleanly
+        std::fs::remove_file(path)
+            .with_context(|| format!("Failed to remove stale socket {}", path.display()))?;
+    }
+    let parent = path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    std::fs::create_dir_all(parent)
+        .with_context(|| format!("Failed to create {}", parent.display()))?;
+
+    // Bind inside a directory only we can enter and narrow the socket to
+    // 0600 there, then move it into place, so no other user can connect
+    // while it still has the default mode
+    let staging = parent.join(format!(".wisprarch-socket-{}", Uuid::new_v4()));
+    std::fs::DirBuilder::new()
+        .mode(0o700)
+        .create(&staging)
+        .with_context(|| format!("Failed to create {}", staging.display()))?;
+    let staged = staging.join("api.sock");
+    let listener = UnixListener::bind(&staged)
+        .with_context(|| format!("Failed to listen on {}", path.display()))
+        .and_then(|listener| {
+            std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o600))
+                .and_then(|()| std::fs::rename(&staged, path))
+                .with_context(|| format!("Failed to move the socket to {}", path.display()))
+                .map(|()| listener)
+        });
+    let _ = std::fs::remove_file(&staged);
+    let _ = std::fs::remove_dir(&staging);
+    listener
+}
+
+/// Serve the router on a Unix socket. `axum::serve` only accepts TCP
+/// listeners, so connections are handed to hyper directly.
+async fn serve_unix(listener: UnixListener, app: Router) -> Result<()> {
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let service = TowerToHyperService::new(app.clone());
+        tokio::spawn(async move {
+            if let Err(e) = hyper::server::conn::http1::Builder::new()
+                .serve_connection(TokioIo::new(stream), service)
+                .await
+            {
+                debug!("Unix socket connection error: {}", e);
+            }
+        });
+    }
+}
+
 async fn status() -> Json<Value> {
     Json(json!({
         "service": "wisprarch",
@@ -92,3 +236,42 @@ async fn version() -> Json<Value> {
         "name": "wisprarch"
     }))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[tokio::test]
+    async fn test_unix_socket_is_user_only_and_serves_router() {
+        let path = std::env::temp_dir().join(format!("wisprarch-api-{}.sock", std::process::id()));
+        let listener = bind_unix_socket(&path).await.unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        // The staging directory is gone
+        assert!(!std::fs::read_dir(std::env::temp_dir())
+            .unwrap()
+            .flatten()
+            .any(|entry| entry
+                .file_name()
+                .to_string_lossy()
+                .starts_with(".wisprarch-socket-")));
+
+        let app = Router::new().route("/version", get(version));
+        tokio::spawn(serve_unix(listener, app));
+
+        let mut stream = UnixStream::connect(&path).await.unwrap();
+        stream
+            .write_all(b"GET /version HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"));
+        assert!(response.contains("wisprarch"));
+
+        // A second instance must not take over a socket that is in use
+        assert!(bind_unix_socket(&path).await.is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,86 @@
+//! Server-Sent Events endpoint.
+//!
+//! `GET /events` streams daemon events. Each SSE event is named after the
+//! event type and carries it as JSON. The current phase is sent first, and
+//! `?types=phase,job_completed` limits the stream to the listed types.
+
+use crate::audio::RecordingStatusHandle;
+use crate::events::DaemonEvent;
+use axum::{
+    extract::{Query, State},
+    response::sse::{Event, KeepAlive, Sse},
+    routing::get,
+    Router,
+};
+use futures_util::stream::{self, Stream, StreamExt};
+use serde::Deserialize;
+use std::convert::Infallible;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{debug, warn};
+
+#[derive(Debug, Deserialize, Default)]
+pub struct EventsQueryParams {
+    /// Comma-separated event types to send (default: all)
+    pub types: Option<String>,
+}
+
+/// Create the events router.
+pub fn router(status: RecordingStatusHandle) -> Router {
+    Router::new()
+        .route("/events", get(stream_events))
+        .with_state(status)
+}
+
+async fn stream_events(
+    State(status): State<RecordingStatusHandle>,
+    Query(params): Query<EventsQueryParams>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let types: Option<Vec<String>> = params.types.map(|types| {
+        types
+            .split(',')
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .collect()
+    });
+    let wanted = move |event: &DaemonEvent| {
+        types
+            .as_ref()
+            .is_none_or(|types| types.iter().any(|t| t == event.name()))
+    };
+
+    // Subscribe before reading the phase so no transition is missed
+    let rx = status.events().subscribe();
+    let current = DaemonEvent::Phase {
+        phase: status.get().await.phase,
+    };
+
+    let updates = stream::unfold(rx, |mut rx| async move {
+        loop {
+            match rx.recv().await {
+                Ok(event) => return Some((event, rx)),
+                Err(RecvError::Lagged(missed)) => {
+                    debug!("Event stream subscriber skipped {} events", missed)
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    });
+
+    let events = stream::once(async move { current })
+        .chain(updates)
+        .filter(move |event| std::future::ready(wanted(event)))
+        .map(|event| Ok(to_sse(&event)));
+
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
+
+fn to_sse(event: &DaemonEvent) -> Event {
+    let sse = Event::default().event(event.name());
+    match sse.clone().json_data(event) {
+        Ok(sse) => sse,
+        Err(e) => {
+            warn!("Failed to encode {} event: {}", event.name(), e);
+            sse.data("{}")
+        }
+    }
+}
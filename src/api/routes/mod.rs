@@ -1,8 +1,11 @@
 //! API route modules.
 
+pub mod events;
 pub mod history;
 pub mod keybind;
 pub mod logs;
 pub mod provider;
 pub mod recording;
+pub mod transcribe;
 pub mod update;
+pub mod usage;
@@ -6,8 +6,10 @@
 //! - Switching input method (POST /input-method, GET /input-method)
 
 use crate::audio::{JobOptions, RecordingPhase, RecordingStatus, RecordingStatusHandle, NUM_BANDS};
-use crate::config::WaybarConfig;
+use crate::config::{ClipboardTarget, DictationMode, WaybarConfig};
+use crate::sinks::Sinks;
 use crate::text_io::{InjectionMethod, TextIoService};
+use crate::transcription::ProviderCapabilities;
 use axum::{
     extract::{Query, State},
     http::StatusCode,
@@ -24,12 +26,28 @@ use tracing::{error, info};
 /// All fields are optional - if not provided, defaults are used from config.
 #[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 pub struct ToggleRequest {
-    /// Whether to copy the transcription to clipboard (default: true)
+    /// Whether to copy the transcription to clipboard (default: false when
+    /// `behavior.restore_clipboard` is set, true otherwise)
     #[serde(default)]
     pub copy_to_clipboard: Option<bool>,
     /// Whether to auto-paste/inject text into the focused app (default: from config)
     #[serde(default)]
     pub auto_paste: Option<bool>,
+    /// Language override for this job (default: from config)
+    #[serde(default)]
+    pub language: Option<String>,
+    /// "prose" or "code" dictation (default: prose)
+    #[serde(default)]
+    pub mode: Option<DictationMode>,
+    /// Names of the `[[sinks]]` that receive this job (default: the enabled sinks)
+    #[serde(default)]
+    pub sinks: Option<Vec<String>>,
+    /// "clipboard", "primary", "both" or "none" (default: from config)
+    #[serde(default)]
+    pub clipboard_target: Option<ClipboardTarget>,
+    /// Hide the copy from clipboard managers (default: from config)
+    #[serde(default)]
+    pub clipboard_sensitive: Option<bool>,
 }
 
 #[derive(Clone)]
@@ -44,6 +62,10 @@ pub struct RecordingState {
     pub status: RecordingStatusHandle,
     pub waybar_config: WaybarConfig,
     pub text_io: TextIoService,
+    pub capabilities: ProviderCapabilities,
+    pub sinks: Sinks,
+    /// Options for fields a toggle request leaves out, from `[behavior]`
+    pub job_defaults: JobOptions,
 }
 
 pub fn router(state: RecordingState) -> Router {
@@ -62,25 +84,62 @@ pub fn router(state: RecordingState) -> Router {
 /// Optional JSON with fields:
 /// - `copy_to_clipboard`: bool - Copy transcription to clipboard
 /// - `auto_paste`: bool - Auto-paste/inject text into focused app
+/// - `language`: string - Language override, checked against provider capabilities
+/// - `mode`: string - `"prose"` or `"code"` dictation
+/// - `sinks`: string[] - `[[sinks]]` that receive the transcript
+/// - `clipboard_target`: string - `"clipboard"`, `"primary"`, `"both"` or `"none"`
+/// - `clipboard_sensitive`: bool - Hide the copy from clipboard managers
 ///
 /// # Response
 /// Returns JSON with recording status and current job information.
 async fn toggle_recording(
     State(state): State<RecordingState>,
     body: Option<Json<ToggleRequest>>,
-) -> Result<Json<Value>, StatusCode> {
-    // Parse optional job options from request body
-    let job_options = body.and_then(|Json(req)| {
-        // Only create JobOptions if at least one field was specified
-        if req.copy_to_clipboard.is_some() || req.auto_paste.is_some() {
-            Some(JobOptions {
-                copy_to_clipboard: req.copy_to_clipboard.unwrap_or(true),
-                auto_paste: req.auto_paste.unwrap_or(true),
-            })
-        } else {
-            None
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let request = body.map(|Json(req)| req).unwrap_or_default();
+
+    if let Some(language) = &request.language {
+        if let Err(error) = state.capabilities.check_language(language) {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "error": true, "message": error })),
+            ));
         }
-    });
+    }
+
+    if let Some(sinks) = &request.sinks {
+        if let Err(error) = state.sinks.check_names(sinks) {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "error": true, "message": error })),
+            ));
+        }
+    }
+
+    // Only create JobOptions if at least one field was specified
+    let job_options = if request.copy_to_clipboard.is_some()
+        || request.auto_paste.is_some()
+        || request.language.is_some()
+        || request.mode.is_some()
+        || request.sinks.is_some()
+        || request.clipboard_target.is_some()
+        || request.clipboard_sensitive.is_some()
+    {
+        Some(JobOptions {
+            copy_to_clipboard: request
+                .copy_to_clipboard
+                .unwrap_or(state.job_defaults.copy_to_clipboard),
+            auto_paste: request.auto_paste.unwrap_or(state.job_defaults.auto_paste),
+            language: request.language,
+            mode: request.mode.unwrap_or_default(),
+            sinks: request.sinks,
+            clipboard_target: request.clipboard_target,
+            clipboard_sensitive: request.clipboard_sensitive,
+            ..state.job_defaults.clone()
+        })
+    } else {
+        None
+    };
 
     info!(
         "Toggle recording command received via API with options: {:?}",
@@ -108,7 +167,10 @@ async fn toggle_recording(
         }
         Err(e) => {
             error!("Failed to send toggle command: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": true, "message": "Failed to send toggle command" })),
+            ))
         }
     }
 }
@@ -151,6 +213,7 @@ async fn recording_status(
         "last_error": status.last_error,
         "audio_level": status.audio_level,
         "frequency_bands": status.frequency_bands,
+        "provider": status.provider,
     }))
 }
 
@@ -283,7 +346,7 @@ async fn set_input_method(
         None => Err((
             StatusCode::BAD_REQUEST,
             Json(json!({
-                "error": format!("Invalid method '{}'. Use: clipboard, wtype, or ydotool", req.method),
+                "error": format!("Invalid method '{}'. Use: clipboard, wtype, ydotool, or xdotool", req.method),
                 "available": get_available_methods()
             })),
         )),
@@ -300,12 +363,9 @@ async fn cycle_input_method(State(state): State<RecordingState>) -> Json<Value>
 }
 
 fn get_available_methods() -> Vec<&'static str> {
-    let mut methods = vec!["clipboard"];
-    if which::which("wtype").is_ok() {
-        methods.push("wtype");
-    }
-    if which::which("ydotool").is_ok() {
-        methods.push("ydotool");
-    }
-    methods
+    InjectionMethod::ALL
+        .iter()
+        .filter(|method| method.is_available())
+        .map(InjectionMethod::as_str)
+        .collect()
 }
@@ -0,0 +1,207 @@
+//! File transcription endpoint.
+//!
+//! `POST /transcribe` runs uploaded audio through the daemon's provider and
+//! post-processing chain without touching the microphone, clipboard or
+//! focused window. The body is either raw WAV/FLAC/OGG/MP3 or
+//! `multipart/form-data` with the audio in a `file` field.
+
+use crate::api::error::{ApiError, ApiResult};
+use crate::config::DictationMode;
+use crate::history;
+use crate::transcription::upload::{self, AudioFormat, UploadedAudio};
+use crate::transcription::{TranscribeOptions, TranscriptResult, TranscriptionService};
+use axum::{
+    body::Bytes,
+    extract::{DefaultBodyLimit, FromRequest, Multipart, Query, Request, State},
+    http::{header::CONTENT_TYPE, StatusCode},
+    response::Json,
+    routing::post,
+    Router,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use tracing::{error, info};
+
+/// Largest accepted upload.
+const MAX_UPLOAD_BYTES: usize = 100 * 1024 * 1024;
+
+#[derive(Clone)]
+pub struct TranscribeState {
+    pub transcription: Arc<TranscriptionService>,
+    pub delete_audio_files: bool,
+}
+
+/// Options for a transcription, as query parameters or multipart fields.
+#[derive(Debug, Deserialize, Default)]
+pub struct TranscribeQueryParams {
+    /// Language override (default: from config)
+    pub language: Option<String>,
+    /// "prose" or "code" post-processing (default: prose)
+    pub mode: Option<DictationMode>,
+    /// Save the transcript to history (default: false)
+    pub save: Option<bool>,
+}
+
+/// Create the transcribe router.
+pub fn router(state: TranscribeState) -> Router {
+    Router::new()
+        .route("/transcribe", post(transcribe))
+        .layer(DefaultBodyLimit::max(MAX_UPLOAD_BYTES))
+        .with_state(state)
+}
+
+/// POST /transcribe - Transcribe an uploaded recording.
+async fn transcribe(
+    State(state): State<TranscribeState>,
+    Query(mut params): Query<TranscribeQueryParams>,
+    request: Request,
+) -> ApiResult<Json<TranscriptResult>> {
+    let is_multipart = request
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("multipart/form-data"));
+
+    let data = if is_multipart {
+        let multipart = Multipart::from_request(request, &())
+            .await
+            .map_err(|e| ApiError::bad_request(e.body_text()))?;
+        read_multipart(multipart, &mut params).await?
+    } else {
+        Bytes::from_request(request, &())
+            .await
+            .map_err(|e| ApiError::new(e.status(), e.body_text()))?
+    };
+
+    let capabilities = state.transcription.capabilities();
+    if let Some(language) = &params.language {
+        capabilities
+            .check_language(language)
+            .map_err(ApiError::bad_request)?;
+    }
+
+    let format = AudioFormat::detect(&data).ok_or_else(|| {
+        ApiError::new(
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            "Unrecognized audio; upload WAV, FLAC, OGG or MP3",
+        )
+    })?;
+    if format.needs_conversion() && !upload::can_convert() {
+        return Err(ApiError::new(
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            format!(
+                "Install ffmpeg to transcribe {} uploads, or upload WAV",
+                format.extension()
+            ),
+        ));
+    }
+
+    let mut audio = UploadedAudio::save(&data, format)
+        .await
+        .map_err(ApiError::from)?
+        .into_wav()
+        .await
+        .map_err(|e| ApiError::bad_request(e.to_string()))?;
+
+    let duration_secs = crate::audio::wav_duration_secs(audio.path())
+        .map_err(|e| ApiError::bad_request(format!("Unreadable WAV upload: {:#}", e)))?;
+    capabilities
+        .check_duration(duration_secs)
+        .map_err(|e| ApiError::new(StatusCode::PAYLOAD_TOO_LARGE, e))?;
+    let size = tokio::fs::metadata(audio.path())
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?
+        .len();
+    capabilities
+        .check_upload_size(size)
+        .map_err(|e| ApiError::new(StatusCode::PAYLOAD_TOO_LARGE, e))?;
+
+    info!(
+        "Transcribing {} upload ({} bytes) via API",
+        format.extension(),
+        data.len()
+    );
+
+    let audio_path = audio.path().to_path_buf();
+    let transcript = state
+        .transcription
+        .transcribe_detailed(
+            &audio_path,
+            TranscribeOptions {
+                language: params.language.as_deref(),
+                mode: params.mode.unwrap_or_default(),
+                app: None,
+                ..TranscribeOptions::default()
+            },
+        )
+        .await
+        .map_err(|e| {
+            error!("Upload transcription failed: {}", e);
+            ApiError::from(e)
+        })?;
+
+    let history_id = if params.save.unwrap_or(false) && !transcript.text.trim().is_empty() {
+        if !state.delete_audio_files {
+            audio.keep();
+        }
+        let text = transcript.text.clone();
+        let raw_text = transcript.raw_text.clone();
+        let id =
+            tokio::task::spawn_blocking(move || history::save(&text, Some(&raw_text), &audio_path))
+                .await
+                .map_err(|e| ApiError::internal(format!("Database task panicked: {}", e)))?
+                .map_err(ApiError::from)?;
+        Some(id)
+    } else {
+        None
+    };
+
+    Ok(Json(TranscriptResult {
+        raw_text: (transcript.raw_text != transcript.text).then_some(transcript.raw_text),
+        text: transcript.text,
+        language: params.language,
+        duration_secs: Some(duration_secs),
+        history_id,
+    }))
+}
+
+/// Read the audio from the `file` field (or the first field with a file
+/// name) and options from the text fields.
+async fn read_multipart(
+    mut multipart: Multipart,
+    params: &mut TranscribeQueryParams,
+) -> ApiResult<Bytes> {
+    let bad_request = |e: axum::extract::multipart::MultipartError| {
+        ApiError::bad_request(format!("Invalid multipart body: {}", e.body_text()))
+    };
+
+    let mut audio = None;
+    while let Some(field) = multipart.next_field().await.map_err(bad_request)? {
+        let name = field.name().unwrap_or_default().to_string();
+        if name == "file" || (audio.is_none() && field.file_name().is_some()) {
+            audio = Some(field.bytes().await.map_err(bad_request)?);
+            continue;
+        }
+
+        let value = field.text().await.map_err(bad_request)?;
+        match name.as_str() {
+            "language" => params.language = Some(value),
+            "mode" => {
+                params.mode = Some(match value.as_str() {
+                    "prose" => DictationMode::Prose,
+                    "code" => DictationMode::Code,
+                    _ => {
+                        return Err(ApiError::bad_request(format!(
+                            "Unknown mode '{}' (expected prose or code)",
+                            value
+                        )))
+                    }
+                })
+            }
+            "save" => params.save = Some(matches!(value.as_str(), "true" | "1" | "yes")),
+            _ => {}
+        }
+    }
+
+    audio.ok_or_else(|| ApiError::bad_request("Multipart body has no file field"))
+}
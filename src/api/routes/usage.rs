@@ -0,0 +1,48 @@
+//! Usage API routes.
+
+use crate::api::error::{ApiError, ApiResult};
+use crate::config::Config;
+use crate::usage::{self, Granularity, QuotaReport, UsageSummary};
+use axum::{extract::Query, response::Json, routing::get, Router};
+use serde::{Deserialize, Serialize};
+
+/// Query parameters for usage rollups.
+#[derive(Debug, Deserialize, Default)]
+pub struct UsageQueryParams {
+    /// Rollup granularity: "daily" (default) or "monthly"
+    pub period: Option<String>,
+    /// Start date (YYYY-MM-DD)
+    pub since: Option<String>,
+}
+
+/// Usage rollups and current quota status.
+#[derive(Debug, Serialize)]
+pub struct UsageResponse {
+    pub usage: Vec<UsageSummary>,
+    pub quota: Option<QuotaReport>,
+}
+
+/// Create the usage router.
+pub fn router() -> Router {
+    Router::new().route("/", get(get_usage))
+}
+
+/// GET /usage - Get usage rollups with estimated costs.
+async fn get_usage(Query(params): Query<UsageQueryParams>) -> ApiResult<Json<UsageResponse>> {
+    let granularity = match params.period.as_deref() {
+        Some(period) => Granularity::parse(period).ok_or_else(|| {
+            ApiError::bad_request(format!(
+                "Invalid period '{}', expected daily or monthly",
+                period
+            ))
+        })?,
+        None => Granularity::Daily,
+    };
+
+    let config = Config::load().map_err(ApiError::from)?;
+    let usage = usage::rollup(&config.usage, granularity, params.since.as_deref())
+        .map_err(|e| ApiError::bad_request(e.to_string()))?;
+    let quota = usage::current_quota_report(&config).map_err(ApiError::from)?;
+
+    Ok(Json(UsageResponse { usage, quota }))
+}
@@ -6,11 +6,14 @@ use crate::audio::{
     ToggleResult,
 };
 use crate::config::Config;
+use crate::jobs;
+use crate::keybind;
+use crate::sinks::Sinks;
 use crate::text_io::TextIoService;
-use crate::transcription::{ProviderConfig, Transcriber, TranscriptionService};
+use crate::transcription::TranscriptionService;
 use crate::ui::Indicator;
 use crate::update::{UpdateConfig, UpdateEngine};
-use anyhow::{anyhow, Result};
+use anyhow::Result;
 use std::sync::Arc;
 use tokio::sync::{mpsc, Mutex};
 use tracing::{error, info, warn};
@@ -21,12 +24,17 @@ pub async fn run_service() -> Result<()> {
     let config = Config::load()?;
 
     let (tx, mut rx) = mpsc::channel::<ApiCommand>(10);
-    let audio_recorder = Arc::new(Mutex::new(AudioStreamManager::new()?));
+    let audio_recorder = Arc::new(Mutex::new(AudioStreamManager::new(
+        config.hallucination.speech_rms_threshold,
+    )?));
 
-    let whisper = build_transcriber(&config)?;
-    let transcription_service = Arc::new(TranscriptionService::new(whisper)?);
+    let transcription_service = Arc::new(TranscriptionService::from_config(&config)?);
+    let capabilities = transcription_service.capabilities();
 
-    let text_io = TextIoService::new(Some(&config.wayland.input_method))?;
+    let text_io = TextIoService::new(
+        Some(&config.wayland.input_method),
+        config.wayland.typing.clone(),
+    )?;
     let indicator = Indicator::new()
         .with_audio_feedback(config.behavior.audio_feedback)
         .with_custom_sounds(
@@ -34,20 +42,30 @@ pub async fn run_service() -> Result<()> {
             config.ui.sounds.complete_sound.clone(),
         );
 
+    let sinks = Sinks::new(&config.sinks)?;
     let status_handle = RecordingStatusHandle::default();
+    status_handle
+        .set_provider(transcription_service.provider_name())
+        .await;
     let recording_machine = RecordingMachine::new(
         audio_recorder.clone(),
-        transcription_service,
+        Arc::clone(&transcription_service),
         indicator,
         text_io.clone(),
-        BehaviorOptions {
-            auto_paste: config.behavior.auto_paste,
-            delete_audio_files: config.behavior.delete_audio_files,
-        },
+        BehaviorOptions::from_config(&config, sinks.clone())?,
         status_handle.clone(),
     );
+    jobs::spawn_retry_worker(recording_machine.retrier());
 
-    let api_server = ApiServer::new(tx, status_handle.clone(), &config, text_io);
+    let api_server = ApiServer::new(
+        tx,
+        status_handle.clone(),
+        &config,
+        text_io,
+        capabilities,
+        sinks,
+        transcription_service,
+    );
     tokio::spawn(async move {
         if let Err(e) = api_server.start().await {
             error!("API server failed: {}", e);
@@ -57,9 +75,10 @@ pub async fn run_service() -> Result<()> {
     spawn_update_manager();
 
     info!("wisprarch is ready!");
+    let toggle = keybind::toggle_command(&config.api);
     info!("Add this to your Hyprland config:");
-    info!("bindd = SUPER, R, wisprarch, exec, curl -X POST http://127.0.0.1:3737/toggle");
-    info!("Or test manually: curl -X POST http://127.0.0.1:3737/toggle");
+    info!("bindd = SUPER, R, wisprarch, exec, {}", toggle);
+    info!("Or test manually: {}", toggle);
 
     while let Some(command) = rx.recv().await {
         match command {
@@ -95,25 +114,6 @@ pub async fn run_service() -> Result<()> {
     Ok(())
 }
 
-fn build_transcriber(config: &Config) -> Result<Transcriber> {
-    let provider = config
-        .whisper
-        .provider
-        .as_deref()
-        .ok_or_else(|| anyhow!("No transcription provider configured. Set [whisper].provider in ~/.config/wisprarch/config.toml"))?;
-
-    let provider_config = ProviderConfig {
-        model: config.whisper.model.clone(),
-        model_path: config.whisper.model_path.clone(),
-        language: config.whisper.language.clone(),
-        command_path: config.whisper.command_path.clone(),
-        api_endpoint: config.whisper.api_endpoint.clone(),
-        api_key: config.whisper.api_key.clone(),
-    };
-
-    Transcriber::with_provider(provider, provider_config)
-}
-
 fn spawn_update_manager() {
     match UpdateConfig::detect(None)
         .and_then(UpdateEngine::new)
@@ -0,0 +1,473 @@
+//! Per-application profiles.
+//!
+//! When a recording starts, the focused Hyprland window is looked up with
+//! `hyprctl activewindow -j` and matched against the `[[apps]]` rules. The
+//! first matching rule overrides the job's injection method, post-processing
+//! chain, dictation mode, language and auto-submit. The window's address is
+//! kept with the job so the text goes to that window even if focus moved
+//! while the transcript was processed.
+
+use crate::audio::JobOptions;
+use crate::config::{AppRule, DictationMode};
+use crate::text_io::InjectionMethod;
+use crate::transcription::ProviderCapabilities;
+use anyhow::{anyhow, Context, Result};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::process::Command;
+use tracing::{debug, warn};
+
+/// Time for Hyprland to move keyboard focus after `focuswindow`.
+const FOCUS_DELAY: Duration = Duration::from_millis(80);
+
+/// The focused window as reported by Hyprland.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ActiveWindow {
+    pub address: String,
+    pub class: String,
+    pub title: String,
+}
+
+/// Query the focused window. Returns `None` outside Hyprland or when no
+/// window has focus.
+pub async fn active_window() -> Option<ActiveWindow> {
+    match query_active_window().await {
+        Ok(window) => window,
+        Err(e) => {
+            debug!("Could not query the active window: {:#}", e);
+            None
+        }
+    }
+}
+
+async fn query_active_window() -> Result<Option<ActiveWindow>> {
+    parse_active_window(&hyprctl(&["activewindow", "-j"]).await?)
+}
+
+/// Where the transcript of a job should be injected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecordedWindow {
+    /// No window was recorded, or it still has focus
+    Focused,
+    /// Focus was moved back to the recorded window from `previous`
+    Refocused { previous: String },
+    /// The recorded window was closed or could not be focused
+    Unavailable,
+}
+
+impl RecordedWindow {
+    /// Give focus back to the window that had it before `focus_recorded_window`.
+    pub async fn restore(self) {
+        if let RecordedWindow::Refocused { previous } = self {
+            if let Err(e) = focus_window(&previous).await {
+                debug!("Could not restore focus to {}: {:#}", previous, e);
+            }
+        }
+    }
+}
+
+/// Focus the window a recording started in, if focus has moved since.
+pub async fn focus_recorded_window(address: Option<&str>) -> RecordedWindow {
+    let Some(address) = address.filter(|address| !address.is_empty()) else {
+        return RecordedWindow::Focused;
+    };
+
+    let current = active_window().await.map(|window| window.address);
+    if current.as_deref() == Some(address) {
+        return RecordedWindow::Focused;
+    }
+
+    match window_exists(address).await {
+        Ok(true) => {}
+        Ok(false) => return RecordedWindow::Unavailable,
+        Err(e) => {
+            debug!("Could not list windows: {:#}", e);
+            return RecordedWindow::Unavailable;
+        }
+    }
+
+    if let Err(e) = focus_window(address).await {
+        warn!("Could not focus window {}: {:#}", address, e);
+        return RecordedWindow::Unavailable;
+    }
+    debug!("Refocused window {} from {:?}", address, current);
+    // Give the compositor a moment to move keyboard focus
+    tokio::time::sleep(FOCUS_DELAY).await;
+
+    RecordedWindow::Refocused {
+        previous: current.unwrap_or_default(),
+    }
+}
+
+async fn hyprctl(args: &[&str]) -> Result<Vec<u8>> {
+    let output = Command::new("hyprctl")
+        .args(args)
+        .output()
+        .await
+        .context("Failed to execute hyprctl")?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "hyprctl failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(output.stdout)
+}
+
+async fn focus_window(address: &str) -> Result<()> {
+    if address.is_empty() {
+        return Ok(());
+    }
+
+    let output = hyprctl(&["dispatch", "focuswindow", &format!("address:{}", address)]).await?;
+    let reply = String::from_utf8_lossy(&output);
+    if reply.trim() != "ok" {
+        return Err(anyhow!("hyprctl dispatch failed: {}", reply.trim()));
+    }
+    Ok(())
+}
+
+async fn window_exists(address: &str) -> Result<bool> {
+    let clients = hyprctl(&["clients", "-j"]).await?;
+    Ok(parse_client_addresses(&clients)?
+        .iter()
+        .any(|client| client == address))
+}
+
+fn parse_client_addresses(json: &[u8]) -> Result<Vec<String>> {
+    let clients: Vec<ActiveWindow> =
+        serde_json::from_slice(json).context("Invalid hyprctl clients output")?;
+    Ok(clients.into_iter().map(|client| client.address).collect())
+}
+
+fn parse_active_window(json: &[u8]) -> Result<Option<ActiveWindow>> {
+    let window: ActiveWindow =
+        serde_json::from_slice(json).context("Invalid hyprctl activewindow output")?;
+    Ok((!window.class.is_empty() || !window.title.is_empty()).then_some(window))
+}
+
+#[derive(Debug)]
+struct CompiledRule {
+    rule: AppRule,
+    class: Option<Regex>,
+    title: Option<Regex>,
+}
+
+impl CompiledRule {
+    fn matches(&self, window: &ActiveWindow) -> bool {
+        self.class
+            .as_ref()
+            .is_none_or(|re| re.is_match(&window.class))
+            && self
+                .title
+                .as_ref()
+                .is_none_or(|re| re.is_match(&window.title))
+    }
+}
+
+/// The `[[apps]]` rules, in config order.
+#[derive(Debug, Clone, Default)]
+pub struct AppProfiles {
+    rules: Arc<Vec<CompiledRule>>,
+}
+
+impl AppProfiles {
+    pub fn new(rules: &[AppRule]) -> Result<Self> {
+        let compiled = rules
+            .iter()
+            .map(|rule| {
+                if rule.name.is_empty() {
+                    return Err(anyhow!("[[apps]] rules need a name"));
+                }
+                if rule.class.is_none() && rule.title.is_none() {
+                    return Err(anyhow!(
+                        "[[apps]] rule '{}' needs a class or title pattern",
+                        rule.name
+                    ));
+                }
+                if let Some(method) = &rule.input_method {
+                    if InjectionMethod::parse(method).is_none() {
+                        return Err(anyhow!(
+                            "[[apps]] rule '{}' has unknown input_method '{}'",
+                            rule.name,
+                            method
+                        ));
+                    }
+                }
+                Ok(CompiledRule {
+                    rule: rule.clone(),
+                    class: compile(&rule.name, rule.class.as_deref())?,
+                    title: compile(&rule.name, rule.title.as_deref())?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            rules: Arc::new(compiled),
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Check the `language` overrides against the provider's languages, the
+    /// way a toggle's `language` is checked.
+    pub fn check_languages(
+        &self,
+        provider: &str,
+        capabilities: &ProviderCapabilities,
+    ) -> Result<()> {
+        for compiled in self.rules.iter() {
+            if let Some(language) = &compiled.rule.language {
+                capabilities.check_language(language).map_err(|error| {
+                    anyhow!(
+                        "[[apps]] rule '{}': {} for {}",
+                        compiled.rule.name,
+                        error,
+                        provider
+                    )
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// First rule matching the window.
+    pub fn find(&self, window: &ActiveWindow) -> Option<&AppRule> {
+        self.rules
+            .iter()
+            .find(|compiled| compiled.matches(window))
+            .map(|compiled| &compiled.rule)
+    }
+
+    /// Apply the rule matching `window` to a job's options.
+    ///
+    /// A language or code mode the job already asked for takes precedence
+    /// over the rule.
+    pub fn apply(&self, window: &ActiveWindow, options: &mut JobOptions) {
+        let Some(rule) = self.find(window) else {
+            debug!("No [[apps]] rule for window class={:?}", window.class);
+            return;
+        };
+
+        debug!("Applying [[apps]] rule '{}' to {:?}", rule.name, window);
+        options.app = Some(rule.name.clone());
+        if rule.input_method.is_some() {
+            options.input_method = rule.input_method.clone();
+        }
+        if rule.paste_shortcut.is_some() {
+            options.paste_shortcut = rule.paste_shortcut;
+        }
+        if options.language.is_none() {
+            options.language = rule.language.clone();
+        }
+        if let (DictationMode::Prose, Some(mode)) = (options.mode, rule.mode) {
+            options.mode = mode;
+        }
+        if let Some(auto_submit) = rule.auto_submit {
+            options.auto_submit = auto_submit;
+        }
+    }
+}
+
+fn compile(name: &str, pattern: Option<&str>) -> Result<Option<Regex>> {
+    pattern
+        .map(|pattern| {
+            RegexBuilder::new(pattern)
+                .case_insensitive(true)
+                .build()
+                .with_context(|| format!("Invalid pattern in [[apps]] rule '{}'", name))
+        })
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::PasteShortcut;
+
+    fn window(class: &str, title: &str) -> ActiveWindow {
+        ActiveWindow {
+            address: "0x1".to_string(),
+            class: class.to_string(),
+            title: title.to_string(),
+        }
+    }
+
+    fn terminal_rule() -> AppRule {
+        AppRule {
+            name: "terminal".to_string(),
+            class: Some("^(kitty|foot|Alacritty)$".to_string()),
+            paste_shortcut: Some(PasteShortcut::CtrlShiftV),
+            mode: Some(DictationMode::Code),
+            auto_submit: Some(true),
+            ..AppRule::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_active_window() {
+        let window = parse_active_window(
+            br#"{"address": "0x55d1", "mapped": true, "class": "kitty", "title": "~/src"}"#,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(window.address, "0x55d1");
+        assert_eq!(window.class, "kitty");
+        assert_eq!(window.title, "~/src");
+
+        assert!(parse_active_window(b"{}").unwrap().is_none());
+        assert!(parse_active_window(b"Invalid").is_err());
+    }
+
+    #[test]
+    fn test_parse_client_addresses() {
+        let addresses = parse_client_addresses(
+            br#"[{"address": "0x1", "class": "kitty"}, {"address": "0x2", "class": "firefox"}]"#,
+        )
+        .unwrap();
+        assert_eq!(addresses, vec!["0x1", "0x2"]);
+    }
+
+    #[tokio::test]
+    async fn test_no_recorded_window_keeps_focus() {
+        assert_eq!(focus_recorded_window(None).await, RecordedWindow::Focused);
+        RecordedWindow::Focused.restore().await;
+    }
+
+    #[test]
+    fn test_first_matching_rule_wins() {
+        let browser = AppRule {
+            name: "github".to_string(),
+            class: Some("firefox".to_string()),
+            title: Some("GitHub".to_string()),
+            ..AppRule::default()
+        };
+        let profiles = AppProfiles::new(&[terminal_rule(), browser]).unwrap();
+
+        assert_eq!(
+            profiles.find(&window("foot", "nvim")).unwrap().name,
+            "terminal"
+        );
+        assert_eq!(
+            profiles
+                .find(&window("firefox", "Pull requests · GitHub"))
+                .unwrap()
+                .name,
+            "github"
+        );
+        assert!(profiles.find(&window("firefox", "Docs")).is_none());
+        assert!(profiles.find(&window("kitty-like", "")).is_none());
+    }
+
+    #[test]
+    fn test_apply_overrides_job_options() {
+        let profiles = AppProfiles::new(&[terminal_rule()]).unwrap();
+        let mut options = JobOptions::default();
+        profiles.apply(&window("kitty", ""), &mut options);
+
+        assert_eq!(options.app.as_deref(), Some("terminal"));
+        assert_eq!(options.paste_shortcut, Some(PasteShortcut::CtrlShiftV));
+        assert_eq!(options.mode, DictationMode::Code);
+        assert!(options.auto_submit);
+        assert!(options.input_method.is_none());
+
+        let mut untouched = JobOptions::default();
+        profiles.apply(&window("firefox", ""), &mut untouched);
+        assert!(untouched.app.is_none());
+        assert_eq!(untouched.mode, DictationMode::Prose);
+    }
+
+    #[test]
+    fn test_job_language_takes_precedence() {
+        let rule = AppRule {
+            language: Some("de".to_string()),
+            ..terminal_rule()
+        };
+        let profiles = AppProfiles::new(&[rule]).unwrap();
+
+        let mut options = JobOptions {
+            language: Some("es".to_string()),
+            ..JobOptions::default()
+        };
+        profiles.apply(&window("kitty", ""), &mut options);
+        assert_eq!(options.language.as_deref(), Some("es"));
+    }
+
+    #[test]
+    fn test_rules_parse_from_toml() {
+        #[derive(serde::Deserialize)]
+        struct Wrapper {
+            apps: Vec<AppRule>,
+        }
+
+        let parsed: Wrapper = toml::from_str(
+            r#"
+            [[apps]]
+            name = "terminal"
+            class = "kitty"
+            paste_shortcut = "ctrl+shift+v"
+
+            [[apps.postprocess]]
+            type = "trailing_punctuation"
+            policy = "remove"
+            "#,
+        )
+        .unwrap();
+        let rule = &parsed.apps[0];
+        assert_eq!(rule.paste_shortcut, Some(PasteShortcut::CtrlShiftV));
+        assert_eq!(rule.postprocess.as_ref().map(Vec::len), Some(1));
+        assert!(AppProfiles::new(&parsed.apps).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_rules_are_rejected() {
+        let no_pattern = AppRule {
+            name: "empty".to_string(),
+            ..AppRule::default()
+        };
+        assert!(AppProfiles::new(&[no_pattern]).is_err());
+
+        let bad_regex = AppRule {
+            class: Some("(".to_string()),
+            ..terminal_rule()
+        };
+        assert!(AppProfiles::new(&[bad_regex]).is_err());
+
+        let bad_method = AppRule {
+            input_method: Some("telepathy".to_string()),
+            ..terminal_rule()
+        };
+        assert!(AppProfiles::new(&[bad_method]).is_err());
+    }
+
+    #[test]
+    fn test_rule_languages_follow_the_provider() {
+        let german = AppRule {
+            language: Some("de".to_string()),
+            ..terminal_rule()
+        };
+        let profiles = AppProfiles::new(&[german]).unwrap();
+        let english = ProviderCapabilities::local().with_languages(&["en"]);
+        let err = profiles
+            .check_languages("parakeet-v2", &english)
+            .unwrap_err();
+        assert!(err.to_string().contains("'terminal'"), "{}", err);
+        assert!(profiles
+            .check_languages("parakeet-v3", &ProviderCapabilities::local())
+            .is_ok());
+
+        let auto = AppRule {
+            language: Some("auto".to_string()),
+            ..terminal_rule()
+        };
+        let profiles = AppProfiles::new(&[auto]).unwrap();
+        assert!(profiles.check_languages("parakeet-v2", &english).is_ok());
+    }
+}
@@ -26,6 +26,17 @@ const BAND_RANGES: [(f32, f32); NUM_BANDS] = [
     (6000.0, 8000.0), // Ultra-high / brilliance
 ];
 
+/// Energy and speech duration of a recording.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SpeechStats {
+    /// Total audio duration in seconds.
+    pub duration_secs: f32,
+    /// Seconds of audio above the speech threshold.
+    pub speech_secs: f32,
+    /// RMS of the loudest frame.
+    pub peak_rms: f32,
+}
+
 /// Real-time audio analyzer that performs FFT and extracts frequency bands.
 pub struct AudioAnalyzer {
     /// Ring buffer for recent audio samples.
@@ -48,6 +59,14 @@ pub struct AudioAnalyzer {
     window: Vec<f32>,
     /// Overall audio level (RMS).
     audio_level: f32,
+    /// Samples processed since the last reset.
+    total_samples: usize,
+    /// RMS above which a 20ms frame counts as speech.
+    speech_threshold: f32,
+    /// Samples in frames above the speech threshold.
+    speech_samples: usize,
+    /// RMS of the loudest frame since the last reset.
+    peak_rms: f32,
 }
 
 impl AudioAnalyzer {
@@ -55,7 +74,8 @@ impl AudioAnalyzer {
     ///
     /// # Arguments
     /// * `sample_rate` - Audio sample rate in Hz (typically 16000 for this app).
-    pub fn new(sample_rate: u32) -> Self {
+    /// * `speech_threshold` - RMS above which a frame counts as speech.
+    pub fn new(sample_rate: u32, speech_threshold: f32) -> Self {
         let window: Vec<f32> = (0..FFT_SIZE)
             .map(|i| {
                 let t = i as f32 / (FFT_SIZE - 1) as f32;
@@ -74,6 +94,10 @@ impl AudioAnalyzer {
             sample_rate: sample_rate as f32,
             window,
             audio_level: 0.0,
+            total_samples: 0,
+            speech_threshold,
+            speech_samples: 0,
+            peak_rms: 0.0,
         }
     }
 
@@ -89,6 +113,17 @@ impl AudioAnalyzer {
             self.audio_level = self.audio_level * 0.7 + level * 0.3;
         }
 
+        // Track speech duration in 20ms frames
+        let frame_len = (self.sample_rate as usize / 50).max(1);
+        for frame in samples.chunks(frame_len) {
+            let rms = (frame.iter().map(|s| s * s).sum::<f32>() / frame.len() as f32).sqrt();
+            self.peak_rms = self.peak_rms.max(rms);
+            if rms > self.speech_threshold {
+                self.speech_samples += frame.len();
+            }
+        }
+        self.total_samples += samples.len();
+
         // Add samples to ring buffer
         for &sample in samples {
             self.sample_buffer[self.write_pos] = sample;
@@ -184,6 +219,15 @@ impl AudioAnalyzer {
         self.audio_level
     }
 
+    /// Get energy and speech duration since the last reset.
+    pub fn get_speech_stats(&self) -> SpeechStats {
+        SpeechStats {
+            duration_secs: self.total_samples as f32 / self.sample_rate,
+            speech_secs: self.speech_samples as f32 / self.sample_rate,
+            peak_rms: self.peak_rms,
+        }
+    }
+
     /// Reset all levels to zero.
     pub fn reset(&mut self) {
         self.sample_buffer.fill(0.0);
@@ -191,6 +235,9 @@ impl AudioAnalyzer {
         self.bands = [0.0; NUM_BANDS];
         self.smoothed_bands = [0.0; NUM_BANDS];
         self.audio_level = 0.0;
+        self.total_samples = 0;
+        self.speech_samples = 0;
+        self.peak_rms = 0.0;
     }
 }
 
@@ -202,9 +249,12 @@ pub struct AudioAnalyzerHandle {
 
 impl AudioAnalyzerHandle {
     /// Create a new analyzer handle.
-    pub fn new(sample_rate: u32) -> Self {
+    pub fn new(sample_rate: u32, speech_threshold: f32) -> Self {
         Self {
-            inner: Arc::new(Mutex::new(AudioAnalyzer::new(sample_rate))),
+            inner: Arc::new(Mutex::new(AudioAnalyzer::new(
+                sample_rate,
+                speech_threshold,
+            ))),
         }
     }
 
@@ -231,6 +281,14 @@ impl AudioAnalyzerHandle {
             .unwrap_or(0.0)
     }
 
+    /// Get energy and speech duration of the current recording.
+    pub fn get_speech_stats(&self) -> SpeechStats {
+        self.inner
+            .lock()
+            .map(|a| a.get_speech_stats())
+            .unwrap_or_default()
+    }
+
     /// Reset analyzer state.
     pub fn reset(&self) {
         if let Ok(mut analyzer) = self.inner.lock() {
@@ -245,14 +303,14 @@ mod tests {
 
     #[test]
     fn test_analyzer_creation() {
-        let analyzer = AudioAnalyzer::new(16000);
+        let analyzer = AudioAnalyzer::new(16000, 0.01);
         assert_eq!(analyzer.get_bands(), [0.0; NUM_BANDS]);
         assert_eq!(analyzer.get_audio_level(), 0.0);
     }
 
     #[test]
     fn test_process_silence() {
-        let mut analyzer = AudioAnalyzer::new(16000);
+        let mut analyzer = AudioAnalyzer::new(16000, 0.01);
         let silence = vec![0.0; 512];
         analyzer.process_samples(&silence);
 
@@ -264,7 +322,7 @@ mod tests {
 
     #[test]
     fn test_process_tone() {
-        let mut analyzer = AudioAnalyzer::new(16000);
+        let mut analyzer = AudioAnalyzer::new(16000, 0.01);
 
         // Generate a 500Hz sine wave (should appear in band 2: 300-600Hz)
         let samples: Vec<f32> = (0..512)
@@ -290,7 +348,7 @@ mod tests {
 
     #[test]
     fn test_analyzer_handle_thread_safety() {
-        let handle = AudioAnalyzerHandle::new(16000);
+        let handle = AudioAnalyzerHandle::new(16000, 0.01);
         let handle_clone = handle.clone();
 
         // Simulate audio callback thread
@@ -308,7 +366,7 @@ mod tests {
 
     #[test]
     fn test_reset() {
-        let mut analyzer = AudioAnalyzer::new(16000);
+        let mut analyzer = AudioAnalyzer::new(16000, 0.01);
 
         // Process some noise
         let samples: Vec<f32> = (0..512).map(|i| (i as f32 * 0.01).sin()).collect();
@@ -319,5 +377,23 @@ mod tests {
 
         assert_eq!(analyzer.get_bands(), [0.0; NUM_BANDS]);
         assert_eq!(analyzer.get_audio_level(), 0.0);
+        assert_eq!(analyzer.get_speech_stats(), SpeechStats::default());
+    }
+
+    #[test]
+    fn test_speech_stats() {
+        let mut analyzer = AudioAnalyzer::new(16000, 0.01);
+
+        // Half a second of silence, then a quarter second of a loud tone
+        analyzer.process_samples(&vec![0.0; 8000]);
+        let tone: Vec<f32> = (0..4000)
+            .map(|i| (2.0 * std::f32::consts::PI * 200.0 * i as f32 / 16000.0).sin() * 0.3)
+            .collect();
+        analyzer.process_samples(&tone);
+
+        let stats = analyzer.get_speech_stats();
+        assert!((stats.duration_secs - 0.75).abs() < 1e-3);
+        assert!((stats.speech_secs - 0.25).abs() < 1e-3);
+        assert!(stats.peak_rms > 0.2);
     }
 }
@@ -1,6 +1,6 @@
 #![allow(clippy::arc_with_non_send_sync)]
 
-use crate::audio::audio_analyzer::{AudioAnalyzerHandle, NUM_BANDS};
+use crate::audio::audio_analyzer::{AudioAnalyzerHandle, SpeechStats, NUM_BANDS};
 use anyhow::{Context, Result};
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use hound::{WavSpec, WavWriter};
@@ -29,8 +29,9 @@ pub struct AudioStreamManager {
 }
 
 impl AudioStreamManager {
-    /// Create a new audio stream manager
-    pub fn new() -> Result<Self> {
+    /// Create a new audio stream manager. Frames louder than
+    /// `speech_threshold` RMS count as speech in [`Self::get_speech_stats`].
+    pub fn new(speech_threshold: f32) -> Result<Self> {
         let host = cpal::default_host();
         let device = host
             .default_input_device()
@@ -53,7 +54,7 @@ impl AudioStreamManager {
             active_stream: Arc::new(Mutex::new(None)),
             state: Arc::new(Mutex::new(RecordingState::Idle)),
             audio_level: Arc::new(Mutex::new(0.0)),
-            analyzer: AudioAnalyzerHandle::new(sample_rate),
+            analyzer: AudioAnalyzerHandle::new(sample_rate, speech_threshold),
         })
     }
 
@@ -72,6 +73,11 @@ impl AudioStreamManager {
         self.analyzer.get_bands()
     }
 
+    /// Energy and speech duration of the current or last recording.
+    pub fn get_speech_stats(&self) -> SpeechStats {
+        self.analyzer.get_speech_stats()
+    }
+
     /// Get handle to the audio analyzer for sharing between threads.
     pub fn get_analyzer_handle(&self) -> AudioAnalyzerHandle {
         self.analyzer.clone()
@@ -232,6 +238,6 @@ mod tests {
         }
 
         // This test may fail in CI without audio devices
-        let _manager = AudioStreamManager::new();
+        let _manager = AudioStreamManager::new(0.01);
     }
 }
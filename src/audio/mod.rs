@@ -2,9 +2,19 @@ pub mod audio_analyzer;
 pub mod audio_stream_manager;
 pub mod recording_machine;
 
-pub use audio_analyzer::{AudioAnalyzerHandle, NUM_BANDS};
+pub use audio_analyzer::{AudioAnalyzerHandle, SpeechStats, NUM_BANDS};
 pub use audio_stream_manager::AudioStreamManager;
 pub use recording_machine::{
-    BehaviorOptions, CompletedJob, JobOptions, RecordingMachine, RecordingPhase, RecordingStatus,
-    RecordingStatusHandle, ToggleResult,
+    BehaviorOptions, CompletedJob, JobOptions, JobRetrier, RecordingMachine, RecordingPhase,
+    RecordingStatus, RecordingStatusHandle, ToggleResult,
 };
+
+/// Duration of a WAV file in seconds.
+pub fn wav_duration_secs(path: &std::path::Path) -> anyhow::Result<f64> {
+    use anyhow::Context;
+
+    let reader =
+        hound::WavReader::open(path).with_context(|| format!("Failed to open {:?}", path))?;
+    let spec = reader.spec();
+    Ok(reader.duration() as f64 / spec.sample_rate as f64)
+}
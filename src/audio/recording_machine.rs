@@ -1,18 +1,29 @@
-use anyhow::Result;
+use anyhow::{anyhow, Context, Result};
 use serde::{Deserialize, Serialize};
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 use std::process::Command;
 use std::sync::Arc;
+use std::time::Duration;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::sync::Mutex;
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
+use crate::apps::{self, AppProfiles, RecordedWindow};
 use crate::audio::audio_analyzer::NUM_BANDS;
-use crate::audio::AudioStreamManager;
-use crate::db::{self, VoiceToTextData, Workflow, WorkflowData, WorkflowType};
-use crate::text_io::TextIoService;
-use crate::transcription::TranscriptionService;
+use crate::audio::{AudioStreamManager, SpeechStats};
+use crate::config::{ClipboardTarget, Config, DictationMode, HallucinationAction, PasteShortcut};
+use crate::events::{DaemonEvent, EventBus};
+use crate::history;
+use crate::jobs::{self, QueuedJob, RetryOutcome};
+use crate::sinks::{SinkJob, Sinks};
+use crate::text_io::{
+    ClipboardSnapshot, CopyOptions, InjectionMethod, KeyAction, TextIoService, Utterance,
+    VoiceCommand,
+};
+use crate::transcription::{
+    provider_capabilities, HallucinationFilter, TranscribeOptions, Transcript, TranscriptionService,
+};
 use crate::ui::Indicator;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -52,6 +63,9 @@ pub struct CompletedJob {
     pub text: String,
     /// When the job completed
     pub created_at: String,
+    /// Whether the text was typed/pasted into the focused app
+    #[serde(default)]
+    pub injected: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -64,6 +78,8 @@ pub struct RecordingStatus {
     pub audio_level: f32,
     /// Frequency band levels for visualizer (0.0 to 1.0 each).
     pub frequency_bands: [f32; NUM_BANDS],
+    /// Provider that transcribed the last job
+    pub provider: Option<String>,
 }
 
 impl Default for RecordingStatus {
@@ -76,13 +92,16 @@ impl Default for RecordingStatus {
             last_error: None,
             audio_level: 0.0,
             frequency_bands: [0.0; NUM_BANDS],
+            provider: None,
         }
     }
 }
 
+/// Shared recording status. Changes are published on the event bus.
 #[derive(Clone, Default)]
 pub struct RecordingStatusHandle {
     inner: Arc<Mutex<RecordingStatus>>,
+    events: EventBus,
 }
 
 impl RecordingStatusHandle {
@@ -90,41 +109,88 @@ impl RecordingStatusHandle {
         self.inner.lock().await.clone()
     }
 
+    pub fn events(&self) -> &EventBus {
+        &self.events
+    }
+
+    fn publish_phase(&self, previous: RecordingPhase, phase: RecordingPhase) {
+        if previous != phase {
+            self.events.publish(DaemonEvent::Phase { phase });
+        }
+    }
+
     pub async fn set_phase(&self, phase: RecordingPhase, last_error: Option<String>) {
         let mut status = self.inner.lock().await;
+        let previous = status.phase;
         status.phase = phase;
         status.last_error = last_error;
+        self.publish_phase(previous, phase);
     }
 
     pub async fn start_job(&self, job_id: String, options: JobOptions) {
         let mut status = self.inner.lock().await;
+        let previous = status.phase;
         status.phase = RecordingPhase::Recording;
-        status.current_job_id = Some(job_id);
-        status.current_job_options = Some(options);
+        status.current_job_id = Some(job_id.clone());
+        status.current_job_options = Some(options.clone());
         status.last_error = None;
+        self.publish_phase(previous, RecordingPhase::Recording);
+        self.events
+            .publish(DaemonEvent::JobStarted { job_id, options });
     }
 
     pub async fn complete_job(&self, completed_job: CompletedJob) {
         let mut status = self.inner.lock().await;
+        let previous = status.phase;
         status.phase = RecordingPhase::Idle;
         status.current_job_id = None;
         status.current_job_options = None;
-        status.last_completed_job = Some(completed_job);
+        status.last_completed_job = Some(completed_job.clone());
         status.last_error = None;
+        self.events
+            .publish(DaemonEvent::JobCompleted(completed_job));
+        self.publish_phase(previous, RecordingPhase::Idle);
+    }
+
+    /// Record the provider that transcribed a job, publishing a change.
+    pub async fn set_provider(&self, provider: &str) {
+        let mut status = self.inner.lock().await;
+        if status.provider.as_deref() == Some(provider) {
+            return;
+        }
+        let previous = status.provider.replace(provider.to_string());
+        self.events.publish(DaemonEvent::ProviderChanged {
+            provider: provider.to_string(),
+            previous,
+        });
+    }
+
+    /// Mark the last completed job as no longer present in the focused app,
+    /// so "scratch that" doesn't delete it twice.
+    pub async fn retract_last_injection(&self) {
+        if let Some(job) = self.inner.lock().await.last_completed_job.as_mut() {
+            job.injected = false;
+        }
     }
 
     pub async fn fail_job(&self, error: String) {
         let mut status = self.inner.lock().await;
+        let previous = status.phase;
         status.phase = RecordingPhase::Error;
-        status.current_job_id = None;
+        let job_id = status.current_job_id.take();
         status.current_job_options = None;
-        status.last_error = Some(error);
+        status.last_error = Some(error.clone());
+        self.events
+            .publish(DaemonEvent::JobFailed { job_id, error });
+        self.publish_phase(previous, RecordingPhase::Error);
     }
 
     pub async fn set_processing(&self) {
         let mut status = self.inner.lock().await;
+        let previous = status.phase;
         status.phase = RecordingPhase::Processing;
         // Keep the current_job_id during processing
+        self.publish_phase(previous, RecordingPhase::Processing);
     }
 
     pub async fn get_current_job_id(&self) -> Option<String> {
@@ -132,7 +198,7 @@ impl RecordingStatusHandle {
     }
 
     pub async fn get_current_job_options(&self) -> Option<JobOptions> {
-        self.inner.lock().await.current_job_options
+        self.inner.lock().await.current_job_options.clone()
     }
 
     pub async fn set_audio_level(&self, level: f32) {
@@ -149,6 +215,7 @@ impl RecordingStatusHandle {
         let mut status = self.inner.lock().await;
         status.audio_level = level;
         status.frequency_bands = bands;
+        self.events.publish_audio_level(level, bands);
     }
 }
 
@@ -163,12 +230,72 @@ pub struct ToggleResult {
 
 /// Per-job options that can override default behavior.
 /// These are set when starting a recording via the API.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JobOptions {
     /// Whether to copy the transcription to clipboard (default: true)
     pub copy_to_clipboard: bool,
     /// Whether to auto-paste/inject text into the focused app (default: from config)
     pub auto_paste: bool,
+    /// Language override for this job (default: from config)
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Prose or code dictation for this job (default: prose)
+    #[serde(default)]
+    pub mode: DictationMode,
+    /// `[[apps]]` rule matched when the recording started
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app: Option<String>,
+    /// Injection method override for this job (default: the current method)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_method: Option<String>,
+    /// Paste shortcut for clipboard injection (default: type the clipboard)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub paste_shortcut: Option<PasteShortcut>,
+    /// Press Enter after injecting the text
+    #[serde(default)]
+    pub auto_submit: bool,
+    /// Hyprland address of the window focused when recording started
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window_address: Option<String>,
+    /// `[[sinks]]` that receive the transcript (default: the enabled sinks)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sinks: Option<Vec<String>>,
+    /// Selections the transcript is copied to (default: from config)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clipboard_target: Option<ClipboardTarget>,
+    /// Hide the copy from clipboard managers (default: from config)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clipboard_sensitive: Option<bool>,
+}
+
+impl JobOptions {
+    /// Options for a job that doesn't set them, from `[behavior]`.
+    pub fn from_behavior(auto_paste: bool, restore_clipboard: bool) -> Self {
+        Self {
+            auto_paste,
+            // With restore_clipboard the transcript only passes through the clipboard
+            copy_to_clipboard: !restore_clipboard,
+            ..Self::default()
+        }
+    }
+
+    /// The transcription pipeline options for this job.
+    pub fn transcribe_options(&self) -> TranscribeOptions<'_> {
+        TranscribeOptions {
+            language: self.language.as_deref(),
+            mode: self.mode,
+            app: self.app.as_deref(),
+            ..TranscribeOptions::default()
+        }
+    }
+
+    /// How this job copies text, falling back to `defaults` from config.
+    pub fn copy_options(&self, defaults: CopyOptions) -> CopyOptions {
+        CopyOptions {
+            target: self.clipboard_target.unwrap_or(defaults.target),
+            sensitive: self.clipboard_sensitive.unwrap_or(defaults.sensitive),
+        }
+    }
 }
 
 impl Default for JobOptions {
@@ -176,14 +303,64 @@ impl Default for JobOptions {
         Self {
             copy_to_clipboard: true,
             auto_paste: true,
+            language: None,
+            mode: DictationMode::Prose,
+            app: None,
+            input_method: None,
+            paste_shortcut: None,
+            auto_submit: false,
+            window_address: None,
+            sinks: None,
+            clipboard_target: None,
+            clipboard_sensitive: None,
         }
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct BehaviorOptions {
     pub auto_paste: bool,
     pub delete_audio_files: bool,
+    pub voice_commands: bool,
+    pub hallucination: HallucinationFilter,
+    /// `[[apps]]` rules applied when a recording starts
+    pub apps: AppProfiles,
+    /// Restore the clipboard this long after pasting, unless the job keeps
+    /// the transcript on the clipboard
+    pub restore_clipboard: Option<Duration>,
+    /// Outputs that receive completed transcripts
+    pub sinks: Sinks,
+    /// How transcripts are copied unless the job says otherwise
+    pub clipboard: CopyOptions,
+}
+
+impl BehaviorOptions {
+    /// Options from the config file, delivering to `sinks`.
+    pub fn from_config(config: &Config, sinks: Sinks) -> Result<Self> {
+        let apps = AppProfiles::new(&config.apps)?;
+        if let Some(provider) = config.whisper.provider.as_deref() {
+            if let Some(capabilities) = provider_capabilities(provider) {
+                apps.check_languages(provider, &capabilities)?;
+            }
+        }
+
+        Ok(Self {
+            auto_paste: config.behavior.auto_paste,
+            delete_audio_files: config.behavior.delete_audio_files,
+            voice_commands: config.behavior.voice_commands,
+            hallucination: HallucinationFilter::new(&config.hallucination),
+            apps,
+            restore_clipboard: config
+                .behavior
+                .restore_clipboard
+                .then(|| Duration::from_millis(config.behavior.restore_clipboard_delay_ms)),
+            sinks,
+            clipboard: CopyOptions {
+                target: config.behavior.clipboard_target,
+                sensitive: config.behavior.clipboard_sensitive,
+            },
+        })
+    }
 }
 
 /// Context for running a transcription processing task.
@@ -195,6 +372,126 @@ struct ProcessingContext {
     temp_path: PathBuf,
     job_id: Option<String>,
     delete_audio_files: bool,
+    voice_commands: bool,
+    status: RecordingStatusHandle,
+    /// Speech measured while recording, for the hallucination filter
+    speech: Option<SpeechStats>,
+    hallucination: HallucinationFilter,
+    restore_clipboard: Option<Duration>,
+    sinks: Sinks,
+    /// How this job copies text
+    clipboard: CopyOptions,
+    /// The offline queue entry this job retries
+    pending_job: Option<i64>,
+}
+
+impl ProcessingContext {
+    /// Pipeline options for this job. Voice commands only apply to text
+    /// that is typed.
+    fn transcribe_options(&self) -> TranscribeOptions<'_> {
+        TranscribeOptions {
+            hallucination: Some(&self.hallucination),
+            speech: self.speech.as_ref(),
+            voice_commands: self.voice_commands && self.job_options.auto_paste,
+            ..self.job_options.transcribe_options()
+        }
+    }
+
+    /// The job's injection method, or the service's current one.
+    async fn injection_method(&self) -> InjectionMethod {
+        match self
+            .job_options
+            .input_method
+            .as_deref()
+            .and_then(InjectionMethod::parse)
+        {
+            Some(method) => method,
+            None => self.text_io.injection_method().await,
+        }
+    }
+}
+
+/// Retries jobs from the offline queue and delivers them the way live
+/// recordings are delivered, except that the text is left on the clipboard
+/// instead of being pasted.
+#[derive(Clone)]
+pub struct JobRetrier {
+    transcription: Arc<TranscriptionService>,
+    indicator: Indicator,
+    text_io: TextIoService,
+    behavior: BehaviorOptions,
+    status: RecordingStatusHandle,
+}
+
+impl JobRetrier {
+    pub fn new(
+        transcription: Arc<TranscriptionService>,
+        indicator: Indicator,
+        text_io: TextIoService,
+        behavior: BehaviorOptions,
+        status: RecordingStatusHandle,
+    ) -> Self {
+        Self {
+            transcription,
+            indicator,
+            text_io,
+            behavior,
+            status,
+        }
+    }
+
+    /// Transcribe a pending job again and deliver it. On success, or when
+    /// the recording holds no speech, the job is removed from the queue.
+    pub async fn retry(&self, job: &QueuedJob) -> Result<RetryOutcome> {
+        if !jobs::claim(job.id).await? {
+            return Ok(RetryOutcome::Busy);
+        }
+
+        if jobs::drop_missing(job).await? {
+            return Ok(RetryOutcome::Missing);
+        }
+
+        info!("Retrying pending job #{} ({})", job.id, job.job_id);
+        let ctx = ProcessingContext {
+            transcription: Arc::clone(&self.transcription),
+            indicator: self.indicator.clone(),
+            text_io: self.text_io.clone(),
+            temp_path: PathBuf::from(&job.audio_path),
+            job_id: Some(job.job_id.clone()),
+            delete_audio_files: self.behavior.delete_audio_files,
+            voice_commands: self.behavior.voice_commands,
+            status: self.status.clone(),
+            speech: None,
+            hallucination: self.behavior.hallucination.clone(),
+            restore_clipboard: None,
+            sinks: self.behavior.sinks.clone(),
+            clipboard: job.options.copy_options(self.behavior.clipboard),
+            job_options: job.options.clone(),
+            pending_job: Some(job.id),
+        };
+
+        let transcript = match ctx
+            .transcription
+            .transcribe_detailed(&ctx.temp_path, ctx.transcribe_options())
+            .await
+        {
+            Ok(transcript) => transcript,
+            Err(e) => {
+                let offline = jobs::is_offline_error(&e);
+                return jobs::fail(job.id, format!("{:#}", e), offline).await;
+            }
+        };
+
+        let completed = RecordingMachine::finish(&ctx, transcript).await;
+        jobs::finish(job.id, ctx.delete_audio_files).await?;
+        Ok(match completed {
+            Some(completed) => RetryOutcome::Completed {
+                history_id: completed.history_id,
+                text: completed.text,
+            },
+            None => RetryOutcome::NoSpeech,
+        })
+    }
 }
 
 pub struct RecordingMachine {
@@ -225,6 +522,17 @@ impl RecordingMachine {
         }
     }
 
+    /// A retrier delivering queued jobs with this machine's settings.
+    pub fn retrier(&self) -> JobRetrier {
+        JobRetrier::new(
+            Arc::clone(&self.transcription),
+            self.indicator.clone(),
+            self.text_io.clone(),
+            self.behavior.clone(),
+            self.status.clone(),
+        )
+    }
+
     /// Toggle recording state and return the result with job information.
     ///
     /// Returns a `ToggleResult` containing:
@@ -254,10 +562,11 @@ impl RecordingMachine {
                 let job_id = Uuid::new_v4().to_string();
 
                 // Use provided options or create defaults from config
-                let job_options = options.unwrap_or(JobOptions {
-                    copy_to_clipboard: true,
-                    auto_paste: self.behavior.auto_paste,
-                });
+                let mut job_options = options.unwrap_or(self.default_job_options());
+                if let Some(window) = apps::active_window().await {
+                    self.behavior.apps.apply(&window, &mut job_options);
+                    job_options.window_address = Some(window.address);
+                }
 
                 info!(
                     "RecordingMachine: starting recording with job_id={}, options={:?}",
@@ -283,10 +592,9 @@ impl RecordingMachine {
             Transition::StopRecording => {
                 let job_id = current.current_job_id.clone();
                 // Job options should always be set when recording started, fall back to defaults if not
-                let job_options = current.current_job_options.unwrap_or(JobOptions {
-                    copy_to_clipboard: true,
-                    auto_paste: self.behavior.auto_paste,
-                });
+                let job_options = current
+                    .current_job_options
+                    .unwrap_or(self.default_job_options());
                 info!(
                     "RecordingMachine: stopping recording and processing job_id={:?}, options={:?}",
                     job_id, job_options
@@ -322,6 +630,14 @@ impl RecordingMachine {
         }
     }
 
+    /// Options for jobs toggled without a request body.
+    fn default_job_options(&self) -> JobOptions {
+        JobOptions::from_behavior(
+            self.behavior.auto_paste,
+            self.behavior.restore_clipboard.is_some(),
+        )
+    }
+
     async fn start_recording(&self) -> Result<()> {
         if let Err(e) = self.indicator.show_recording().await {
             warn!("Failed to show recording indicator: {}", e);
@@ -366,10 +682,12 @@ impl RecordingMachine {
     ) -> Result<()> {
         let temp_path = Self::temp_audio_path();
 
-        {
+        let speech = {
             let recorder = self.audio.lock().await;
             recorder.stop_recording(temp_path.clone()).await?;
-        }
+            recorder.get_speech_stats()
+        };
+        debug!("Speech stats: {:?}", speech);
 
         let indicator_for_task = self.indicator.clone();
         if let Err(e) = indicator_for_task.show_processing().await {
@@ -401,10 +719,18 @@ impl RecordingMachine {
             transcription: Arc::clone(&self.transcription),
             indicator: indicator_for_task,
             text_io: self.text_io.clone(),
-            job_options,
             temp_path,
             job_id,
             delete_audio_files: self.behavior.delete_audio_files,
+            voice_commands: self.behavior.voice_commands,
+            status: status.clone(),
+            speech: Some(speech),
+            hallucination: self.behavior.hallucination.clone(),
+            restore_clipboard: self.behavior.restore_clipboard,
+            sinks: self.behavior.sinks.clone(),
+            clipboard: job_options.copy_options(self.behavior.clipboard),
+            job_options,
+            pending_job: None,
         };
 
         tokio::spawn(async move {
@@ -435,90 +761,363 @@ impl RecordingMachine {
     /// Run the transcription processing task.
     /// Returns `Ok(Some(CompletedJob))` on success, `Ok(None)` if no speech detected.
     async fn run_processing_task(ctx: ProcessingContext) -> Result<Option<CompletedJob>> {
-        let completed_job = match ctx.transcription.transcribe(&ctx.temp_path).await {
-            Ok(text) => {
-                if text.trim().is_empty() {
-                    warn!("No speech detected in recording");
-                    let _ = ctx.indicator.show_error("No speech detected").await;
-                    None
-                } else {
-                    info!("Transcription complete: {} chars", text.len());
-
-                    // Use job_options to control clipboard/paste behavior
-                    if ctx.job_options.copy_to_clipboard {
-                        if let Err(e) = ctx.text_io.copy_to_clipboard(&text).await {
-                            error!("Failed to copy to clipboard: {}", e);
-                        }
-                    }
+        Self::check_provider_limits(&ctx).await?;
 
-                    if ctx.job_options.auto_paste {
-                        if let Err(e) = ctx.text_io.inject_text(&text).await {
-                            error!("Failed to inject text: {}", e);
-                            // Only try paste fallback if we copied to clipboard
-                            if ctx.job_options.copy_to_clipboard {
-                                let _ = ctx.text_io.paste_from_clipboard().await;
-                            }
-                        }
-                    }
+        let completed_job = match ctx
+            .transcription
+            .transcribe_detailed(&ctx.temp_path, ctx.transcribe_options())
+            .await
+        {
+            Ok(transcript) => Self::finish(&ctx, transcript).await,
+            Err(e) => {
+                if ctx.transcription.capabilities().is_local() || !jobs::is_offline_error(&e) {
+                    return Err(e);
+                }
 
-                    if let Err(e) = ctx.indicator.show_complete(&text).await {
-                        warn!("Failed to show completion indicator: {}", e);
+                // Keep the recording so it can be transcribed once we're back online
+                let job_id = ctx.job_id.unwrap_or_else(|| "unknown".to_string());
+                let temp_path = ctx.temp_path.clone();
+                let options = ctx.job_options.clone();
+                let error = format!("{:#}", e);
+                let queued = tokio::task::spawn_blocking(move || {
+                    jobs::enqueue(&job_id, &temp_path, &options, &error)
+                })
+                .await;
+
+                return match queued {
+                    Ok(Ok(pending_id)) => {
+                        warn!(
+                            "Provider unreachable, queued as pending job #{}",
+                            pending_id
+                        );
+                        Err(e.context(format!(
+                            "Offline: transcription queued for retry (pending job #{})",
+                            pending_id
+                        )))
+                    }
+                    Ok(Err(queue_err)) => {
+                        error!("Failed to queue pending job: {:?}", queue_err);
+                        Err(e)
                     }
+                    Err(join_err) => {
+                        error!("Pending job task panicked: {:?}", join_err);
+                        Err(e)
+                    }
+                };
+            }
+        };
 
-                    // Save transcription to database and get the history ID
-                    let text_for_db = text.clone();
-                    let temp_path_for_db = ctx.temp_path.clone();
-                    let job_id_for_db = ctx.job_id.clone();
+        if ctx.delete_audio_files {
+            if let Err(e) = tokio::fs::remove_file(&ctx.temp_path).await {
+                warn!(
+                    "Failed to delete temp audio file {:?}: {}",
+                    ctx.temp_path, e
+                );
+            } else {
+                debug!("Deleted temp audio file {:?}", ctx.temp_path);
+            }
+        }
 
-                    let db_result = tokio::task::spawn_blocking(move || {
-                        save_to_database(&text_for_db, &temp_path_for_db)
-                    })
-                    .await;
+        Ok(completed_job)
+    }
 
-                    match db_result {
+    /// Deliver a transcript: filter hallucinations, run voice commands, copy
+    /// or inject the text, save it to history and send it to sinks. Returns
+    /// `None` if there was no text to deliver.
+    async fn finish(ctx: &ProcessingContext, transcript: Transcript) -> Option<CompletedJob> {
+        let Transcript {
+            text: transcript,
+            raw_text,
+            provider,
+            hallucination,
+            utterance,
+        } = transcript;
+        ctx.status.set_provider(provider).await;
+        let utterance = match hallucination {
+            Some(reason) => {
+                warn!(
+                    "Discarding likely hallucination ({}): {:?}",
+                    reason, transcript
+                );
+                if ctx.hallucination.action() == HallucinationAction::Flag {
+                    let temp_path = ctx.temp_path.clone();
+                    let raw_text = raw_text.clone();
+                    match tokio::task::spawn_blocking(move || {
+                        history::save(&transcript, Some(&raw_text), &temp_path)
+                    })
+                    .await
+                    {
                         Ok(Ok(history_id)) => {
-                            let completed = CompletedJob {
-                                job_id: ctx.job_id.unwrap_or_else(|| "unknown".to_string()),
-                                history_id,
-                                text,
-                                created_at: chrono::Utc::now().to_rfc3339(),
-                            };
-                            Some(completed)
+                            info!("Flagged transcript kept as history #{}", history_id)
                         }
+                        Ok(Err(e)) => error!("Failed to save flagged transcript: {:?}", e),
+                        Err(e) => error!("Database task panicked: {:?}", e),
+                    }
+                }
+                Utterance::default()
+            }
+            None => utterance,
+        };
+        let text = utterance.text.clone();
+
+        if utterance.is_empty() {
+            warn!("No speech detected in recording");
+            if ctx.pending_job.is_none() {
+                let _ = ctx.indicator.show_error("No speech detected").await;
+            }
+            return None;
+        }
+        if text.trim().is_empty() {
+            info!("Utterance contained only voice commands");
+            Self::deliver(ctx, &utterance).await;
+            return None;
+        }
+
+        info!("Transcription complete: {} chars", text.len());
+        let injected = Self::deliver(ctx, &utterance).await;
+
+        if ctx.pending_job.is_some() {
+            let _ = ctx
+                .indicator
+                .notify("Queued transcription ready", &text)
+                .await;
+        } else if let Err(e) = ctx.indicator.show_complete(&text).await {
+            warn!("Failed to show completion indicator: {}", e);
+        }
+
+        // Save transcription to database and get the history ID
+        let text_for_db = text.clone();
+        let raw_text_for_db = raw_text.clone();
+        let temp_path_for_db = ctx.temp_path.clone();
+
+        let db_result = tokio::task::spawn_blocking(move || {
+            history::save(&text_for_db, Some(&raw_text_for_db), &temp_path_for_db)
+        })
+        .await;
+
+        let history_id = match db_result {
+            Ok(Ok(history_id)) => history_id,
+            Ok(Err(e)) => {
+                error!("Failed to save transcription to database: {:?}", e);
+                // Still return a completed job but with id 0
+                0
+            }
+            Err(e) => {
+                error!("Database task panicked: {:?}", e);
+                return None;
+            }
+        };
+        let completed = CompletedJob {
+            job_id: ctx.job_id.clone().unwrap_or_else(|| "unknown".to_string()),
+            history_id,
+            text,
+            created_at: chrono::Utc::now().to_rfc3339(),
+            injected,
+        };
+
+        let sink_job = SinkJob::new(&completed, &raw_text, &ctx.job_options);
+        if ctx.pending_job.is_some() {
+            // Retries may run from the CLI, which exits right after
+            for (name, result) in ctx
+                .sinks
+                .send(ctx.job_options.sinks.as_deref(), &sink_job)
+                .await
+            {
+                if let Err(e) = result {
+                    warn!("Sink {:?} failed: {:#}", name, e);
+                }
+            }
+        } else {
+            ctx.sinks
+                .dispatch(ctx.job_options.sinks.as_deref(), sink_job);
+        }
+        Some(completed)
+    }
+
+    /// Reject a recording the provider won't accept, instead of uploading it
+    /// only to have it refused. The recording is kept.
+    async fn check_provider_limits(ctx: &ProcessingContext) -> Result<()> {
+        let capabilities = ctx.transcription.capabilities();
+        if capabilities.max_duration_secs.is_some() {
+            let duration = crate::audio::wav_duration_secs(&ctx.temp_path)?;
+            capabilities
+                .check_duration(duration)
+                .map_err(|e| anyhow!(e))?;
+        }
+        if capabilities.max_upload_bytes.is_some() {
+            let size = tokio::fs::metadata(&ctx.temp_path)
+                .await
+                .with_context(|| format!("Failed to read {:?}", ctx.temp_path))?
+                .len();
+            capabilities
+                .check_upload_size(size)
+                .map_err(|e| anyhow!(e))?;
+        }
+        Ok(())
+    }
+
+    /// Copy and inject the text and run its voice commands in the window the
+    /// recording started in. Returns whether the text was injected.
+    async fn deliver(ctx: &ProcessingContext, utterance: &Utterance) -> bool {
+        let text = &utterance.text;
+        let has_text = !text.trim().is_empty();
+
+        // A retried job finishes long after it was dictated, so its text is
+        // left on the clipboard instead of typed into whatever has focus now
+        if ctx.pending_job.is_some() {
+            if has_text && (ctx.job_options.copy_to_clipboard || ctx.job_options.auto_paste) {
+                if let Err(e) = ctx
+                    .text_io
+                    .copy_with(text, ctx.clipboard.with_clipboard())
+                    .await
+                {
+                    error!("Failed to copy to clipboard: {}", e);
+                }
+            }
+            return false;
+        }
+
+        let target = if ctx.job_options.auto_paste {
+            apps::focus_recorded_window(ctx.job_options.window_address.as_deref()).await
+        } else {
+            RecordedWindow::Focused
+        };
+        if target == RecordedWindow::Unavailable {
+            warn!("The window the recording started in is gone; leaving the text on the clipboard");
+            if has_text {
+                if let Err(e) = ctx
+                    .text_io
+                    .copy_with(text, ctx.clipboard.with_clipboard())
+                    .await
+                {
+                    error!("Failed to copy to clipboard: {}", e);
+                }
+            }
+            return false;
+        }
+
+        Self::run_voice_commands(ctx, &utterance.leading).await;
+
+        // Use job_options to control clipboard/paste behavior
+        if has_text && ctx.job_options.copy_to_clipboard {
+            if let Err(e) = ctx.text_io.copy_with(text, ctx.clipboard).await {
+                error!("Failed to copy to clipboard: {}", e);
+            }
+        }
+
+        let mut injected = false;
+        if has_text && ctx.job_options.auto_paste {
+            let method = ctx.injection_method().await;
+            let keep_on_clipboard =
+                ctx.job_options.copy_to_clipboard && ctx.clipboard.target.includes_clipboard();
+            let snapshot = match ctx.restore_clipboard {
+                Some(delay) if !keep_on_clipboard => {
+                    match tokio::task::spawn_blocking(ClipboardSnapshot::capture).await {
+                        Ok(Ok(snapshot)) => Some((snapshot, delay)),
                         Ok(Err(e)) => {
-                            error!("Failed to save transcription to database: {:?}", e);
-                            // Still return a completed job but with id 0
-                            Some(CompletedJob {
-                                job_id: job_id_for_db.unwrap_or_else(|| "unknown".to_string()),
-                                history_id: 0,
-                                text,
-                                created_at: chrono::Utc::now().to_rfc3339(),
-                            })
+                            warn!("Failed to save the clipboard: {}", e);
+                            None
                         }
                         Err(e) => {
-                            error!("Database task panicked: {:?}", e);
+                            error!("Clipboard task panicked: {:?}", e);
                             None
                         }
                     }
                 }
+                _ => None,
+            };
+
+            // Clipboard injection pastes whatever is on the clipboard
+            if method == InjectionMethod::Clipboard && !keep_on_clipboard {
+                if let Err(e) = ctx
+                    .text_io
+                    .copy_with(text, ctx.clipboard.clipboard_only())
+                    .await
+                {
+                    error!("Failed to copy to clipboard: {}", e);
+                }
             }
-            Err(e) => {
-                return Err(e);
-            }
-        };
 
-        if ctx.delete_audio_files {
-            if let Err(e) = tokio::fs::remove_file(&ctx.temp_path).await {
-                warn!(
-                    "Failed to delete temp audio file {:?}: {}",
-                    ctx.temp_path, e
-                );
+            if let Err(e) = ctx
+                .text_io
+                .inject_text_with(
+                    text,
+                    method,
+                    ctx.job_options.paste_shortcut,
+                    ctx.clipboard.sensitive,
+                )
+                .await
+            {
+                error!("Failed to inject text: {}", e);
+                // Only try paste fallback if we copied to clipboard
+                if keep_on_clipboard {
+                    injected = ctx.text_io.paste_from_clipboard().await.is_ok();
+                }
             } else {
-                debug!("Deleted temp audio file {:?}", ctx.temp_path);
+                injected = true;
+            }
+
+            if let Some((snapshot, delay)) = snapshot {
+                let pasted = text.clone();
+                tokio::spawn(async move {
+                    tokio::time::sleep(delay).await;
+                    match tokio::task::spawn_blocking(move || {
+                        snapshot.restore_unless_changed(&pasted)
+                    })
+                    .await
+                    {
+                        Ok(Ok(true)) => debug!("Restored the previous clipboard contents"),
+                        Ok(Ok(false)) => {
+                            debug!("Clipboard changed since the paste; not restoring it")
+                        }
+                        Ok(Err(e)) => warn!("Failed to restore the clipboard: {}", e),
+                        Err(e) => error!("Clipboard task panicked: {:?}", e),
+                    }
+                });
             }
         }
 
-        Ok(completed_job)
+        if injected && ctx.job_options.auto_submit {
+            let method = ctx.injection_method().await;
+            if let Err(e) = ctx.text_io.send_key_with(method, KeyAction::Enter).await {
+                warn!("Auto-submit failed: {}", e);
+            }
+        }
+
+        Self::run_voice_commands(ctx, &utterance.trailing).await;
+        target.restore().await;
+
+        injected
+    }
+
+    /// Run voice commands in order, logging failures.
+    async fn run_voice_commands(ctx: &ProcessingContext, commands: &[VoiceCommand]) {
+        for command in commands {
+            let action = match command {
+                VoiceCommand::ScratchThat => {
+                    let last_job = ctx.status.get().await.last_completed_job;
+                    match last_job.filter(|job| job.injected) {
+                        Some(job) => KeyAction::Backspace(job.text.chars().count()),
+                        None => {
+                            info!("Nothing to scratch");
+                            continue;
+                        }
+                    }
+                }
+                VoiceCommand::Key(action) => *action,
+            };
+
+            info!("Running voice command {:?}", command);
+            let method = ctx.injection_method().await;
+            if let Err(e) = ctx.text_io.send_key_with(method, action).await {
+                warn!("Voice command {:?} failed: {}", command, e);
+                continue;
+            }
+
+            if *command == VoiceCommand::ScratchThat {
+                ctx.status.retract_last_injection().await;
+            }
+        }
     }
 
     fn temp_audio_path() -> PathBuf {
@@ -530,29 +1129,6 @@ impl RecordingMachine {
     }
 }
 
-/// Save transcription to database and return the history ID.
-fn save_to_database(text: &str, audio_path: &Path) -> Result<i64> {
-    let conn = db::init_db()?;
-
-    let workflow_data = WorkflowData::VoiceToText(VoiceToTextData {
-        text: text.to_string(),
-        audio_path: audio_path.to_string_lossy().to_string(),
-    });
-
-    let workflow = Workflow::new(WorkflowType::VoiceToText, workflow_data);
-
-    let id = db::insert_workflow(&conn, &workflow)?;
-    debug!("Saved transcription to database with ID: {}", id);
-
-    // Prune old workflows if count exceeds 10,000
-    let pruned = db::prune_old_workflows(&conn, 10_000)?;
-    if pruned > 0 {
-        info!("Pruned {} old transcriptions from database", pruned);
-    }
-
-    Ok(id)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -577,6 +1153,27 @@ mod tests {
         assert_eq!(parsed, RecordingPhase::Idle);
     }
 
+    #[test]
+    fn test_job_copy_options_fall_back_to_config() {
+        let defaults = CopyOptions {
+            target: ClipboardTarget::Both,
+            sensitive: true,
+        };
+        assert_eq!(JobOptions::default().copy_options(defaults), defaults);
+
+        let job = JobOptions {
+            clipboard_target: Some(ClipboardTarget::Primary),
+            ..JobOptions::default()
+        };
+        assert_eq!(
+            job.copy_options(defaults),
+            CopyOptions {
+                target: ClipboardTarget::Primary,
+                sensitive: true,
+            }
+        );
+    }
+
     #[test]
     fn test_recording_status_default() {
         let status = RecordingStatus::default();
@@ -610,6 +1207,9 @@ mod tests {
         let options = JobOptions {
             copy_to_clipboard: false,
             auto_paste: false,
+            language: Some("de".to_string()),
+            mode: DictationMode::Code,
+            ..JobOptions::default()
         };
         handle
             .start_job("test-job-custom".to_string(), options)
@@ -622,6 +1222,7 @@ mod tests {
         let job_options = status.current_job_options.unwrap();
         assert!(!job_options.copy_to_clipboard);
         assert!(!job_options.auto_paste);
+        assert_eq!(job_options.language.as_deref(), Some("de"));
     }
 
     #[tokio::test]
@@ -656,6 +1257,7 @@ mod tests {
             history_id: 42,
             text: "Hello world".to_string(),
             created_at: "2025-01-15T10:30:00Z".to_string(),
+            injected: true,
         };
         handle.complete_job(completed).await;
 
@@ -687,6 +1289,33 @@ mod tests {
         assert_eq!(status.last_error, Some("Something went wrong".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_status_handle_publishes_events() {
+        let handle = RecordingStatusHandle::default();
+        let mut events = handle.events().subscribe();
+
+        handle
+            .start_job("evt-job".to_string(), JobOptions::default())
+            .await;
+        handle.fail_job("boom".to_string()).await;
+        handle.set_provider("groq").await;
+        handle.set_provider("groq").await;
+
+        let names: Vec<&str> = std::iter::from_fn(|| events.try_recv().ok())
+            .map(|event| event.name())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "phase",
+                "job_started",
+                "job_failed",
+                "phase",
+                "provider_changed"
+            ]
+        );
+    }
+
     #[tokio::test]
     async fn test_status_handle_job_lifecycle() {
         let handle = RecordingStatusHandle::default();
@@ -715,6 +1344,7 @@ mod tests {
             history_id: 100,
             text: "Test transcription".to_string(),
             created_at: "2025-01-15T12:00:00Z".to_string(),
+            injected: false,
         };
         handle.complete_job(completed).await;
 
@@ -735,6 +1365,7 @@ mod tests {
             history_id: 1,
             text: "First".to_string(),
             created_at: "2025-01-15T10:00:00Z".to_string(),
+            injected: true,
         };
         handle.complete_job(first_job).await;
 
@@ -749,6 +1380,26 @@ mod tests {
         assert_eq!(status.last_completed_job.unwrap().job_id, "first-job");
     }
 
+    #[tokio::test]
+    async fn test_retract_last_injection() {
+        let handle = RecordingStatusHandle::default();
+        handle
+            .complete_job(CompletedJob {
+                job_id: "job".to_string(),
+                history_id: 1,
+                text: "Typed".to_string(),
+                created_at: "2025-01-15T10:00:00Z".to_string(),
+                injected: true,
+            })
+            .await;
+
+        handle.retract_last_injection().await;
+
+        let job = handle.get().await.last_completed_job.unwrap();
+        assert!(!job.injected);
+        assert_eq!(job.text, "Typed");
+    }
+
     #[test]
     fn test_job_options_default() {
         let options = JobOptions::default();
@@ -761,16 +1412,26 @@ mod tests {
         let options = JobOptions {
             copy_to_clipboard: false,
             auto_paste: true,
+            language: None,
+            mode: DictationMode::Code,
+            ..JobOptions::default()
         };
 
         let json = serde_json::to_string(&options).unwrap();
         assert!(json.contains("\"copy_to_clipboard\":false"));
         assert!(json.contains("\"auto_paste\":true"));
+        assert!(json.contains("\"mode\":\"code\""));
 
         // Test deserialization
         let parsed: JobOptions = serde_json::from_str(&json).unwrap();
         assert!(!parsed.copy_to_clipboard);
         assert!(parsed.auto_paste);
+
+        // Language is optional for older clients
+        let parsed: JobOptions =
+            serde_json::from_str(r#"{"copy_to_clipboard":true,"auto_paste":false}"#).unwrap();
+        assert!(parsed.language.is_none());
+        assert_eq!(parsed.mode, DictationMode::Prose);
     }
 
     #[test]
@@ -797,6 +1458,7 @@ mod tests {
             history_id: 42,
             text: "Hello world".to_string(),
             created_at: "2025-01-15T10:30:00Z".to_string(),
+            injected: false,
         };
 
         let json = serde_json::to_string(&job).unwrap();
@@ -809,4 +1471,91 @@ mod tests {
         assert_eq!(parsed.job_id, "test-uuid");
         assert_eq!(parsed.history_id, 42);
     }
+
+    /// A context typing with a stand-in `wtype` in `dir` that logs its
+    /// arguments to `dir/keys`. `dir` has to be put first on `PATH`.
+    fn typing_context(dir: &std::path::Path) -> ProcessingContext {
+        let wtype = dir.join("wtype");
+        std::fs::write(
+            &wtype,
+            format!(
+                "#!/bin/sh\nprintf '%s\\n' \"$*\" >> {}\n",
+                dir.join("keys").display()
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(&wtype, std::os::unix::fs::PermissionsExt::from_mode(0o755))
+            .unwrap();
+        let transcriber = crate::transcription::Transcriber::with_provider(
+            "command",
+            crate::transcription::ProviderConfig {
+                command_path: Some("true".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        ProcessingContext {
+            transcription: Arc::new(TranscriptionService::new(transcriber).unwrap()),
+            indicator: Indicator::new(),
+            text_io: TextIoService::new(Some("clipboard"), Default::default()).unwrap(),
+            job_options: JobOptions {
+                copy_to_clipboard: false,
+                auto_paste: true,
+                input_method: Some("wtype".to_string()),
+                ..JobOptions::default()
+            },
+            temp_path: dir.join("recording.wav"),
+            job_id: Some("test-job".to_string()),
+            delete_audio_files: false,
+            voice_commands: true,
+            status: RecordingStatusHandle::default(),
+            speech: None,
+            hallucination: HallucinationFilter::new(&Default::default()),
+            restore_clipboard: None,
+            sinks: Sinks::new(&[]).unwrap(),
+            clipboard: CopyOptions::default(),
+            pending_job: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_trailing_scratch_types_nothing() {
+        let dir = std::env::temp_dir().join(format!("wisprarch-deliver-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let ctx = typing_context(&dir);
+        ctx.status
+            .complete_job(CompletedJob {
+                job_id: "previous".to_string(),
+                history_id: 1,
+                text: "earlier text".to_string(),
+                created_at: String::new(),
+                injected: true,
+            })
+            .await;
+
+        let path = std::env::var_os("PATH").unwrap_or_default();
+        let mut paths = vec![dir.clone()];
+        paths.extend(std::env::split_paths(&path));
+        std::env::set_var("PATH", std::env::join_paths(paths).unwrap());
+
+        let scratched =
+            RecordingMachine::deliver(&ctx, &Utterance::parse("hello world scratch that")).await;
+        let keys_after_scratch = std::fs::read_to_string(dir.join("keys")).unwrap_or_default();
+        RecordingMachine::deliver(&ctx, &Utterance::parse("Scratch that. Hello world")).await;
+        let keys = std::fs::read_to_string(dir.join("keys")).unwrap_or_default();
+
+        std::env::set_var("PATH", path);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        // Neither the new text nor the previous transcription is touched
+        assert!(!scratched);
+        assert_eq!(keys_after_scratch, "");
+
+        // A leading scratch still deletes the previous transcription
+        let lines: Vec<&str> = keys.lines().collect();
+        assert_eq!(lines.len(), 2, "{:?}", lines);
+        assert_eq!(lines[0].matches("BackSpace").count(), "earlier text".len());
+        assert!(lines[1].ends_with("-- Hello world"), "{:?}", lines[1]);
+    }
 }
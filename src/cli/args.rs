@@ -21,6 +21,14 @@ pub enum CliCommand {
     Provider(ProviderCliArgs),
     /// Search and view transcription history
     History(HistoryCliArgs),
+    /// List, retry or drop queued transcriptions that failed while offline
+    Jobs(JobsCliArgs),
+    /// Preview the configured post-processing chain on some text
+    Postprocess(PostprocessCliArgs),
+    /// List, add or try out snippet trigger phrases
+    Snippets(SnippetsCliArgs),
+    /// Show provider usage, estimated cost and quota status
+    Usage(UsageCliArgs),
     /// View application and transcription logs
     Logs(LogsCliArgs),
     /// Manage Hyprland keybindings for WisprArch
@@ -74,6 +82,17 @@ pub enum ProviderCommand {
         #[arg(short, long)]
         file: Option<String>,
     },
+    /// Benchmark providers against WAV files with `.txt` reference transcripts
+    Bench {
+        /// Directory containing `<name>.wav` and `<name>.txt` pairs
+        dir: String,
+        /// Only benchmark these targets ("default" or a profile name)
+        #[arg(short, long)]
+        profile: Vec<String>,
+        /// Print results as JSON
+        #[arg(long)]
+        json: bool,
+    },
     /// Show provider status and readiness
     Status,
     /// Reset provider configuration to defaults
@@ -101,6 +120,83 @@ pub struct HistoryCliArgs {
     /// ID of specific workflow to copy to clipboard
     #[arg(short, long)]
     pub copy: Option<i64>,
+    /// With --copy, copy the transcript as it was before post-processing
+    #[arg(long, requires = "copy")]
+    pub raw: bool,
+}
+
+#[derive(ClapArgs, Debug)]
+pub struct JobsCliArgs {
+    #[command(subcommand)]
+    pub command: Option<JobsCommand>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum JobsCommand {
+    /// List queued transcription jobs
+    List,
+    /// Retry queued jobs now
+    Retry {
+        /// ID of the job to retry (all jobs if omitted)
+        id: Option<i64>,
+    },
+    /// Remove a queued job and delete its recording
+    Drop {
+        /// ID of the job to drop
+        id: i64,
+    },
+}
+
+#[derive(ClapArgs, Debug)]
+pub struct PostprocessCliArgs {
+    /// Text to run through the post-processing steps
+    #[arg(short, long)]
+    pub text: String,
+    /// Preview code dictation instead of the prose chain
+    #[arg(long)]
+    pub code: bool,
+}
+
+#[derive(ClapArgs, Debug)]
+pub struct SnippetsCliArgs {
+    #[command(subcommand)]
+    pub command: Option<SnippetsCommand>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum SnippetsCommand {
+    /// List configured snippets
+    List,
+    /// Add or replace a snippet
+    Add {
+        /// Spoken trigger phrase (e.g. "insert signature")
+        trigger: String,
+        /// Text to insert; may use {date}, {time} and {clipboard}
+        text: String,
+    },
+    /// Remove a snippet
+    Remove {
+        /// Trigger phrase of the snippet to remove
+        trigger: String,
+    },
+    /// Show how some text would be expanded
+    Expand {
+        /// Text to expand, as if it had been dictated
+        text: String,
+    },
+}
+
+#[derive(ClapArgs, Debug)]
+pub struct UsageCliArgs {
+    /// Group usage by month instead of by day
+    #[arg(long)]
+    pub monthly: bool,
+    /// Only include usage since this date (YYYY-MM-DD)
+    #[arg(long)]
+    pub since: Option<String>,
+    /// Print usage as JSON
+    #[arg(long)]
+    pub json: bool,
 }
 
 #[derive(ClapArgs, Debug)]
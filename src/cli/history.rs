@@ -13,7 +13,7 @@ use super::args::HistoryCliArgs;
 pub fn handle_history_command(args: HistoryCliArgs) -> Result<()> {
     // If copy flag is provided, copy that specific workflow to clipboard
     if let Some(id) = args.copy {
-        return handle_copy_by_id(id);
+        return handle_copy_by_id(id, args.raw);
     }
 
     // Check if no filters are specified (use interactive mode)
@@ -27,8 +27,12 @@ pub fn handle_history_command(args: HistoryCliArgs) -> Result<()> {
 }
 
 /// Copy a specific transcription to clipboard by ID.
-fn handle_copy_by_id(id: i64) -> Result<()> {
-    let text = history::get_text_by_id(id)?;
+fn handle_copy_by_id(id: i64, raw: bool) -> Result<()> {
+    let text = if raw {
+        history::get_raw_text_by_id(id)?
+    } else {
+        history::get_text_by_id(id)?
+    };
 
     copy_to_clipboard_sync(&text)?;
 
@@ -116,6 +120,9 @@ fn handle_search_mode(args: &HistoryCliArgs) -> Result<()> {
         println!("ID: {}", entry.id);
         println!("Date: {}", entry.created_at);
         println!("Text: {}", display_text);
+        if let Some(raw_text) = &entry.raw_text {
+            println!("Raw:  {}", raw_text);
+        }
         println!("---");
     }
 
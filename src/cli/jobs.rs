@@ -0,0 +1,113 @@
+//! CLI handler for the offline job queue.
+//!
+//! This module handles terminal presentation and user interaction.
+//! Core business logic is delegated to the `jobs` module.
+
+use crate::audio::{BehaviorOptions, JobRetrier, RecordingStatusHandle};
+use crate::config::Config;
+use crate::jobs::{self, RetryOutcome};
+use crate::sinks::Sinks;
+use crate::text_io::TextIoService;
+use crate::transcription::TranscriptionService;
+use crate::ui::Indicator;
+use anyhow::{anyhow, Result};
+use std::sync::Arc;
+
+use super::args::{JobsCliArgs, JobsCommand};
+
+pub async fn handle_jobs_command(args: JobsCliArgs) -> Result<()> {
+    match args.command {
+        Some(JobsCommand::List) | None => handle_list(),
+        Some(JobsCommand::Retry { id }) => handle_retry(id).await,
+        Some(JobsCommand::Drop { id }) => handle_drop(id),
+    }
+}
+
+fn handle_list() -> Result<()> {
+    let jobs = jobs::list()?;
+
+    if jobs.is_empty() {
+        println!("No queued transcription jobs.");
+        return Ok(());
+    }
+
+    println!();
+    println!("Queued Transcription Jobs");
+    println!("=========================");
+    println!();
+    for job in &jobs {
+        println!(
+            "[{}] {} - {} attempt(s)",
+            job.id, job.created_at, job.attempts
+        );
+        println!("    Audio: {}", job.audio_path);
+        println!("    Error: {}", truncate(&job.error, 100));
+    }
+    println!();
+    println!("Retry with `wisprarch jobs retry [ID]`, remove with `wisprarch jobs drop <ID>`.");
+
+    Ok(())
+}
+
+async fn handle_retry(id: Option<i64>) -> Result<()> {
+    let queued = match id {
+        Some(id) => vec![jobs::get(id)?.ok_or_else(|| anyhow!("Job #{} not found", id))?],
+        None => jobs::list()?,
+    };
+
+    if queued.is_empty() {
+        println!("No queued transcription jobs.");
+        return Ok(());
+    }
+
+    let config = Config::load()?;
+    let retrier = JobRetrier::new(
+        Arc::new(TranscriptionService::from_config(&config)?),
+        Indicator::new(),
+        TextIoService::new(
+            Some(&config.wayland.input_method),
+            config.wayland.typing.clone(),
+        )?,
+        BehaviorOptions::from_config(&config, Sinks::new(&config.sinks)?)?,
+        RecordingStatusHandle::default(),
+    );
+
+    for job in &queued {
+        print!("Job #{}... ", job.id);
+        match retrier.retry(job).await? {
+            RetryOutcome::Completed { history_id, text } => {
+                println!("done (history #{})", history_id);
+                println!("  \"{}\"", truncate(&text, 100));
+            }
+            RetryOutcome::NoSpeech => println!("no speech detected, removed"),
+            RetryOutcome::Missing => println!("recording not found, removed"),
+            RetryOutcome::Busy => println!("already being retried by the running service"),
+            RetryOutcome::Failed { error, offline } => {
+                println!("failed: {}", truncate(&error, 100));
+                if offline {
+                    println!("Provider is still unreachable; remaining jobs stay queued.");
+                    break;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn handle_drop(id: i64) -> Result<()> {
+    if !jobs::remove(id, true)? {
+        return Err(anyhow!("Job #{} not found", id));
+    }
+
+    println!("Dropped job #{}", id);
+    Ok(())
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() > max_chars {
+        format!("{}...", text.chars().take(max_chars).collect::<String>())
+    } else {
+        text.to_string()
+    }
+}
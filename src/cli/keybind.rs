@@ -4,6 +4,7 @@
 //! Core business logic is delegated to the `keybind` module.
 
 use crate::cli::{KeybindCliArgs, KeybindCommand};
+use crate::config::Config;
 use crate::keybind::discovery::get_all_config_files;
 use crate::keybind::{
     self, check_conflicts, discover_config, find_wisprarch_bindings, parse_bindings, write_binding,
@@ -57,8 +58,10 @@ fn handle_interactive() -> Result<()> {
         all_bindings.extend(parse_bindings(file));
     }
 
+    let api = Config::load()?.api;
+
     // Check for existing wisprarch bindings
-    let existing = find_wisprarch_bindings(&all_bindings);
+    let existing = find_wisprarch_bindings(&all_bindings, &api);
     if !existing.is_empty() {
         println!("Existing wisprarch keybinding found:");
         for binding in &existing {
@@ -83,7 +86,7 @@ fn handle_interactive() -> Result<()> {
     }
 
     // Propose default binding
-    let mut proposed = ProposedBinding::default();
+    let mut proposed = ProposedBinding::default().for_api(&api);
 
     println!("Proposed keybinding:");
     println!("  {} -> Toggle wisprarch recording", proposed.display_key());
@@ -132,11 +135,11 @@ fn handle_interactive() -> Result<()> {
         match (fallback_conflicts.has_conflicts(), selection) {
             (false, 0) => {
                 // Use fallback
-                proposed = fallback;
+                proposed = fallback.for_api(&api);
             }
             (false, 1) | (true, 0) => {
                 // Custom keybinding
-                proposed = prompt_custom_keybinding(&theme)?;
+                proposed = prompt_custom_keybinding(&theme)?.for_api(&api);
             }
             _ => {
                 println!("Skipping keybinding configuration.");
@@ -160,7 +163,7 @@ fn handle_interactive() -> Result<()> {
                 .interact()?;
 
             if custom {
-                proposed = prompt_custom_keybinding(&theme)?;
+                proposed = prompt_custom_keybinding(&theme)?.for_api(&api);
             } else {
                 println!("Skipping keybinding configuration.");
                 return Ok(());
@@ -214,7 +217,8 @@ fn handle_install(key: Option<String>, dry_run: bool) -> Result<()> {
             keybind::parse_key_string(key_str)?
         } else {
             ProposedBinding::default()
-        };
+        }
+        .for_api(&Config::load()?.api);
 
         println!("Dry run - would add to {}:", config_path.display());
         println!("  {}", WISPRARCH_SECTION_MARKER);
@@ -1,20 +1,30 @@
 mod args;
 mod history;
+mod jobs;
 mod keybind;
 mod logs;
 mod models;
+mod postprocess;
 pub mod provider;
+mod snippets;
 mod update;
+mod usage;
 mod waybar;
 
 pub use args::{
-    Cli, CliCommand, HistoryCliArgs, KeybindCliArgs, KeybindCommand, LogsCliArgs, ModelsCliArgs,
-    ModelsCommand, ProviderCliArgs, ProviderCommand, UpdateCliArgs, WaybarCliArgs, WaybarCommand,
+    Cli, CliCommand, HistoryCliArgs, JobsCliArgs, JobsCommand, KeybindCliArgs, KeybindCommand,
+    LogsCliArgs, ModelsCliArgs, ModelsCommand, PostprocessCliArgs, ProviderCliArgs,
+    ProviderCommand, SnippetsCliArgs, SnippetsCommand, UpdateCliArgs, UsageCliArgs, WaybarCliArgs,
+    WaybarCommand,
 };
 pub use history::handle_history_command;
+pub use jobs::handle_jobs_command;
 pub use keybind::handle_keybind_command;
 pub use logs::handle_logs_command;
 pub use models::handle_models_command;
+pub use postprocess::handle_postprocess_command;
 pub use provider::handle_provider_command;
+pub use snippets::handle_snippets_command;
 pub use update::handle_update_command;
+pub use usage::handle_usage_command;
 pub use waybar::handle_waybar_command;
@@ -0,0 +1,57 @@
+//! CLI handler for previewing the post-processing chain.
+//!
+//! This module handles terminal presentation.
+//! Core business logic is delegated to the `normalizer` module.
+
+use crate::config::Config;
+use crate::normalizer::{LlmCleanup, PostProcessor, SnippetExpander, TranscriptionNormalizer};
+use anyhow::Result;
+
+use super::args::PostprocessCliArgs;
+
+pub async fn handle_postprocess_command(args: PostprocessCliArgs) -> Result<()> {
+    let config = Config::load()?;
+    let processor = if args.code {
+        PostProcessor::for_code()
+    } else {
+        PostProcessor::for_profile(&config.whisper, &config.postprocess)?
+    };
+    let snippets = SnippetExpander::new(&config.snippets)?;
+    let cleanup = if config.llm_cleanup.enabled && !args.code {
+        Some(LlmCleanup::new(&config.llm_cleanup)?)
+    } else {
+        None
+    };
+
+    if processor.is_empty() && cleanup.is_none() && snippets.is_empty() {
+        println!(
+            "No [[postprocess]] steps, itn, snippets or LLM cleanup configured; text is left unchanged."
+        );
+        println!();
+        println!("{}", args.text);
+        return Ok(());
+    }
+
+    println!();
+    println!("  {:<22} {:?}", "input", args.text);
+    let mut text = args.text.clone();
+    for (name, output) in processor.trace(&args.text) {
+        println!("  {:<22} {:?}", name, output);
+        text = output;
+    }
+    if let Some(cleanup) = &cleanup {
+        match cleanup.clean(&text).await {
+            Ok(cleaned) => text = cleaned,
+            Err(e) => println!("  LLM cleanup failed, keeping text: {:#}", e),
+        }
+        println!("  {:<22} {:?}", "llm_cleanup", text);
+    }
+    if !snippets.is_empty() {
+        text = snippets.normalize(&text);
+        println!("  {:<22} {:?}", snippets.name(), text);
+    }
+    println!();
+    println!("{}", text);
+
+    Ok(())
+}
@@ -5,8 +5,10 @@
 
 use crate::cli::{ProviderCliArgs, ProviderCommand};
 use crate::config::{Config, WhisperConfig};
+use crate::transcription::bench::{self, BenchTarget};
 use crate::transcription::{
-    get_provider_status_from_config, ProviderConfig, ProviderStatus, Transcriber,
+    get_provider_status_from_config, provider_capabilities, ProviderCapabilities, ProviderConfig,
+    ProviderStatus, Transcriber,
 };
 use anyhow::{anyhow, Context, Result};
 use chrono::Local;
@@ -20,19 +22,22 @@ use which::which;
 
 const MAX_CONFIG_BACKUPS: usize = 3;
 
-pub fn handle_provider_command(args: ProviderCliArgs) -> Result<()> {
+pub async fn handle_provider_command(args: ProviderCliArgs) -> Result<()> {
     match args.command {
         Some(ProviderCommand::Show) => handle_show(),
         Some(ProviderCommand::Configure { dry_run }) => handle_configure(dry_run),
-        Some(ProviderCommand::Test { file }) => handle_test(file),
+        Some(ProviderCommand::Test { file }) => handle_test(file).await,
+        Some(ProviderCommand::Bench { dir, profile, json }) => {
+            handle_bench(&dir, &profile, json).await
+        }
         Some(ProviderCommand::Status) => handle_status(),
         Some(ProviderCommand::Reset { force }) => handle_reset(force),
-        None => handle_interactive(),
+        None => handle_interactive().await,
     }
 }
 
 /// Interactive provider setup wizard (default when no subcommand provided)
-fn handle_interactive() -> Result<()> {
+async fn handle_interactive() -> Result<()> {
     if !io::stdin().is_terminal() {
         info!("Non-interactive session. Use 'wisprarch provider configure' for automated setup.");
         return Ok(());
@@ -71,7 +76,7 @@ fn handle_interactive() -> Result<()> {
 
     match selection {
         0 => handle_configure(false),
-        1 => handle_test(None),
+        1 => handle_test(None).await,
         2 => handle_show(),
         3 => handle_reset(false),
         _ => {
@@ -110,6 +115,16 @@ fn handle_show() -> Result<()> {
     println!("Local Binary Settings:");
     println!("  Command:    {}", display_value(&whisper.command_path));
     println!("  Model Path: {}", display_value(&whisper.model_path));
+    if whisper.provider.as_deref() == Some("command") {
+        println!("  Args:       {}", whisper.command_args.join(" "));
+        println!(
+            "  Timeout:    {}",
+            whisper
+                .timeout_secs
+                .map(|secs| format!("{secs}s"))
+                .unwrap_or_else(|| "<default>".to_string())
+        );
+    }
     println!();
     println!("Config file:  {}", crate::global::config_file()?.display());
 
@@ -147,6 +162,7 @@ fn handle_configure(dry_run: bool) -> Result<()> {
         ProviderSelection::OpenAiApi => configure_openai_api(&theme, &mut config.whisper)?,
         ProviderSelection::OpenAiCli => configure_openai_cli(&theme, &mut config.whisper)?,
         ProviderSelection::WhisperCpp => configure_whisper_cpp(&theme, &mut config.whisper)?,
+        ProviderSelection::Command => configure_command(&theme, &mut config.whisper)?,
     }
 
     // Show what would change
@@ -196,7 +212,7 @@ fn handle_configure(dry_run: bool) -> Result<()> {
 }
 
 /// Test provider with optional audio file
-fn handle_test(file: Option<String>) -> Result<()> {
+async fn handle_test(file: Option<String>) -> Result<()> {
     let config = Config::load()?;
     let provider_name = config.whisper.provider.as_deref().ok_or_else(|| {
         anyhow!("No transcription provider configured. Run `wisprarch provider configure` first.")
@@ -226,8 +242,7 @@ fn handle_test(file: Option<String>) -> Result<()> {
         print!("Transcribing... ");
 
         let start = Instant::now();
-        let result = tokio::runtime::Runtime::new()?
-            .block_on(async { transcriber.transcribe(&audio_path).await })?;
+        let result = transcriber.transcribe(&audio_path).await?;
         let elapsed = start.elapsed();
 
         println!("done ({:.2}s)", elapsed.as_secs_f64());
@@ -250,6 +265,108 @@ fn handle_test(file: Option<String>) -> Result<()> {
     Ok(())
 }
 
+/// Benchmark the default provider and profiles - uses transcription::bench
+async fn handle_bench(dir: &str, only: &[String], json: bool) -> Result<()> {
+    let config = Config::load()?;
+    let samples = bench::load_samples(Path::new(dir))?;
+    if samples.is_empty() {
+        return Err(anyhow!(
+            "No WAV files with .txt reference transcripts found in {}",
+            dir
+        ));
+    }
+
+    let mut targets = vec![BenchTarget {
+        name: "default".to_string(),
+        whisper: config.whisper.clone(),
+    }];
+    targets.extend(config.profiles.iter().map(|(name, whisper)| BenchTarget {
+        name: name.clone(),
+        whisper: whisper.clone(),
+    }));
+
+    if !only.is_empty() {
+        if let Some(unknown) = only.iter().find(|n| !targets.iter().any(|t| &t.name == *n)) {
+            return Err(anyhow!("Unknown profile: {}", unknown));
+        }
+        targets.retain(|t| only.contains(&t.name));
+        if let Some(target) = targets.iter().find(|t| t.whisper.provider.is_none()) {
+            return Err(anyhow!(
+                "Profile '{}' has no provider; set provider in its config section",
+                target.name
+            ));
+        }
+    }
+    targets.retain(|t| t.whisper.provider.is_some());
+    if targets.is_empty() {
+        return Err(anyhow!(
+            "No transcription provider configured. Run `wisprarch provider configure` first."
+        ));
+    }
+
+    if !json {
+        println!();
+        println!(
+            "Benchmarking {} target(s) over {} sample(s)...",
+            targets.len(),
+            samples.len()
+        );
+    }
+
+    let mut reports = Vec::with_capacity(targets.len());
+    for target in &targets {
+        if !json {
+            println!("  {}", target.name);
+        }
+        reports.push(bench::run_target(target, &samples).await?);
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&reports)?);
+        return Ok(());
+    }
+
+    println!();
+    println!(
+        "{:<16} {:<12} {:>7} {:>7} {:>8} {:>8} {:>8} {:>6}",
+        "TARGET", "PROVIDER", "WER", "RTF", "P50", "P90", "P99", "FAIL"
+    );
+    for report in &reports {
+        println!(
+            "{:<16} {:<12} {:>7} {:>7} {:>8} {:>8} {:>8} {:>6}",
+            report.target,
+            report.provider.as_deref().unwrap_or("-"),
+            format_metric(report.wer.map(|w| w * 100.0), "%"),
+            format_metric(report.rtf, ""),
+            format_metric(report.latency_p50_secs, "s"),
+            format_metric(report.latency_p90_secs, "s"),
+            format_metric(report.latency_p99_secs, "s"),
+            report.failures,
+        );
+    }
+
+    for report in &reports {
+        for result in report.results.iter().filter(|r| !r.success) {
+            println!(
+                "  {} / {}: {}",
+                report.target,
+                result.file,
+                result.error.as_deref().unwrap_or("unknown error")
+            );
+        }
+    }
+    println!();
+
+    Ok(())
+}
+
+fn format_metric(value: Option<f64>, suffix: &str) -> String {
+    match value {
+        Some(v) => format!("{:.2}{}", v, suffix),
+        None => "-".to_string(),
+    }
+}
+
 /// Show provider status and health - uses transcription::get_provider_status_from_config()
 fn handle_status() -> Result<()> {
     let config = Config::load()?;
@@ -266,6 +383,7 @@ fn handle_status() -> Result<()> {
             provider,
             model,
             language,
+            capabilities,
         } => {
             println!("Status: READY");
             println!();
@@ -302,9 +420,17 @@ fn handle_status() -> Result<()> {
                     println!("Command:   {}", display_value(&whisper.command_path));
                     println!("Model:     {}", display_value(&whisper.model_path));
                 }
+                "command" => {
+                    println!("Command:   {}", display_value(&whisper.command_path));
+                    println!("Args:      {}", whisper.command_args.join(" "));
+                    println!("Timeout:   {}s", whisper.timeout_secs.unwrap_or(120));
+                }
                 _ => {}
             }
 
+            println!();
+            print_capabilities(&capabilities);
+
             println!();
             println!("Health: Ready for transcription");
         }
@@ -391,6 +517,36 @@ fn handle_reset(force: bool) -> Result<()> {
 // Provider status helpers
 // ============================================================================
 
+fn print_capabilities(capabilities: &ProviderCapabilities) {
+    let features = capabilities.features();
+
+    println!("Capabilities:");
+    println!("  Runs:       {}", capabilities.locality.as_str());
+    println!("  Languages:  {}", capabilities.languages_display());
+    println!(
+        "  Auto-lang:  {}",
+        if capabilities.auto_language {
+            "yes"
+        } else {
+            "no"
+        }
+    );
+    if let Some(max) = capabilities.max_upload_bytes {
+        println!("  Max upload: {:.0} MB", max as f64 / 1_000_000.0);
+    }
+    if let Some(max) = capabilities.max_duration_secs {
+        println!("  Max audio:  {} min", max / 60);
+    }
+    println!(
+        "  Features:   {}",
+        if features.is_empty() {
+            "none".to_string()
+        } else {
+            features.join(", ")
+        }
+    );
+}
+
 /// Display helper for ProviderStatus
 fn provider_status_display(status: &ProviderStatus) -> &'static str {
     match status {
@@ -626,6 +782,40 @@ fn configure_whisper_cpp(theme: &ColorfulTheme, whisper: &mut WhisperConfig) ->
     Ok(())
 }
 
+fn configure_command(theme: &ColorfulTheme, whisper: &mut WhisperConfig) -> Result<()> {
+    whisper.api_key = None;
+    whisper.api_endpoint = None;
+    whisper.model_path = None;
+
+    println!("The command receives a JSON request on stdin and must print a JSON");
+    println!("transcript ({{\"text\": \"...\"}}) on stdout.");
+    println!();
+
+    let command_default = whisper.command_path.clone();
+    whisper.command_path = Some(prompt_required_path(
+        theme,
+        "Path to transcription command",
+        command_default,
+        true,
+    )?);
+
+    let args_default = whisper.command_args.join(" ");
+    let args = prompt_string_with_default(theme, "Extra arguments", &args_default)?;
+    whisper.command_args = args.split_whitespace().map(str::to_string).collect();
+
+    let timeout_default = whisper.timeout_secs.unwrap_or(120).to_string();
+    let timeout = prompt_string_with_default(theme, "Timeout in seconds", &timeout_default)?;
+    whisper.timeout_secs = Some(
+        timeout
+            .parse()
+            .with_context(|| format!("Invalid timeout: {}", timeout))?,
+    );
+
+    prompt_language_choice(theme, whisper, "en")?;
+
+    Ok(())
+}
+
 // ============================================================================
 // Input prompt helpers
 // ============================================================================
@@ -641,6 +831,7 @@ fn prompt_provider_selection(
         ("openai-api", "OpenAI Whisper API (requires API key)"),
         ("openai-cli", "Local OpenAI Whisper CLI (requires install)"),
         ("whisper-cpp", "Local whisper.cpp (requires install)"),
+        ("command", "External command (custom engine via JSON)"),
     ];
 
     let items: Vec<String> = OPTIONS
@@ -708,19 +899,39 @@ fn prompt_language_choice(
         .clone()
         .unwrap_or_else(|| fallback.to_string());
 
-    let prompt = format!("Language code (ISO 639-1, e.g. en, es, auto) [{current}]");
-    let value: String = Input::with_theme(theme)
-        .with_prompt(prompt)
-        .allow_empty(true)
-        .interact_text()?;
+    let capabilities = whisper.provider.as_deref().and_then(provider_capabilities);
 
-    let trimmed = value.trim();
-    if trimmed.is_empty() {
-        whisper.language = Some(current);
-    } else {
-        whisper.language = Some(trimmed.to_string());
+    if let Some(capabilities) = &capabilities {
+        if capabilities.languages.is_some() {
+            println!("Supported languages: {}", capabilities.languages_display());
+        }
+    }
+
+    loop {
+        let prompt = format!("Language code (ISO 639-1, e.g. en, es, auto) [{current}]");
+        let value: String = Input::with_theme(theme)
+            .with_prompt(prompt)
+            .allow_empty(true)
+            .interact_text()?;
+
+        let trimmed = value.trim();
+        let language = if trimmed.is_empty() {
+            current.clone()
+        } else {
+            trimmed.to_string()
+        };
+
+        if let Some(Err(error)) = capabilities
+            .as_ref()
+            .map(|caps| caps.check_language(&language))
+        {
+            println!("{}", error);
+            continue;
+        }
+
+        whisper.language = Some(language);
+        return Ok(());
     }
-    Ok(())
 }
 
 fn prompt_required_path(
@@ -782,14 +993,7 @@ fn detect_default_binary(program: &str) -> Option<String> {
 }
 
 fn provider_config_from_whisper(whisper: &WhisperConfig) -> ProviderConfig {
-    ProviderConfig {
-        model: whisper.model.clone(),
-        model_path: whisper.model_path.clone(),
-        language: whisper.language.clone(),
-        command_path: whisper.command_path.clone(),
-        api_endpoint: whisper.api_endpoint.clone(),
-        api_key: whisper.api_key.clone(),
-    }
+    ProviderConfig::from(whisper)
 }
 
 // ============================================================================
@@ -827,6 +1031,7 @@ enum ProviderSelection {
     OpenAiApi,
     OpenAiCli,
     WhisperCpp,
+    Command,
 }
 
 impl ProviderSelection {
@@ -838,6 +1043,7 @@ impl ProviderSelection {
             ProviderSelection::OpenAiApi => "openai-api",
             ProviderSelection::OpenAiCli => "openai-cli",
             ProviderSelection::WhisperCpp => "whisper-cpp",
+            ProviderSelection::Command => "command",
         }
     }
 
@@ -848,7 +1054,8 @@ impl ProviderSelection {
             2 => ProviderSelection::ParakeetV2,
             3 => ProviderSelection::OpenAiApi,
             4 => ProviderSelection::OpenAiCli,
-            _ => ProviderSelection::WhisperCpp,
+            5 => ProviderSelection::WhisperCpp,
+            _ => ProviderSelection::Command,
         }
     }
 }
@@ -863,6 +1070,7 @@ mod tests {
             provider: "wisprarch-api".to_string(),
             model: Some("base".to_string()),
             language: Some("en".to_string()),
+            capabilities: ProviderCapabilities::local(),
         };
         assert_eq!(provider_status_display(&status), "Ready");
 
@@ -895,6 +1103,17 @@ mod tests {
         let status = get_provider_status_from_config(&whisper).unwrap();
         assert!(matches!(status, ProviderStatus::ConfigError { .. }));
 
+        // Parakeet v2 only understands English
+        whisper.provider = Some("parakeet-v2".to_string());
+        whisper.language = Some("de".to_string());
+        let status = get_provider_status_from_config(&whisper).unwrap();
+        match status {
+            ProviderStatus::ConfigError { error, .. } => assert!(error.contains("'de'")),
+            other => panic!("expected config error, got {:?}", other),
+        }
+        whisper.provider = Some("openai-api".to_string());
+        whisper.language = Some("en".to_string());
+
         // OpenAI API with key is valid
         whisper.api_key = Some("sk-test".to_string());
         let status = get_provider_status_from_config(&whisper).unwrap();
@@ -0,0 +1,73 @@
+//! CLI handler for snippet trigger phrases.
+//!
+//! This module handles terminal presentation.
+//! Core business logic is delegated to the `normalizer` module.
+
+use crate::config::Config;
+use crate::normalizer::{SnippetExpander, TranscriptionNormalizer};
+use anyhow::{anyhow, Result};
+
+use super::args::{SnippetsCliArgs, SnippetsCommand};
+
+pub fn handle_snippets_command(args: SnippetsCliArgs) -> Result<()> {
+    match args.command {
+        Some(SnippetsCommand::List) | None => handle_list(),
+        Some(SnippetsCommand::Add { trigger, text }) => handle_add(trigger, text),
+        Some(SnippetsCommand::Remove { trigger }) => handle_remove(&trigger),
+        Some(SnippetsCommand::Expand { text }) => handle_expand(&text),
+    }
+}
+
+fn handle_list() -> Result<()> {
+    let config = Config::load()?;
+
+    if config.snippets.is_empty() {
+        println!("No snippets configured.");
+        println!("Add one with `wisprarch snippets add \"my email\" \"me@example.com\"`.");
+        return Ok(());
+    }
+
+    println!();
+    println!("Snippets");
+    println!("========");
+    println!();
+    for (trigger, text) in &config.snippets {
+        println!("  {:<24} {:?}", trigger, text);
+    }
+    println!();
+
+    Ok(())
+}
+
+fn handle_add(trigger: String, text: String) -> Result<()> {
+    let mut config = Config::load()?;
+    let trigger = trigger.trim().to_lowercase();
+    config.snippets.insert(trigger.clone(), text);
+    SnippetExpander::new(&config.snippets)?;
+    config.save()?;
+
+    println!("Saved snippet \"{}\".", trigger);
+    Ok(())
+}
+
+fn handle_remove(trigger: &str) -> Result<()> {
+    let mut config = Config::load()?;
+    let key = config
+        .snippets
+        .keys()
+        .find(|key| key.eq_ignore_ascii_case(trigger.trim()))
+        .cloned()
+        .ok_or_else(|| anyhow!("No snippet with trigger \"{}\"", trigger))?;
+    config.snippets.remove(&key);
+    config.save()?;
+
+    println!("Removed snippet \"{}\".", key);
+    Ok(())
+}
+
+fn handle_expand(text: &str) -> Result<()> {
+    let config = Config::load()?;
+    let expander = SnippetExpander::new(&config.snippets)?;
+    println!("{}", expander.normalize(text));
+    Ok(())
+}
@@ -0,0 +1,80 @@
+//! CLI handler for provider usage and cost reporting.
+//!
+//! This module handles terminal presentation.
+//! Core business logic is delegated to the `usage` module.
+
+use crate::config::Config;
+use crate::usage::{self, Granularity, QuotaState};
+use anyhow::Result;
+use serde_json::json;
+
+use super::args::UsageCliArgs;
+
+pub fn handle_usage_command(args: UsageCliArgs) -> Result<()> {
+    let config = Config::load()?;
+    let granularity = if args.monthly {
+        Granularity::Monthly
+    } else {
+        Granularity::Daily
+    };
+
+    let summaries = usage::rollup(&config.usage, granularity, args.since.as_deref())?;
+    let quota = usage::current_quota_report(&config)?;
+
+    if args.json {
+        let output = json!({ "usage": summaries, "quota": quota });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    println!();
+    println!("Transcription Usage");
+    println!("===================");
+    println!();
+
+    if summaries.is_empty() {
+        println!("No usage recorded yet.");
+    } else {
+        println!(
+            "{:<10} {:<12} {:<24} {:>5} {:>5} {:>9} {:>9}",
+            "PERIOD", "PROVIDER", "MODEL", "REQS", "FAIL", "MINUTES", "COST"
+        );
+        for summary in &summaries {
+            println!(
+                "{:<10} {:<12} {:<24} {:>5} {:>5} {:>9.1} {:>9}",
+                summary.period,
+                summary.provider,
+                summary.model.as_deref().unwrap_or("-"),
+                summary.requests,
+                summary.failures,
+                summary.audio_minutes,
+                summary
+                    .estimated_cost_usd
+                    .map(|cost| format!("${:.2}", cost))
+                    .unwrap_or_else(|| "-".to_string()),
+            );
+        }
+    }
+
+    if let Some(report) = quota {
+        println!();
+        println!(
+            "Current period (since {}) for {}: {:.1} min, ${:.2}",
+            report.period_start, report.provider, report.audio_minutes, report.estimated_cost_usd
+        );
+        match report.state {
+            QuotaState::Ok => println!("Quota: OK"),
+            QuotaState::SoftExceeded(message) => println!("Quota: WARNING - {}", message),
+            QuotaState::HardExceeded(message) => {
+                let action = match config.usage.fallback_profile.as_deref() {
+                    Some(profile) => format!("using profile '{}'", profile),
+                    None => "transcription is blocked".to_string(),
+                };
+                println!("Quota: EXCEEDED - {} ({})", message, action);
+            }
+        }
+    }
+    println!();
+
+    Ok(())
+}
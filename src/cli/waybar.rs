@@ -1,4 +1,5 @@
 use super::args::{WaybarCliArgs, WaybarCommand};
+use crate::config::{ApiConfig, Config};
 use anyhow::{Context, Result};
 use std::fs;
 use std::path::PathBuf;
@@ -12,15 +13,31 @@ pub fn handle_waybar_command(args: WaybarCliArgs) -> Result<()> {
     }
 }
 
-const WISPRARCH_MODULE: &str = r#"
-  "custom/wisprarch": {
-    "exec": "while true; do curl -s 'http://127.0.0.1:3737/status?style=waybar' 2>/dev/null || echo '{\"text\":\"󰍬\",\"class\":\"wisprarch-offline\"}'; sleep 0.2; done",
+const OFFLINE_STATUS: &str = r#"{"text":"󰍬","class":"wisprarch-offline"}"#;
+
+/// The Waybar module definition, calling the API configured in `[api]`.
+fn wisprarch_module(api: &ApiConfig) -> String {
+    let exec = format!(
+        "while true; do {} 2>/dev/null || echo '{}'; sleep 0.2; done",
+        api.curl_command("-s", "/status?style=waybar"),
+        OFFLINE_STATUS
+    );
+    let json = |command: &str| serde_json::Value::from(command).to_string();
+    format!(
+        r#"
+  "custom/wisprarch": {{
+    "exec": {},
     "exec-on-event": false,
     "return-type": "json",
-    "on-click": "curl -X POST http://127.0.0.1:3737/toggle",
-    "on-click-right": "curl -X POST http://127.0.0.1:3737/input-method/cycle",
+    "on-click": {},
+    "on-click-right": {},
     "tooltip": true
-  }"#;
+  }}"#,
+        json(&exec),
+        json(&api.curl_command("-X POST", "/toggle")),
+        json(&api.curl_command("-X POST", "/input-method/cycle")),
+    )
+}
 
 pub fn run_install() -> Result<()> {
     let config_path = find_waybar_config()?;
@@ -33,7 +50,8 @@ pub fn run_install() -> Result<()> {
         return Ok(());
     }
 
-    let new_content = inject_module(&content)?;
+    let module = wisprarch_module(&Config::load()?.api);
+    let new_content = inject_module(&content, &module)?;
 
     fs::write(&config_path, &new_content).context("Failed to write Waybar config")?;
 
@@ -111,7 +129,7 @@ fn find_waybar_config() -> Result<PathBuf> {
     ))
 }
 
-fn inject_module(content: &str) -> Result<String> {
+fn inject_module(content: &str, module: &str) -> Result<String> {
     let mut lines: Vec<String> = content.lines().map(|l| l.to_string()).collect();
     let mut added_to_modules = false;
     let mut added_definition = false;
@@ -137,7 +155,7 @@ fn inject_module(content: &str) -> Result<String> {
         let before = &result[..last_brace];
         let before = before.trim_end();
         let before = before.trim_end_matches(',');
-        result = format!("{},{}\n}}\n", before, WISPRARCH_MODULE);
+        result = format!("{},{}\n}}\n", before, module);
         added_definition = true;
     }
 
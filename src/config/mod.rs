@@ -1,6 +1,7 @@
 use crate::global;
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 use tracing::info;
 
@@ -11,6 +12,25 @@ pub struct Config {
     pub ui: UiConfig,
     pub wayland: WaylandConfig,
     pub behavior: BehaviorConfig,
+    /// Named alternative provider configurations (`[profiles.<name>]`)
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, WhisperConfig>,
+    pub usage: UsageConfig,
+    /// Post-processing steps applied to every transcript, in order
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub postprocess: Vec<PostProcessStep>,
+    pub hallucination: HallucinationConfig,
+    pub llm_cleanup: LlmCleanupConfig,
+    /// Spoken trigger phrases expanded to stored text (`[snippets]`)
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub snippets: BTreeMap<String, String>,
+    /// Per-application overrides matched against the focused window
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub apps: Vec<AppRule>,
+    /// Outputs that receive every completed transcript (`[[sinks]]`)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub sinks: Vec<SinkConfig>,
+    pub api: ApiConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,6 +43,17 @@ pub struct WhisperConfig {
     pub api_endpoint: Option<String>,
     pub provider: Option<String>,
     pub api_key: Option<String>,
+    /// Extra arguments passed to `command_path` (command provider only)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub command_args: Vec<String>,
+    /// Provider-specific options forwarded in the command provider request
+    #[serde(skip_serializing_if = "serde_json::Map::is_empty")]
+    pub command_options: serde_json::Map<String, serde_json::Value>,
+    /// Timeout for external transcription commands
+    pub timeout_secs: Option<u64>,
+    /// Write spoken numbers, dates and units in written form
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub itn: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -53,6 +84,30 @@ pub struct WaybarConfig {
 #[serde(default)]
 pub struct WaylandConfig {
     pub input_method: String,
+    pub typing: TypingConfig,
+}
+
+/// How wtype, ydotool and xdotool type text (`[wayland.typing]`).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TypingConfig {
+    /// Characters per backend call (0 types the whole text at once)
+    pub chunk_size: usize,
+    /// Delay between keystrokes (0 uses the backend default)
+    pub key_delay_ms: u64,
+    /// Pause between chunks
+    pub chunk_delay_ms: u64,
+    pub newline: NewlineKey,
+}
+
+/// Key sent for a line break in typed text.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NewlineKey {
+    #[default]
+    Enter,
+    /// Line break without sending the message in chat apps
+    ShiftEnter,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -62,6 +117,258 @@ pub struct BehaviorConfig {
     pub delete_audio_files: bool,
     #[serde(default = "default_audio_feedback")]
     pub audio_feedback: bool,
+    /// Recognize editing commands such as "scratch that" and "press enter"
+    pub voice_commands: bool,
+    /// Put the previous clipboard contents back after pasting a transcript
+    /// that shouldn't stay on the clipboard
+    pub restore_clipboard: bool,
+    /// Time the target app gets to read the pasted text before the restore
+    pub restore_clipboard_delay_ms: u64,
+    /// Selections a transcript is copied to
+    pub clipboard_target: ClipboardTarget,
+    /// Ask clipboard managers not to keep copied transcripts
+    pub clipboard_sensitive: bool,
+}
+
+/// Selections copied text is written to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClipboardTarget {
+    /// The regular Ctrl+C / Ctrl+V clipboard
+    #[default]
+    Clipboard,
+    /// The primary selection, pasted with the middle mouse button
+    Primary,
+    Both,
+    None,
+}
+
+impl ClipboardTarget {
+    pub fn includes_clipboard(&self) -> bool {
+        matches!(self, ClipboardTarget::Clipboard | ClipboardTarget::Both)
+    }
+
+    pub fn includes_primary(&self) -> bool {
+        matches!(self, ClipboardTarget::Primary | ClipboardTarget::Both)
+    }
+}
+
+/// Where the HTTP API listens (`[api]`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ApiConfig {
+    /// Serve the API over TCP at `host`:`port`. Turning it off needs
+    /// `unix_socket`.
+    pub tcp: bool,
+    pub host: String,
+    pub port: u16,
+    /// User-only Unix domain socket served next to TCP. Generated keybind
+    /// and Waybar commands use it when set.
+    pub unix_socket: Option<String>,
+    /// Require the bearer token from the data directory on every route
+    /// except `/version`
+    pub require_token: bool,
+    /// Browser origins allowed to call the API, e.g. `moz-extension://<uuid>`.
+    /// Requests from any other origin are rejected.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub allowed_origins: Vec<String>,
+    /// Extra names clients may address the API by in `Host`, e.g. the LAN
+    /// address when `host` is `0.0.0.0`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub allowed_hosts: Vec<String>,
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self {
+            tcp: true,
+            host: "127.0.0.1".to_string(),
+            port: 3737,
+            unix_socket: None,
+            require_token: false,
+            allowed_origins: Vec::new(),
+            allowed_hosts: Vec::new(),
+        }
+    }
+}
+
+impl ApiConfig {
+    /// The socket path with `~/` expanded.
+    pub fn socket_path(&self) -> Option<PathBuf> {
+        self.unix_socket
+            .as_deref()
+            .filter(|path| !path.is_empty())
+            .map(global::expand_home)
+    }
+
+    /// Address the TCP listener binds to.
+    pub fn tcp_addr(&self) -> String {
+        if self.host.contains(':') && !self.host.starts_with('[') {
+            format!("[{}]:{}", self.host, self.port)
+        } else {
+            format!("{}:{}", self.host, self.port)
+        }
+    }
+
+    /// URL of an endpoint over TCP.
+    pub fn url(&self, path: &str) -> String {
+        format!("http://{}{}", self.tcp_addr(), path)
+    }
+
+    /// Shell command calling an endpoint with curl, over the Unix socket
+    /// when one is configured. With `require_token` the command reads the
+    /// token file when it runs, so the token never ends up in other configs.
+    pub fn curl_command(&self, options: &str, path: &str) -> String {
+        let mut command = String::from("curl");
+        if !options.is_empty() {
+            command.push(' ');
+            command.push_str(options);
+        }
+        if let Some(token_file) = self
+            .require_token
+            .then(global::api_token_file)
+            .and_then(Result::ok)
+        {
+            command.push_str(&format!(
+                " -H \"Authorization: Bearer $(cat {})\"",
+                shell_quote(&token_file.to_string_lossy())
+            ));
+        }
+        let url = match self.socket_path() {
+            Some(socket) => {
+                command.push_str(" --unix-socket ");
+                command.push_str(&shell_quote(&socket.to_string_lossy()));
+                format!("http://localhost{}", path)
+            }
+            None => self.url(path),
+        };
+        command.push(' ');
+        command.push_str(&shell_quote(&url));
+        command
+    }
+}
+
+fn shell_quote(s: &str) -> String {
+    let safe = |c: char| c.is_ascii_alphanumeric() || "/._-:+=@%,".contains(c);
+    if !s.is_empty() && s.chars().all(safe) {
+        s.to_string()
+    } else {
+        format!("'{}'", s.replace('\'', "'\\''"))
+    }
+}
+
+/// How a job's transcript is written out.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DictationMode {
+    /// Regular text through the `[[postprocess]]` chain
+    #[default]
+    Prose,
+    /// Identifiers and symbols for editors and terminals
+    Code,
+}
+
+impl DictationMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DictationMode::Prose => "prose",
+            DictationMode::Code => "code",
+        }
+    }
+}
+
+/// Key combination that pastes the clipboard in clipboard injection mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PasteShortcut {
+    #[serde(rename = "ctrl+v")]
+    CtrlV,
+    /// Terminals such as kitty, foot and Alacritty
+    #[serde(rename = "ctrl+shift+v")]
+    CtrlShiftV,
+}
+
+/// Overrides for recordings started while a matching window has focus
+/// (`[[apps]]`). Unset options keep the global behavior.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppRule {
+    /// Name shown in logs and used for the rule's post-processing chain
+    pub name: String,
+    /// Regex matched against the Hyprland window class
+    pub class: Option<String>,
+    /// Regex matched against the window title
+    pub title: Option<String>,
+    /// "wtype", "ydotool", "xdotool" or "clipboard"
+    pub input_method: Option<String>,
+    pub paste_shortcut: Option<PasteShortcut>,
+    /// Replaces the `[[postprocess]]` chain
+    pub postprocess: Option<Vec<PostProcessStep>>,
+    pub mode: Option<DictationMode>,
+    pub language: Option<String>,
+    /// Press Enter after the text is pasted
+    pub auto_submit: Option<bool>,
+}
+
+/// An output that receives completed transcripts (`[[sinks]]` with a `type` key).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SinkConfig {
+    /// Name used to pick the sink per job
+    pub name: String,
+    /// Run for jobs that don't pick their sinks
+    #[serde(default = "default_sink_enabled")]
+    pub enabled: bool,
+    #[serde(flatten)]
+    pub kind: SinkKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SinkKind {
+    /// Append the rendered template to a file
+    File {
+        path: String,
+        #[serde(default = "default_sink_template")]
+        template: String,
+    },
+    /// Write the rendered template to a named pipe with a reader
+    Fifo {
+        path: String,
+        #[serde(default = "default_sink_template")]
+        template: String,
+    },
+    /// POST the job as JSON
+    Webhook {
+        url: String,
+        #[serde(default)]
+        headers: BTreeMap<String, String>,
+        #[serde(default = "default_webhook_timeout_secs")]
+        timeout_secs: u64,
+    },
+    /// Run a program with the text on stdin and job metadata in
+    /// `WISPRARCH_*` environment variables
+    Command {
+        command: String,
+        #[serde(default)]
+        args: Vec<String>,
+        #[serde(default = "default_sink_command_timeout_secs")]
+        timeout_secs: u64,
+    },
+}
+
+fn default_sink_enabled() -> bool {
+    true
+}
+
+fn default_sink_template() -> String {
+    "{text}\n".to_string()
+}
+
+fn default_webhook_timeout_secs() -> u64 {
+    10
+}
+
+fn default_sink_command_timeout_secs() -> u64 {
+    30
 }
 
 fn default_audio_feedback() -> bool {
@@ -78,6 +385,10 @@ impl Default for WhisperConfig {
             api_endpoint: None,
             provider: Some("groq".to_string()),
             api_key: None,
+            command_args: Vec::new(),
+            command_options: serde_json::Map::new(),
+            timeout_secs: None,
+            itn: false,
         }
     }
 }
@@ -107,16 +418,201 @@ impl Default for WaylandConfig {
     fn default() -> Self {
         Self {
             input_method: "clipboard".to_string(),
+            typing: TypingConfig::default(),
+        }
+    }
+}
+
+/// A configurable post-processing step (`[[postprocess]]` with a `type` key).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PostProcessStep {
+    /// Regex replacement; `$1` etc. refer to capture groups
+    Regex {
+        pattern: String,
+        replacement: String,
+        #[serde(default)]
+        case_insensitive: bool,
+    },
+    /// Remove filler words such as "um" and "uh"
+    Fillers {
+        #[serde(default = "default_filler_words")]
+        words: Vec<String>,
+    },
+    /// Change letter case, then apply the exact casing of `words`
+    Case {
+        #[serde(default)]
+        mode: CaseMode,
+        #[serde(default)]
+        words: Vec<String>,
+    },
+    /// What to do with punctuation at the end of the transcript
+    TrailingPunctuation { policy: TrailingPunctuation },
+    /// Turn spoken "comma", "new line", "open paren"... into symbols
+    SpokenPunctuation {
+        /// Vocabulary language (default: `[whisper].language`)
+        #[serde(default)]
+        language: Option<String>,
+        /// Word that keeps the following phrase literal (default per language)
+        #[serde(default)]
+        escape_word: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CaseMode {
+    #[default]
+    Keep,
+    /// Capitalize the first letter of each sentence
+    Sentence,
+    Lower,
+    Upper,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrailingPunctuation {
+    Keep,
+    /// Strip trailing punctuation (handy for chat and search boxes)
+    Remove,
+    /// End with a period unless the text already ends a sentence
+    Ensure,
+}
+
+pub fn default_filler_words() -> Vec<String> {
+    ["um", "umm", "uh", "uhm", "er", "erm", "ah", "hmm"]
+        .iter()
+        .map(|w| w.to_string())
+        .collect()
+}
+
+/// Rewrite of transcripts by an OpenAI-compatible chat model.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LlmCleanupConfig {
+    pub enabled: bool,
+    /// Chat completions URL (OpenAI, llama.cpp server, Ollama, ...)
+    pub endpoint: String,
+    pub model: String,
+    pub api_key: Option<String>,
+    pub system_prompt: String,
+    /// Keep the unprocessed text if the model doesn't answer in time
+    pub timeout_secs: u64,
+}
+
+impl Default for LlmCleanupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: "http://127.0.0.1:11434/v1/chat/completions".to_string(),
+            model: "llama3.2".to_string(),
+            api_key: None,
+            system_prompt: "You clean up dictated text. Fix grammar, punctuation and \
+                            capitalization, keeping the wording and meaning. Reply with the \
+                            corrected text only."
+                .to_string(),
+            timeout_secs: 10,
         }
     }
 }
 
+/// What to do with a transcript that looks like a hallucination.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HallucinationAction {
+    /// Discard the text
+    #[default]
+    Drop,
+    /// Keep the text in history, but don't copy or paste it
+    Flag,
+}
+
+/// Filter for text models produce from silent or near-silent recordings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HallucinationConfig {
+    pub enabled: bool,
+    pub action: HallucinationAction,
+    /// RMS above which a 20ms frame counts as speech (0.01 is about -40 dBFS)
+    pub speech_rms_threshold: f32,
+    /// Recordings with less speech than this are treated as silent
+    pub min_speech_secs: f32,
+    /// Blocklisted phrases are accepted only with at least this much speech
+    pub blocklist_speech_secs: f32,
+    /// Known hallucinations, matched against the whole transcript
+    /// ignoring case and punctuation
+    pub blocklist: Vec<String>,
+}
+
+impl Default for HallucinationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            action: HallucinationAction::Drop,
+            speech_rms_threshold: 0.01,
+            min_speech_secs: 0.3,
+            blocklist_speech_secs: 1.5,
+            blocklist: default_hallucination_blocklist(),
+        }
+    }
+}
+
+pub fn default_hallucination_blocklist() -> Vec<String> {
+    [
+        "thanks for watching",
+        "thank you for watching",
+        "thank you for watching please subscribe",
+        "thanks for watching and don't forget to subscribe",
+        "please subscribe",
+        "like and subscribe",
+        "subtitles by the amara org community",
+    ]
+    .iter()
+    .map(|w| w.to_string())
+    .collect()
+}
+
+/// Period a usage quota applies to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QuotaPeriod {
+    Day,
+    #[default]
+    Month,
+}
+
+/// Usage tracking, pricing and quotas for transcription providers.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UsageConfig {
+    /// Price in USD per audio minute, keyed by `provider` or `provider/model`
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub prices: BTreeMap<String, f64>,
+    pub quota_period: QuotaPeriod,
+    /// Warn once this many audio minutes were used in the period
+    pub soft_quota_minutes: Option<f64>,
+    /// Stop using the cloud provider after this many audio minutes
+    pub hard_quota_minutes: Option<f64>,
+    /// Warn once the estimated cost in the period exceeds this amount
+    pub soft_quota_usd: Option<f64>,
+    /// Stop using the cloud provider once the estimated cost exceeds this amount
+    pub hard_quota_usd: Option<f64>,
+    /// Profile from `[profiles]` to use once a hard quota is exceeded
+    pub fallback_profile: Option<String>,
+}
+
 impl Default for BehaviorConfig {
     fn default() -> Self {
         Self {
             auto_paste: true,
             delete_audio_files: true,
             audio_feedback: true,
+            voice_commands: false,
+            restore_clipboard: false,
+            restore_clipboard_delay_ms: 500,
+            clipboard_target: ClipboardTarget::Clipboard,
+            clipboard_sensitive: false,
         }
     }
 }
@@ -29,6 +29,8 @@ pub fn migrate(conn: &Connection) -> Result<()> {
     )
     .context("Failed to create workflows table")?;
 
+    add_column_if_missing(conn, "workflows", "raw_text", "TEXT")?;
+
     // Create index for faster text searches
     conn.execute(
         "CREATE INDEX IF NOT EXISTS idx_workflows_created_at ON workflows(created_at DESC)",
@@ -36,5 +38,71 @@ pub fn migrate(conn: &Connection) -> Result<()> {
     )
     .context("Failed to create index on created_at")?;
 
+    // Recordings whose transcription failed while offline, retried later
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS pending_jobs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            job_id TEXT NOT NULL,
+            audio_path TEXT NOT NULL,
+            options TEXT NOT NULL,
+            error TEXT NOT NULL,
+            attempts INTEGER NOT NULL DEFAULT 0,
+            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+            last_attempt_at TIMESTAMP
+        )",
+        [],
+    )
+    .context("Failed to create pending_jobs table")?;
+
+    // Set while a retry runs, so the daemon and `wisprarch jobs retry`
+    // don't both deliver the same job
+    add_column_if_missing(conn, "pending_jobs", "claimed_at", "TIMESTAMP")?;
+
+    // One row per transcription request, used for usage and cost reporting
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS usage_events (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            provider TEXT NOT NULL,
+            model TEXT,
+            audio_secs REAL NOT NULL,
+            success INTEGER NOT NULL,
+            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )
+    .context("Failed to create usage_events table")?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_usage_events_created_at ON usage_events(created_at)",
+        [],
+    )
+    .context("Failed to create index on usage_events")?;
+
+    Ok(())
+}
+
+/// Add a column to a table created by an older version.
+fn add_column_if_missing(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> Result<()> {
+    let exists = conn
+        .prepare(&format!(
+            "SELECT 1 FROM pragma_table_info('{}') WHERE name = ?1",
+            table
+        ))?
+        .exists([column])
+        .with_context(|| format!("Failed to inspect table {}", table))?;
+
+    if !exists {
+        conn.execute(
+            &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+            [],
+        )
+        .with_context(|| format!("Failed to add column {}.{}", table, column))?;
+    }
+
     Ok(())
 }
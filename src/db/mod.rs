@@ -8,7 +8,11 @@ mod tests;
 // Re-export public API
 pub use init::{init_db, migrate};
 pub use operations::{
-    count_workflows, get_recent_workflows, get_workflow_by_id, insert_workflow,
-    prune_old_workflows, search_workflows,
+    claim_pending_job, count_workflows, delete_pending_job, get_pending_job_by_id,
+    get_pending_jobs, get_recent_workflows, get_usage_rollup, get_workflow_by_id,
+    insert_pending_job, insert_usage_event, insert_workflow, prune_old_workflows,
+    record_pending_job_attempt, search_workflows,
+};
+pub use schemas::{
+    PendingJob, UsageEvent, UsageRollupRow, VoiceToTextData, Workflow, WorkflowData, WorkflowType,
 };
-pub use schemas::{VoiceToTextData, Workflow, WorkflowData, WorkflowType};
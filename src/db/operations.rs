@@ -1,19 +1,21 @@
 use anyhow::{Context, Result};
 use rusqlite::Connection;
 
-use super::schemas::{VoiceToTextData, Workflow, WorkflowData, WorkflowType};
+use super::schemas::{
+    PendingJob, UsageEvent, UsageRollupRow, VoiceToTextData, Workflow, WorkflowData, WorkflowType,
+};
 
 pub fn insert_workflow(conn: &Connection, workflow: &Workflow) -> Result<i64> {
     let (workflow_type_str, _json_data) = workflow.to_row()?;
 
     // Extract text and audio_path from the workflow data
-    let (text, audio_path) = match &workflow.data {
-        WorkflowData::VoiceToText(data) => (&data.text, &data.audio_path),
+    let (text, audio_path, raw_text) = match &workflow.data {
+        WorkflowData::VoiceToText(data) => (&data.text, &data.audio_path, &data.raw_text),
     };
 
     conn.execute(
-        "INSERT INTO workflows (workflow_type, text, audio_path) VALUES (?1, ?2, ?3)",
-        rusqlite::params![workflow_type_str, text, audio_path],
+        "INSERT INTO workflows (workflow_type, text, audio_path, raw_text) VALUES (?1, ?2, ?3, ?4)",
+        rusqlite::params![workflow_type_str, text, audio_path, raw_text],
     )
     .context("Failed to insert workflow")?;
 
@@ -22,7 +24,7 @@ pub fn insert_workflow(conn: &Connection, workflow: &Workflow) -> Result<i64> {
 
 pub fn get_recent_workflows(conn: &Connection, limit: usize) -> Result<Vec<Workflow>> {
     let mut stmt = conn
-        .prepare("SELECT id, workflow_type, text, audio_path, created_at FROM workflows ORDER BY created_at DESC LIMIT ?1")
+        .prepare("SELECT id, workflow_type, text, audio_path, created_at, raw_text FROM workflows ORDER BY created_at DESC LIMIT ?1")
         .context("Failed to prepare query")?;
 
     let workflows = stmt
@@ -32,9 +34,14 @@ pub fn get_recent_workflows(conn: &Connection, limit: usize) -> Result<Vec<Workf
             let text: String = row.get(2)?;
             let audio_path: String = row.get(3)?;
             let created_at: String = row.get(4)?;
+            let raw_text: Option<String> = row.get(5)?;
 
             // Reconstruct the WorkflowData from the database fields
-            let data = WorkflowData::VoiceToText(VoiceToTextData { text, audio_path });
+            let data = WorkflowData::VoiceToText(VoiceToTextData {
+                text,
+                audio_path,
+                raw_text,
+            });
 
             let workflow_type_enum =
                 WorkflowType::parse(&workflow_type).map_err(|_| rusqlite::Error::InvalidQuery)?;
@@ -65,7 +72,7 @@ pub fn count_workflows(conn: &Connection) -> Result<i64> {
 pub fn get_workflow_by_id(conn: &Connection, id: i64) -> Result<Option<Workflow>> {
     let mut stmt = conn
         .prepare(
-            "SELECT id, workflow_type, text, audio_path, created_at FROM workflows WHERE id = ?1",
+            "SELECT id, workflow_type, text, audio_path, created_at, raw_text FROM workflows WHERE id = ?1",
         )
         .context("Failed to prepare get_workflow_by_id query")?;
 
@@ -79,8 +86,13 @@ pub fn get_workflow_by_id(conn: &Connection, id: i64) -> Result<Option<Workflow>
         let text: String = row.get(2)?;
         let audio_path: String = row.get(3)?;
         let created_at: String = row.get(4)?;
+        let raw_text: Option<String> = row.get(5)?;
 
-        let data = WorkflowData::VoiceToText(VoiceToTextData { text, audio_path });
+        let data = WorkflowData::VoiceToText(VoiceToTextData {
+            text,
+            audio_path,
+            raw_text,
+        });
 
         let workflow_type_enum = WorkflowType::parse(&workflow_type)
             .map_err(|e| anyhow::anyhow!("Invalid workflow type: {}", e))?;
@@ -124,8 +136,9 @@ pub fn search_workflows(
     date_to: Option<&str>,
     limit: usize,
 ) -> Result<Vec<Workflow>> {
-    let mut sql = "SELECT id, workflow_type, text, audio_path, created_at FROM workflows WHERE 1=1"
-        .to_string();
+    let mut sql =
+        "SELECT id, workflow_type, text, audio_path, created_at, raw_text FROM workflows WHERE 1=1"
+            .to_string();
     let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
 
     if let Some(q) = query {
@@ -159,8 +172,13 @@ pub fn search_workflows(
             let text: String = row.get(2)?;
             let audio_path: String = row.get(3)?;
             let created_at: String = row.get(4)?;
+            let raw_text: Option<String> = row.get(5)?;
 
-            let data = WorkflowData::VoiceToText(VoiceToTextData { text, audio_path });
+            let data = WorkflowData::VoiceToText(VoiceToTextData {
+                text,
+                audio_path,
+                raw_text,
+            });
 
             let workflow_type_enum =
                 WorkflowType::parse(&workflow_type).map_err(|_| rusqlite::Error::InvalidQuery)?;
@@ -178,3 +196,162 @@ pub fn search_workflows(
 
     Ok(workflows)
 }
+
+const PENDING_JOB_COLUMNS: &str =
+    "id, job_id, audio_path, options, error, attempts, created_at, last_attempt_at";
+
+fn pending_job_from_row(row: &rusqlite::Row) -> rusqlite::Result<PendingJob> {
+    Ok(PendingJob {
+        id: Some(row.get(0)?),
+        job_id: row.get(1)?,
+        audio_path: row.get(2)?,
+        options: row.get(3)?,
+        error: row.get(4)?,
+        attempts: row.get(5)?,
+        created_at: row.get(6)?,
+        last_attempt_at: row.get(7)?,
+    })
+}
+
+pub fn insert_pending_job(conn: &Connection, job: &PendingJob) -> Result<i64> {
+    conn.execute(
+        "INSERT INTO pending_jobs (job_id, audio_path, options, error, attempts) VALUES (?1, ?2, ?3, ?4, ?5)",
+        rusqlite::params![job.job_id, job.audio_path, job.options, job.error, job.attempts],
+    )
+    .context("Failed to insert pending job")?;
+
+    Ok(conn.last_insert_rowid())
+}
+
+/// Get all pending jobs, oldest first.
+pub fn get_pending_jobs(conn: &Connection) -> Result<Vec<PendingJob>> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM pending_jobs ORDER BY id ASC",
+            PENDING_JOB_COLUMNS
+        ))
+        .context("Failed to prepare pending jobs query")?;
+
+    let jobs = stmt
+        .query_map([], pending_job_from_row)
+        .context("Failed to query pending jobs")?
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .context("Failed to map pending jobs")?;
+
+    Ok(jobs)
+}
+
+pub fn get_pending_job_by_id(conn: &Connection, id: i64) -> Result<Option<PendingJob>> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM pending_jobs WHERE id = ?1",
+            PENDING_JOB_COLUMNS
+        ))
+        .context("Failed to prepare get_pending_job_by_id query")?;
+
+    let mut rows = stmt
+        .query([id])
+        .context("Failed to execute get_pending_job_by_id query")?;
+
+    match rows.next().context("Failed to fetch row")? {
+        Some(row) => Ok(Some(pending_job_from_row(row)?)),
+        None => Ok(None),
+    }
+}
+
+/// Claim a pending job for a retry. Returns false if another retry holds a
+/// claim younger than `stale_after_secs`.
+pub fn claim_pending_job(conn: &Connection, id: i64, stale_after_secs: u64) -> Result<bool> {
+    let claimed = conn
+        .execute(
+            "UPDATE pending_jobs SET claimed_at = CURRENT_TIMESTAMP
+             WHERE id = ?1 AND (claimed_at IS NULL OR claimed_at < datetime('now', ?2))",
+            rusqlite::params![id, format!("-{} seconds", stale_after_secs)],
+        )
+        .context("Failed to claim pending job")?;
+
+    Ok(claimed > 0)
+}
+
+/// Record a failed retry attempt with its error and release the claim.
+pub fn record_pending_job_attempt(conn: &Connection, id: i64, error: &str) -> Result<()> {
+    conn.execute(
+        "UPDATE pending_jobs SET attempts = attempts + 1, error = ?2, last_attempt_at = CURRENT_TIMESTAMP, claimed_at = NULL WHERE id = ?1",
+        rusqlite::params![id, error],
+    )
+    .context("Failed to update pending job")?;
+
+    Ok(())
+}
+
+/// Delete a pending job. Returns true if a row was removed.
+pub fn delete_pending_job(conn: &Connection, id: i64) -> Result<bool> {
+    let deleted = conn
+        .execute("DELETE FROM pending_jobs WHERE id = ?1", [id])
+        .context("Failed to delete pending job")?;
+
+    Ok(deleted > 0)
+}
+
+pub fn insert_usage_event(conn: &Connection, event: &UsageEvent) -> Result<i64> {
+    conn.execute(
+        "INSERT INTO usage_events (provider, model, audio_secs, success) VALUES (?1, ?2, ?3, ?4)",
+        rusqlite::params![event.provider, event.model, event.audio_secs, event.success],
+    )
+    .context("Failed to insert usage event")?;
+
+    Ok(conn.last_insert_rowid())
+}
+
+/// Aggregate usage by period, provider and model.
+///
+/// `period_format` is an SQLite `strftime` format such as `%Y-%m-%d`. Periods
+/// use local time; `since` (YYYY-MM-DD) and `provider` narrow the events.
+pub fn get_usage_rollup(
+    conn: &Connection,
+    period_format: &str,
+    since: Option<&str>,
+    provider: Option<&str>,
+) -> Result<Vec<UsageRollupRow>> {
+    let mut sql = "SELECT strftime(?, created_at, 'localtime') AS period, provider, model,
+                COUNT(*), SUM(CASE WHEN success THEN 0 ELSE 1 END),
+                COALESCE(SUM(CASE WHEN success THEN audio_secs ELSE 0 END), 0)
+         FROM usage_events WHERE 1=1"
+        .to_string();
+    let mut params: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(period_format.to_string())];
+
+    if let Some(since) = since {
+        sql.push_str(" AND date(created_at, 'localtime') >= ?");
+        params.push(Box::new(since.to_string()));
+    }
+
+    if let Some(provider) = provider {
+        sql.push_str(" AND provider = ?");
+        params.push(Box::new(provider.to_string()));
+    }
+
+    sql.push_str(" GROUP BY period, provider, model ORDER BY period DESC, provider, model");
+
+    let mut stmt = conn
+        .prepare(&sql)
+        .context("Failed to prepare usage query")?;
+
+    let param_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+
+    let rows = stmt
+        .query_map(param_refs.as_slice(), |row| {
+            Ok(UsageRollupRow {
+                period: row.get(0)?,
+                provider: row.get(1)?,
+                model: row.get(2)?,
+                requests: row.get(3)?,
+                failures: row.get(4)?,
+                audio_secs: row.get(5)?,
+            })
+        })
+        .context("Failed to query usage")?
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .context("Failed to map usage rows")?;
+
+    Ok(rows)
+}
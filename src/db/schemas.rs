@@ -5,6 +5,9 @@ use serde::{Deserialize, Serialize};
 pub struct VoiceToTextData {
     pub text: String,
     pub audio_path: String,
+    /// Transcript before post-processing, when it differs from `text`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_text: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -73,3 +76,54 @@ impl Workflow {
         }
     }
 }
+
+/// A transcription job waiting to be retried.
+#[derive(Debug, Clone)]
+pub struct PendingJob {
+    pub id: Option<i64>,
+    pub job_id: String,
+    pub audio_path: String,
+    /// Serialized `JobOptions` JSON
+    pub options: String,
+    pub error: String,
+    pub attempts: i64,
+    pub created_at: Option<String>,
+    pub last_attempt_at: Option<String>,
+}
+
+impl PendingJob {
+    pub fn new(job_id: String, audio_path: String, options: String, error: String) -> Self {
+        PendingJob {
+            id: None,
+            job_id,
+            audio_path,
+            options,
+            error,
+            attempts: 0,
+            created_at: None,
+            last_attempt_at: None,
+        }
+    }
+}
+
+/// A single transcription request made to a provider.
+#[derive(Debug, Clone)]
+pub struct UsageEvent {
+    pub provider: String,
+    pub model: Option<String>,
+    pub audio_secs: f64,
+    pub success: bool,
+}
+
+/// Usage aggregated per period, provider and model.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UsageRollupRow {
+    /// Period key, e.g. `2025-01-15` or `2025-01`
+    pub period: String,
+    pub provider: String,
+    pub model: Option<String>,
+    pub requests: i64,
+    pub failures: i64,
+    /// Audio seconds of successful requests
+    pub audio_secs: f64,
+}
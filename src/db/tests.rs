@@ -1,6 +1,8 @@
 use super::init::migrate;
 use super::operations::*;
-use super::schemas::{VoiceToTextData, Workflow, WorkflowData, WorkflowType};
+use super::schemas::{
+    PendingJob, UsageEvent, VoiceToTextData, Workflow, WorkflowData, WorkflowType,
+};
 use anyhow::Result;
 use rusqlite::Connection;
 
@@ -16,6 +18,7 @@ fn create_test_workflow(text: &str) -> Workflow {
         WorkflowData::VoiceToText(VoiceToTextData {
             text: text.to_string(),
             audio_path: "/tmp/test.wav".to_string(),
+            raw_text: None,
         }),
     )
 }
@@ -150,6 +153,52 @@ fn test_search_workflows_limit() {
     assert_eq!(results.len(), 5);
 }
 
+#[test]
+fn test_workflow_raw_text_round_trip() {
+    let conn = setup_test_db().unwrap();
+    let workflow = Workflow::new(
+        WorkflowType::VoiceToText,
+        WorkflowData::VoiceToText(VoiceToTextData {
+            text: "Cleaned text.".to_string(),
+            audio_path: "/tmp/test.wav".to_string(),
+            raw_text: Some("cleaned text".to_string()),
+        }),
+    );
+    let id = insert_workflow(&conn, &workflow).unwrap();
+    insert_workflow(&conn, &create_test_workflow("No cleanup")).unwrap();
+
+    let WorkflowData::VoiceToText(data) = get_workflow_by_id(&conn, id).unwrap().unwrap().data;
+    assert_eq!(data.text, "Cleaned text.");
+    assert_eq!(data.raw_text.as_deref(), Some("cleaned text"));
+
+    let results = search_workflows(&conn, Some("No cleanup"), None, None, 10).unwrap();
+    let WorkflowData::VoiceToText(data) = &results[0].data;
+    assert!(data.raw_text.is_none());
+}
+
+#[test]
+fn test_migrate_adds_raw_text_to_old_table() {
+    let conn = Connection::open_in_memory().unwrap();
+    conn.execute(
+        "CREATE TABLE workflows (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            workflow_type TEXT NOT NULL,
+            text TEXT NOT NULL,
+            audio_path TEXT NOT NULL,
+            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )
+    .unwrap();
+
+    migrate(&conn).unwrap();
+    // Running again must not try to add the column twice
+    migrate(&conn).unwrap();
+
+    let id = insert_workflow(&conn, &create_test_workflow("Old")).unwrap();
+    assert!(get_workflow_by_id(&conn, id).unwrap().is_some());
+}
+
 #[test]
 fn test_workflow_serialization() {
     let workflow = create_test_workflow("Test text");
@@ -178,3 +227,109 @@ fn test_workflow_from_row() {
     assert_eq!(data.text, "Test");
     assert_eq!(data.audio_path, "/tmp/test.wav");
 }
+
+fn create_test_pending_job(job_id: &str) -> PendingJob {
+    PendingJob::new(
+        job_id.to_string(),
+        "/tmp/pending.wav".to_string(),
+        r#"{"copy_to_clipboard":true,"auto_paste":false}"#.to_string(),
+        "Failed to send request to Groq API".to_string(),
+    )
+}
+
+#[test]
+fn test_pending_job_round_trip() {
+    let conn = setup_test_db().unwrap();
+
+    let first = insert_pending_job(&conn, &create_test_pending_job("job-1")).unwrap();
+    let second = insert_pending_job(&conn, &create_test_pending_job("job-2")).unwrap();
+
+    let jobs = get_pending_jobs(&conn).unwrap();
+    assert_eq!(jobs.len(), 2);
+    assert_eq!(jobs[0].id, Some(first));
+    assert_eq!(jobs[0].job_id, "job-1");
+    assert_eq!(jobs[0].attempts, 0);
+    assert!(jobs[0].last_attempt_at.is_none());
+
+    let job = get_pending_job_by_id(&conn, second).unwrap().unwrap();
+    assert_eq!(job.job_id, "job-2");
+    assert!(get_pending_job_by_id(&conn, 999).unwrap().is_none());
+}
+
+#[test]
+fn test_pending_job_attempt_and_delete() {
+    let conn = setup_test_db().unwrap();
+    let id = insert_pending_job(&conn, &create_test_pending_job("job-1")).unwrap();
+
+    record_pending_job_attempt(&conn, id, "still offline").unwrap();
+
+    let job = get_pending_job_by_id(&conn, id).unwrap().unwrap();
+    assert_eq!(job.attempts, 1);
+    assert_eq!(job.error, "still offline");
+    assert!(job.last_attempt_at.is_some());
+
+    assert!(delete_pending_job(&conn, id).unwrap());
+    assert!(!delete_pending_job(&conn, id).unwrap());
+    assert!(get_pending_jobs(&conn).unwrap().is_empty());
+}
+
+#[test]
+fn test_pending_job_claim_is_exclusive() {
+    let conn = setup_test_db().unwrap();
+    let id = insert_pending_job(&conn, &create_test_pending_job("job-1")).unwrap();
+
+    assert!(claim_pending_job(&conn, id, 600).unwrap());
+    assert!(!claim_pending_job(&conn, id, 600).unwrap());
+
+    // A failed attempt releases the claim
+    record_pending_job_attempt(&conn, id, "still offline").unwrap();
+    assert!(claim_pending_job(&conn, id, 600).unwrap());
+
+    // A claim left behind by a crashed retry expires
+    conn.execute(
+        "UPDATE pending_jobs SET claimed_at = datetime('now', '-1 hour') WHERE id = ?1",
+        [id],
+    )
+    .unwrap();
+    assert!(claim_pending_job(&conn, id, 600).unwrap());
+    assert!(!claim_pending_job(&conn, 999, 600).unwrap());
+}
+
+fn usage_event(provider: &str, model: Option<&str>, audio_secs: f64, success: bool) -> UsageEvent {
+    UsageEvent {
+        provider: provider.to_string(),
+        model: model.map(str::to_string),
+        audio_secs,
+        success,
+    }
+}
+
+#[test]
+fn test_usage_rollup_groups_by_provider_and_model() {
+    let conn = setup_test_db().unwrap();
+
+    insert_usage_event(&conn, &usage_event("groq", Some("turbo"), 30.0, true)).unwrap();
+    insert_usage_event(&conn, &usage_event("groq", Some("turbo"), 15.0, true)).unwrap();
+    insert_usage_event(&conn, &usage_event("groq", Some("turbo"), 20.0, false)).unwrap();
+    insert_usage_event(&conn, &usage_event("parakeet-v3", None, 10.0, true)).unwrap();
+
+    let rows = get_usage_rollup(&conn, "%Y-%m", None, None).unwrap();
+    assert_eq!(rows.len(), 2);
+
+    let groq = rows.iter().find(|r| r.provider == "groq").unwrap();
+    assert_eq!(groq.model.as_deref(), Some("turbo"));
+    assert_eq!(groq.requests, 3);
+    assert_eq!(groq.failures, 1);
+    assert_eq!(groq.audio_secs, 45.0);
+    assert_eq!(
+        groq.period,
+        chrono::Local::now().format("%Y-%m").to_string()
+    );
+
+    let filtered = get_usage_rollup(&conn, "%Y-%m-%d", None, Some("parakeet-v3")).unwrap();
+    assert_eq!(filtered.len(), 1);
+    assert_eq!(filtered[0].audio_secs, 10.0);
+
+    let future = get_usage_rollup(&conn, "%Y-%m-%d", Some("2999-01-01"), None).unwrap();
+    assert!(future.is_empty());
+}
@@ -0,0 +1,153 @@
+//! Daemon events.
+//!
+//! The recording status handle publishes phase transitions, job lifecycle,
+//! throttled audio levels and provider changes on a broadcast channel.
+//! `GET /events` streams them to status bars and scripts as Server-Sent
+//! Events, so they can react without polling `/status`.
+
+use crate::audio::{CompletedJob, JobOptions, RecordingPhase, NUM_BANDS};
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+
+/// Events a slow subscriber can fall behind by before it misses some.
+const CHANNEL_CAPACITY: usize = 256;
+
+/// Minimum time between two audio level events.
+pub const AUDIO_EVENT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Something that happened in the daemon.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DaemonEvent {
+    /// The recording phase changed
+    Phase { phase: RecordingPhase },
+    /// A recording started
+    JobStarted { job_id: String, options: JobOptions },
+    /// A job was transcribed and delivered
+    JobCompleted(CompletedJob),
+    /// Recording or transcription failed
+    JobFailed {
+        job_id: Option<String>,
+        error: String,
+    },
+    /// Input level and frequency bands while recording
+    AudioLevel { level: f32, bands: [f32; NUM_BANDS] },
+    /// Transcription moved to another provider, e.g. the quota fallback
+    ProviderChanged {
+        provider: String,
+        previous: Option<String>,
+    },
+}
+
+impl DaemonEvent {
+    /// The SSE event name, the same as the `type` field.
+    pub fn name(&self) -> &'static str {
+        match self {
+            DaemonEvent::Phase { .. } => "phase",
+            DaemonEvent::JobStarted { .. } => "job_started",
+            DaemonEvent::JobCompleted(_) => "job_completed",
+            DaemonEvent::JobFailed { .. } => "job_failed",
+            DaemonEvent::AudioLevel { .. } => "audio_level",
+            DaemonEvent::ProviderChanged { .. } => "provider_changed",
+        }
+    }
+}
+
+/// Broadcast channel for daemon events.
+#[derive(Debug, Clone)]
+pub struct EventBus {
+    tx: broadcast::Sender<DaemonEvent>,
+    last_audio_event: Arc<Mutex<Option<Instant>>>,
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        let (tx, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self {
+            tx,
+            last_audio_event: Arc::new(Mutex::new(None)),
+        }
+    }
+}
+
+impl EventBus {
+    pub fn subscribe(&self) -> broadcast::Receiver<DaemonEvent> {
+        self.tx.subscribe()
+    }
+
+    /// Publish an event. Without subscribers it is dropped.
+    pub fn publish(&self, event: DaemonEvent) {
+        let _ = self.tx.send(event);
+    }
+
+    /// Publish an audio level event unless one was sent less than
+    /// `AUDIO_EVENT_INTERVAL` ago.
+    pub fn publish_audio_level(&self, level: f32, bands: [f32; NUM_BANDS]) {
+        if self.tx.receiver_count() == 0 {
+            return;
+        }
+
+        let now = Instant::now();
+        {
+            let mut last = self
+                .last_audio_event
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            if last.is_some_and(|last| now.duration_since(last) < AUDIO_EVENT_INTERVAL) {
+                return;
+            }
+            *last = Some(now);
+        }
+        self.publish(DaemonEvent::AudioLevel { level, bands });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_json_has_type() {
+        let event = DaemonEvent::Phase {
+            phase: RecordingPhase::Recording,
+        };
+        assert_eq!(event.name(), "phase");
+        assert_eq!(
+            serde_json::to_value(&event).unwrap(),
+            serde_json::json!({ "type": "phase", "phase": "recording" })
+        );
+
+        let event = DaemonEvent::JobCompleted(CompletedJob {
+            job_id: "job-1".to_string(),
+            history_id: 3,
+            text: "hello".to_string(),
+            created_at: "2026-03-03T09:30:00Z".to_string(),
+            injected: true,
+        });
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["type"], event.name());
+        assert_eq!(json["text"], "hello");
+    }
+
+    #[tokio::test]
+    async fn test_audio_levels_are_throttled() {
+        let bus = EventBus::default();
+        let mut rx = bus.subscribe();
+        bus.publish_audio_level(0.5, [0.0; NUM_BANDS]);
+        bus.publish_audio_level(0.6, [0.0; NUM_BANDS]);
+        bus.publish(DaemonEvent::Phase {
+            phase: RecordingPhase::Processing,
+        });
+
+        assert!(matches!(
+            rx.recv().await.unwrap(),
+            DaemonEvent::AudioLevel { level, .. } if level == 0.5
+        ));
+        assert!(matches!(
+            rx.recv().await.unwrap(),
+            DaemonEvent::Phase { .. }
+        ));
+    }
+}
@@ -23,6 +23,14 @@ pub fn data_dir() -> Result<PathBuf> {
     Err(anyhow!("Unable to determine data directory"))
 }
 
+/// Expand a leading `~/` to the home directory.
+pub fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
 pub fn updates_dir() -> Result<PathBuf> {
     Ok(data_dir()?.join("updates"))
 }
@@ -35,6 +43,10 @@ pub fn update_lock_file() -> Result<PathBuf> {
     Ok(data_dir()?.join("update.lock"))
 }
 
+pub fn api_token_file() -> Result<PathBuf> {
+    Ok(data_dir()?.join("api-token"))
+}
+
 pub fn db_file() -> Result<PathBuf> {
     Ok(data_dir()?.join("wisprarch.db"))
 }
@@ -3,9 +3,14 @@
 //! This module provides the core business logic for searching, retrieving,
 //! and managing transcription history. It is used by both the CLI and REST API.
 
-use crate::db::{self, Workflow, WorkflowData};
+use crate::db::{self, VoiceToTextData, Workflow, WorkflowData, WorkflowType};
 use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tracing::{debug, info};
+
+/// Maximum number of transcriptions kept in history.
+const MAX_HISTORY_ENTRIES: i64 = 10_000;
 
 /// Parameters for searching transcription history.
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
@@ -55,18 +60,22 @@ impl SearchParams {
 pub struct HistoryEntry {
     pub id: i64,
     pub text: String,
+    /// Transcript before post-processing, when it differs from `text`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw_text: Option<String>,
     pub audio_path: String,
     pub created_at: String,
 }
 
 impl From<Workflow> for HistoryEntry {
     fn from(workflow: Workflow) -> Self {
-        let (text, audio_path) = match workflow.data {
-            WorkflowData::VoiceToText(data) => (data.text, data.audio_path),
+        let (text, audio_path, raw_text) = match workflow.data {
+            WorkflowData::VoiceToText(data) => (data.text, data.audio_path, data.raw_text),
         };
         Self {
             id: workflow.id.unwrap_or(0),
             text,
+            raw_text,
             audio_path,
             created_at: workflow.created_at.unwrap_or_else(|| "Unknown".to_string()),
         }
@@ -117,6 +126,42 @@ pub fn get_text_by_id(id: i64) -> Result<String> {
         .ok_or_else(|| anyhow!("Workflow with ID {} not found", id))
 }
 
+/// Get the transcript of an entry before post-processing.
+///
+/// Falls back to the final text for entries that weren't changed.
+pub fn get_raw_text_by_id(id: i64) -> Result<String> {
+    get_by_id(id)?
+        .map(|entry| entry.raw_text.unwrap_or(entry.text))
+        .ok_or_else(|| anyhow!("Workflow with ID {} not found", id))
+}
+
+/// Save a transcription to history and return its ID.
+///
+/// `raw_text` is the transcript before post-processing; it is only stored
+/// when it differs from `text`. Prunes the oldest entries once the history
+/// exceeds its size limit.
+pub fn save(text: &str, raw_text: Option<&str>, audio_path: &Path) -> Result<i64> {
+    let conn = db::init_db()?;
+
+    let workflow_data = WorkflowData::VoiceToText(VoiceToTextData {
+        text: text.to_string(),
+        audio_path: audio_path.to_string_lossy().to_string(),
+        raw_text: raw_text.filter(|raw| *raw != text).map(str::to_string),
+    });
+
+    let workflow = Workflow::new(WorkflowType::VoiceToText, workflow_data);
+
+    let id = db::insert_workflow(&conn, &workflow)?;
+    debug!("Saved transcription to database with ID: {}", id);
+
+    let pruned = db::prune_old_workflows(&conn, MAX_HISTORY_ENTRIES)?;
+    if pruned > 0 {
+        info!("Pruned {} old transcriptions from database", pruned);
+    }
+
+    Ok(id)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,300 @@
+//! Offline queue for failed cloud transcriptions.
+//!
+//! When a cloud provider can't be reached, the recording is kept and stored as
+//! a pending job. The service retries pending jobs in the background and they
+//! can be managed manually with `wisprarch jobs`.
+
+use crate::audio::{JobOptions, JobRetrier};
+use crate::db::{self, PendingJob};
+use anyhow::{Context, Result};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info, warn};
+
+/// How often the service retries pending jobs.
+pub const RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Jobs that failed this many times are only retried manually.
+pub const MAX_AUTO_RETRY_ATTEMPTS: i64 = 20;
+
+/// A pending transcription job.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedJob {
+    pub id: i64,
+    pub job_id: String,
+    pub audio_path: String,
+    pub options: JobOptions,
+    pub error: String,
+    pub attempts: i64,
+    pub created_at: String,
+    pub last_attempt_at: Option<String>,
+}
+
+impl From<PendingJob> for QueuedJob {
+    fn from(job: PendingJob) -> Self {
+        let options = serde_json::from_str(&job.options).unwrap_or_else(|e| {
+            warn!("Invalid options for pending job {:?}: {}", job.id, e);
+            JobOptions::default()
+        });
+        Self {
+            id: job.id.unwrap_or(0),
+            job_id: job.job_id,
+            audio_path: job.audio_path,
+            options,
+            error: job.error,
+            attempts: job.attempts,
+            created_at: job.created_at.unwrap_or_else(|| "Unknown".to_string()),
+            last_attempt_at: job.last_attempt_at,
+        }
+    }
+}
+
+/// Result of retrying a pending job.
+#[derive(Debug, Clone)]
+pub enum RetryOutcome {
+    /// Transcribed, delivered and saved to history; the job was removed
+    Completed { history_id: i64, text: String },
+    /// The recording contained no speech; the job was removed
+    NoSpeech,
+    /// Another retry of the job is running
+    Busy,
+    /// The recording no longer exists; the job was removed
+    Missing,
+    /// The attempt failed; the job stays queued
+    Failed { error: String, offline: bool },
+}
+
+/// Whether an error means the provider could not be reached at all.
+///
+/// Only connection failures and timeouts count; API errors such as invalid
+/// credentials are reported to the user instead of being queued.
+pub fn is_offline_error(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        cause
+            .downcast_ref::<reqwest::Error>()
+            .map(|e| e.is_connect() || e.is_timeout())
+            .unwrap_or(false)
+    })
+}
+
+/// Store a failed recording as a pending job and return its ID.
+pub fn enqueue(job_id: &str, audio_path: &Path, options: &JobOptions, error: &str) -> Result<i64> {
+    let conn = db::init_db()?;
+    let job = PendingJob::new(
+        job_id.to_string(),
+        audio_path.to_string_lossy().to_string(),
+        serde_json::to_string(options).context("Failed to encode job options")?,
+        error.to_string(),
+    );
+    db::insert_pending_job(&conn, &job)
+}
+
+/// List all pending jobs, oldest first.
+pub fn list() -> Result<Vec<QueuedJob>> {
+    let conn = db::init_db()?;
+    let jobs = db::get_pending_jobs(&conn)?;
+    Ok(jobs.into_iter().map(QueuedJob::from).collect())
+}
+
+/// Get a pending job by ID.
+pub fn get(id: i64) -> Result<Option<QueuedJob>> {
+    let conn = db::init_db()?;
+    Ok(db::get_pending_job_by_id(&conn, id)?.map(QueuedJob::from))
+}
+
+/// Remove a pending job and optionally delete its recording.
+///
+/// Returns false if no job with this ID exists.
+pub fn remove(id: i64, delete_audio: bool) -> Result<bool> {
+    let conn = db::init_db()?;
+    let Some(job) = db::get_pending_job_by_id(&conn, id)? else {
+        return Ok(false);
+    };
+
+    db::delete_pending_job(&conn, id)?;
+    if delete_audio {
+        delete_audio_file(Path::new(&job.audio_path));
+    }
+    Ok(true)
+}
+
+/// Claims older than this are left over from a retry that never finished.
+const CLAIM_TIMEOUT_SECS: u64 = 600;
+
+/// Claim a job for a retry. Returns false if another retry is running it.
+pub async fn claim(id: i64) -> Result<bool> {
+    run_blocking(move || db::claim_pending_job(&db::init_db()?, id, CLAIM_TIMEOUT_SECS)).await
+}
+
+/// Record a failed attempt, release the claim and report it as an outcome.
+pub async fn fail(id: i64, error: String, offline: bool) -> Result<RetryOutcome> {
+    record_failure(id, &error).await?;
+    Ok(RetryOutcome::Failed { error, offline })
+}
+
+/// Remove a job whose recording no longer exists, since no retry of it can
+/// succeed. Returns whether it was removed.
+pub async fn drop_missing(job: &QueuedJob) -> Result<bool> {
+    let job = job.clone();
+    run_blocking(move || drop_if_missing(&db::init_db()?, &job)).await
+}
+
+fn drop_if_missing(conn: &Connection, job: &QueuedJob) -> Result<bool> {
+    if Path::new(&job.audio_path).exists() {
+        return Ok(false);
+    }
+
+    warn!(
+        "Recording {} of pending job #{} is gone, removing the job",
+        job.audio_path, job.id
+    );
+    db::delete_pending_job(conn, job.id)?;
+    Ok(true)
+}
+
+/// Remove a job that was delivered (or held no speech).
+pub async fn finish(id: i64, delete_audio: bool) -> Result<()> {
+    run_blocking(move || remove(id, delete_audio).map(|_| ())).await
+}
+
+/// Retry pending jobs in the background until the service shuts down.
+///
+/// A round stops at the first job that still fails with a connectivity error,
+/// since the remaining jobs would fail the same way.
+pub fn spawn_retry_worker(retrier: JobRetrier) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(RETRY_INTERVAL).await;
+
+            let jobs = match run_blocking(list).await {
+                Ok(jobs) => jobs,
+                Err(e) => {
+                    error!("Failed to load pending jobs: {:?}", e);
+                    continue;
+                }
+            };
+
+            for job in jobs
+                .iter()
+                .filter(|job| job.attempts < MAX_AUTO_RETRY_ATTEMPTS)
+            {
+                match retrier.retry(job).await {
+                    Ok(RetryOutcome::Completed { history_id, .. }) => {
+                        info!(
+                            "Pending job #{} delivered as history #{}",
+                            job.id, history_id
+                        );
+                    }
+                    Ok(RetryOutcome::NoSpeech) => {
+                        info!("Pending job #{} contained no speech", job.id);
+                    }
+                    Ok(RetryOutcome::Missing) => {}
+                    Ok(RetryOutcome::Busy) => {
+                        debug!("Pending job #{} is being retried elsewhere", job.id);
+                    }
+                    Ok(RetryOutcome::Failed { error, offline }) => {
+                        debug!("Pending job #{} failed again: {}", job.id, error);
+                        if offline {
+                            break;
+                        }
+                    }
+                    Err(e) => error!("Failed to retry pending job #{}: {:?}", job.id, e),
+                }
+            }
+        }
+    })
+}
+
+async fn record_failure(id: i64, error: &str) -> Result<()> {
+    let error = error.to_string();
+    run_blocking(move || db::record_pending_job_attempt(&db::init_db()?, id, &error)).await
+}
+
+async fn run_blocking<T, F>(f: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T> + Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .context("Database task panicked")?
+}
+
+fn delete_audio_file(path: &Path) {
+    match std::fs::remove_file(path) {
+        Ok(()) => debug!("Deleted audio file {:?}", path),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => warn!("Failed to delete audio file {:?}: {}", path, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_offline_error_ignores_other_errors() {
+        let err = anyhow::anyhow!("Groq API error (401): invalid key");
+        assert!(!is_offline_error(&err));
+    }
+
+    #[tokio::test]
+    async fn test_is_offline_error_detects_connection_failure() {
+        // Nothing listens on port 9 (discard) in the test environment
+        let err = reqwest::Client::new()
+            .get("http://127.0.0.1:9/")
+            .send()
+            .await
+            .unwrap_err();
+        let err = anyhow::Error::new(err).context("Failed to send request to Groq API");
+        assert!(is_offline_error(&err));
+    }
+
+    #[test]
+    fn test_queued_job_from_pending_job() {
+        let mut pending = PendingJob::new(
+            "job-1".to_string(),
+            "/tmp/a.wav".to_string(),
+            r#"{"copy_to_clipboard":false,"auto_paste":false,"language":"de"}"#.to_string(),
+            "offline".to_string(),
+        );
+        pending.id = Some(7);
+
+        let job = QueuedJob::from(pending.clone());
+        assert_eq!(job.id, 7);
+        assert!(!job.options.copy_to_clipboard);
+        assert_eq!(job.options.language.as_deref(), Some("de"));
+
+        pending.options = "not json".to_string();
+        let job = QueuedJob::from(pending);
+        assert!(job.options.copy_to_clipboard);
+    }
+
+    #[test]
+    fn test_job_without_recording_is_dropped() {
+        let conn = Connection::open_in_memory().unwrap();
+        db::migrate(&conn).unwrap();
+        let recording =
+            std::env::temp_dir().join(format!("wisprarch-job-{}.wav", uuid::Uuid::new_v4()));
+        std::fs::write(&recording, b"RIFF").unwrap();
+
+        let pending = PendingJob::new(
+            "job-1".to_string(),
+            recording.to_string_lossy().to_string(),
+            "{}".to_string(),
+            "offline".to_string(),
+        );
+        let id = db::insert_pending_job(&conn, &pending).unwrap();
+        let job = QueuedJob::from(db::get_pending_job_by_id(&conn, id).unwrap().unwrap());
+
+        assert!(!drop_if_missing(&conn, &job).unwrap());
+        assert!(db::get_pending_job_by_id(&conn, id).unwrap().is_some());
+
+        std::fs::remove_file(&recording).unwrap();
+        assert!(drop_if_missing(&conn, &job).unwrap());
+        assert!(db::get_pending_job_by_id(&conn, id).unwrap().is_none());
+    }
+}
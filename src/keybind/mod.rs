@@ -28,6 +28,7 @@ pub use discovery::{discover_config, ConfigDiscovery};
 pub use parser::{parse_bindings, HyprBinding, Modifier, Modifiers};
 pub use writer::{remove_binding, write_binding};
 
+use crate::config::{ApiConfig, Config};
 use anyhow::{anyhow, Result};
 use discovery::get_all_config_files;
 use serde::{Deserialize, Serialize};
@@ -39,7 +40,6 @@ pub const DEFAULT_MODIFIERS: &[&str] = &["SUPER"];
 pub const FALLBACK_MODIFIERS: &[&str] = &["SUPER", "SHIFT"];
 pub const WISPRARCH_SECTION_MARKER: &str =
     "# wisprarch voice-to-text (managed by wisprarch keybind)";
-pub const WISPRARCH_TOGGLE_ENDPOINT: &str = "http://127.0.0.1:3737/toggle";
 
 /// Represents a proposed keybinding to install
 #[derive(Debug, Clone)]
@@ -56,7 +56,7 @@ impl Default for ProposedBinding {
             modifiers: Modifiers::from_strs(DEFAULT_MODIFIERS),
             key: DEFAULT_KEY.to_string(),
             description: "wisprarch".to_string(),
-            command: format!("curl -X POST {}", WISPRARCH_TOGGLE_ENDPOINT),
+            command: toggle_command(&ApiConfig::default()),
         }
     }
 }
@@ -71,6 +71,12 @@ impl ProposedBinding {
         }
     }
 
+    /// Point the binding at the API configured in `[api]`
+    pub fn for_api(mut self, api: &ApiConfig) -> Self {
+        self.command = toggle_command(api);
+        self
+    }
+
     /// Format the binding as a Hyprland bindd directive
     pub fn to_hyprland_line(&self) -> String {
         format!(
@@ -89,6 +95,11 @@ impl ProposedBinding {
     }
 }
 
+/// Command that toggles recording through the configured API
+pub fn toggle_command(api: &ApiConfig) -> String {
+    api.curl_command("-X POST", "/toggle")
+}
+
 /// Result of checking for conflicts
 #[derive(Debug)]
 pub struct ConflictCheckResult {
@@ -119,13 +130,29 @@ pub fn check_conflicts(
     }
 }
 
-/// Find existing wisprarch bindings in the configuration
-pub fn find_wisprarch_bindings(bindings: &[HyprBinding]) -> Vec<&HyprBinding> {
+/// Find existing wisprarch bindings in the configuration: commands calling
+/// the API at the address or socket from `api`, or the default address.
+pub fn find_wisprarch_bindings<'a>(
+    bindings: &'a [HyprBinding],
+    api: &ApiConfig,
+) -> Vec<&'a HyprBinding> {
+    let defaults = ApiConfig::default();
+    let mut endpoints = vec![
+        api.tcp_addr(),
+        format!("localhost:{}", api.port),
+        defaults.tcp_addr(),
+        format!("localhost:{}", defaults.port),
+    ];
+    if let Some(socket) = api.socket_path() {
+        endpoints.push(socket.to_string_lossy().into_owned());
+    }
+
     bindings
         .iter()
         .filter(|b| {
-            b.command.contains("127.0.0.1:3737")
-                || b.command.contains("localhost:3737")
+            endpoints
+                .iter()
+                .any(|endpoint| b.command.contains(endpoint.as_str()))
                 || b.description
                     .as_ref()
                     .map(|d| d.to_lowercase().contains("wisprarch"))
@@ -199,7 +226,8 @@ pub fn get_status() -> Result<KeybindStatus> {
         all_bindings.extend(parse_bindings(file));
     }
 
-    let existing = find_wisprarch_bindings(&all_bindings);
+    let api = Config::load()?.api;
+    let existing = find_wisprarch_bindings(&all_bindings, &api);
 
     if let Some(binding) = existing.into_iter().next() {
         Ok(KeybindStatus::Installed {
@@ -238,7 +266,8 @@ pub fn install(key: Option<&str>, dry_run: bool) -> Result<Option<InstallResult>
         parse_key_string(key_str)?
     } else {
         ProposedBinding::default()
-    };
+    }
+    .for_api(&Config::load()?.api);
 
     // Check for conflicts
     let all_files = get_all_config_files(&discovery);
@@ -332,3 +361,62 @@ pub fn parse_key_string(s: &str) -> Result<ProposedBinding> {
 
     Ok(ProposedBinding::new(&mod_strs, &key))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::parser::parse_bindings_from_content;
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_toggle_command_uses_api_config() {
+        assert_eq!(
+            toggle_command(&ApiConfig::default()),
+            "curl -X POST http://127.0.0.1:3737/toggle"
+        );
+
+        let api = ApiConfig {
+            port: 4747,
+            unix_socket: Some("/run/user/1000/wisprarch test.sock".to_string()),
+            ..ApiConfig::default()
+        };
+        assert_eq!(
+            toggle_command(&api),
+            "curl -X POST --unix-socket '/run/user/1000/wisprarch test.sock' http://localhost/toggle"
+        );
+
+        let api_with_token = ApiConfig {
+            require_token: true,
+            ..ApiConfig::default()
+        };
+        let command = toggle_command(&api_with_token);
+        assert!(command.starts_with("curl -X POST -H \"Authorization: Bearer $(cat "));
+        assert!(command.ends_with("api-token)\" http://127.0.0.1:3737/toggle"));
+
+        let binding = ProposedBinding::new(&["SUPER"], "T").for_api(&api);
+        assert!(binding
+            .to_hyprland_line()
+            .ends_with("exec, curl -X POST --unix-socket '/run/user/1000/wisprarch test.sock' http://localhost/toggle"));
+    }
+
+    #[test]
+    fn test_find_bindings_for_configured_api() {
+        let content = "\
+bind = SUPER, R, exec, curl -X POST http://127.0.0.1:4747/toggle
+bind = SUPER, T, exec, curl -X POST --unix-socket '/run/user/1000/wisprarch test.sock' http://localhost/toggle
+bind = SUPER, U, exec, curl -X POST http://127.0.0.1:3737/toggle
+bind = SUPER, V, exec, curl http://127.0.0.1:8080/toggle
+";
+        let bindings = parse_bindings_from_content(content, Path::new("/test"));
+        let api = ApiConfig {
+            port: 4747,
+            unix_socket: Some("/run/user/1000/wisprarch test.sock".to_string()),
+            ..ApiConfig::default()
+        };
+        let keys: Vec<&str> = find_wisprarch_bindings(&bindings, &api)
+            .iter()
+            .map(|b| b.key.as_str())
+            .collect();
+        assert_eq!(keys, ["R", "T", "U"]);
+    }
+}
@@ -1,17 +1,22 @@
 pub mod api;
 pub mod app;
+pub mod apps;
 pub mod audio;
 pub mod cli;
 pub mod config;
 pub mod db;
+pub mod events;
 pub mod global;
 pub mod history;
+pub mod jobs;
 pub mod keybind;
 pub mod logs;
 pub mod models;
 pub mod normalizer;
+pub mod sinks;
 pub mod text_io;
 pub mod transcription;
 pub mod tui;
 pub mod ui;
 pub mod update;
+pub mod usage;
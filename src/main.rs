@@ -6,8 +6,10 @@ use tracing_subscriber::EnvFilter;
 use wisprarch::{
     app,
     cli::{
-        handle_history_command, handle_keybind_command, handle_logs_command, handle_models_command,
-        handle_provider_command, handle_update_command, handle_waybar_command, Cli, CliCommand,
+        handle_history_command, handle_jobs_command, handle_keybind_command, handle_logs_command,
+        handle_models_command, handle_postprocess_command, handle_provider_command,
+        handle_snippets_command, handle_update_command, handle_usage_command,
+        handle_waybar_command, Cli, CliCommand,
     },
 };
 
@@ -29,13 +31,29 @@ async fn main() -> Result<()> {
             return Ok(());
         }
         Some(CliCommand::Provider(args)) => {
-            handle_provider_command(args)?;
+            handle_provider_command(args).await?;
             return Ok(());
         }
         Some(CliCommand::History(args)) => {
             handle_history_command(args)?;
             return Ok(());
         }
+        Some(CliCommand::Jobs(args)) => {
+            handle_jobs_command(args).await?;
+            return Ok(());
+        }
+        Some(CliCommand::Postprocess(args)) => {
+            handle_postprocess_command(args).await?;
+            return Ok(());
+        }
+        Some(CliCommand::Snippets(args)) => {
+            handle_snippets_command(args)?;
+            return Ok(());
+        }
+        Some(CliCommand::Usage(args)) => {
+            handle_usage_command(args)?;
+            return Ok(());
+        }
         Some(CliCommand::Logs(args)) => {
             handle_logs_command(args)?;
             return Ok(());
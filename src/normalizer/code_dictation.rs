@@ -0,0 +1,366 @@
+//! Code dictation.
+//!
+//! Used instead of the prose post-processing chain when a job runs in code
+//! mode. Turns "camel case foo bar" into `fooBar`, spoken symbols such as
+//! "double colon" or "equals equals" into `::` and `==`, and drops the
+//! sentence capitalization and punctuation providers add to prose. A casing
+//! command applies to the words that follow it, up to the next symbol or
+//! command. Saying "literal" before a command keeps it as words.
+
+use super::TranscriptionNormalizer;
+
+/// Word that keeps the next command as text.
+const ESCAPE_WORD: &str = "literal";
+
+/// Punctuation providers attach to words in prose.
+const PROSE_PUNCTUATION: &[char] = &[',', '.', ';', ':', '?', '!', '…'];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Casing {
+    Camel,
+    Pascal,
+    Snake,
+    Kebab,
+    /// `SCREAMING_SNAKE`, for constants
+    AllCaps,
+}
+
+const CASINGS: &[(&[&str], Casing)] = &[
+    (&["camel", "case"], Casing::Camel),
+    (&["pascal", "case"], Casing::Pascal),
+    (&["snake", "case"], Casing::Snake),
+    (&["kebab", "case"], Casing::Kebab),
+    (&["all", "caps"], Casing::AllCaps),
+];
+
+/// How a symbol is spaced against its neighbours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Spacing {
+    /// Spaces on both sides: `a == b`
+    Binary,
+    /// No spaces: `a::b`, `a.b`, `f(x`
+    Tight,
+    /// Attaches to the previous token: `)` `,` `;`
+    Close,
+    /// Attaches to the next token: `!x`, `&x`
+    Open,
+}
+
+const SYMBOLS: &[(&[&str], &str, Spacing)] = &[
+    (&["equals", "equals", "equals"], "===", Spacing::Binary),
+    (&["equals", "equals"], "==", Spacing::Binary),
+    (&["double", "equals"], "==", Spacing::Binary),
+    (&["not", "equals"], "!=", Spacing::Binary),
+    (&["less", "than", "or", "equal"], "<=", Spacing::Binary),
+    (&["greater", "than", "or", "equal"], ">=", Spacing::Binary),
+    (&["less", "than"], "<", Spacing::Binary),
+    (&["greater", "than"], ">", Spacing::Binary),
+    (&["plus", "equals"], "+=", Spacing::Binary),
+    (&["minus", "equals"], "-=", Spacing::Binary),
+    (&["fat", "arrow"], "=>", Spacing::Binary),
+    (&["arrow"], "->", Spacing::Binary),
+    (&["equals"], "=", Spacing::Binary),
+    (&["plus"], "+", Spacing::Binary),
+    (&["minus"], "-", Spacing::Binary),
+    (&["and", "and"], "&&", Spacing::Binary),
+    (&["or", "or"], "||", Spacing::Binary),
+    (&["pipe"], "|", Spacing::Binary),
+    (&["double", "colon"], "::", Spacing::Tight),
+    (&["dot"], ".", Spacing::Tight),
+    (&["underscore"], "_", Spacing::Tight),
+    (&["slash"], "/", Spacing::Tight),
+    (&["backslash"], "\\", Spacing::Tight),
+    (&["dash"], "-", Spacing::Tight),
+    (&["comma"], ",", Spacing::Close),
+    (&["semicolon"], ";", Spacing::Close),
+    (&["colon"], ":", Spacing::Close),
+    (&["close", "paren"], ")", Spacing::Close),
+    (&["close", "bracket"], "]", Spacing::Close),
+    (&["close", "brace"], "}", Spacing::Close),
+    (&["question", "mark"], "?", Spacing::Close),
+    (&["open", "paren"], "(", Spacing::Tight),
+    (&["open", "bracket"], "[", Spacing::Tight),
+    (&["open", "brace"], "{", Spacing::Binary),
+    (&["bang"], "!", Spacing::Open),
+    (&["ampersand"], "&", Spacing::Open),
+    (&["star"], "*", Spacing::Open),
+    (&["hash"], "#", Spacing::Open),
+    (&["dollar", "sign"], "$", Spacing::Open),
+    (&["at", "sign"], "@", Spacing::Open),
+    (&["tilde"], "~", Spacing::Open),
+];
+
+/// Longest phrase in `CASINGS` or `SYMBOLS`.
+const MAX_PHRASE_WORDS: usize = 4;
+
+struct Word {
+    text: String,
+    /// Starts a sentence in the provider's prose, so a leading capital is
+    /// only sentence case
+    sentence_start: bool,
+}
+
+struct Token {
+    text: String,
+    space_before: bool,
+    space_after: bool,
+}
+
+impl Token {
+    fn word(text: String) -> Self {
+        Self {
+            text,
+            space_before: true,
+            space_after: true,
+        }
+    }
+
+    fn symbol(text: &str, spacing: Spacing) -> Self {
+        let (space_before, space_after) = match spacing {
+            Spacing::Binary => (true, true),
+            Spacing::Tight => (false, false),
+            Spacing::Close => (false, true),
+            Spacing::Open => (true, false),
+        };
+        Self {
+            text: text.to_string(),
+            space_before,
+            space_after,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct CodeDictation;
+
+impl CodeDictation {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl TranscriptionNormalizer for CodeDictation {
+    fn normalize(&self, raw_output: &str) -> String {
+        let words = split_words(raw_output);
+        let mut tokens: Vec<Token> = Vec::new();
+        let mut casing: Option<(Casing, Vec<String>)> = None;
+        let mut i = 0;
+
+        while i < words.len() {
+            if words[i].text.eq_ignore_ascii_case(ESCAPE_WORD) && i + 1 < words.len() {
+                let len = match_casing(&words[i + 1..])
+                    .map(|(len, _)| len)
+                    .or_else(|| match_symbol(&words[i + 1..]).map(|(len, ..)| len))
+                    .unwrap_or(1);
+                for word in &words[i + 1..i + 1 + len] {
+                    push_word(&mut tokens, &mut casing, word);
+                }
+                i += 1 + len;
+                continue;
+            }
+
+            if let Some((len, next)) = match_casing(&words[i..]) {
+                flush_casing(&mut tokens, &mut casing);
+                casing = Some((next, Vec::new()));
+                i += len;
+            } else if let Some((len, symbol, spacing)) = match_symbol(&words[i..]) {
+                flush_casing(&mut tokens, &mut casing);
+                tokens.push(Token::symbol(symbol, spacing));
+                i += len;
+            } else {
+                push_word(&mut tokens, &mut casing, &words[i]);
+                i += 1;
+            }
+        }
+        flush_casing(&mut tokens, &mut casing);
+
+        join(&tokens)
+    }
+
+    fn name(&self) -> &'static str {
+        "code"
+    }
+}
+
+/// Split on whitespace, dropping prose punctuation at the end of words and
+/// splitting hyphenated words ("snake-case") that providers write for
+/// spoken compounds.
+fn split_words(text: &str) -> Vec<Word> {
+    let mut words = Vec::new();
+    let mut sentence_start = true;
+    for raw in text.split_whitespace() {
+        let trimmed = raw.trim_end_matches(PROSE_PUNCTUATION);
+        let ends_sentence =
+            trimmed.len() < raw.len() && raw[trimmed.len()..].contains(['.', '?', '!', '…']);
+
+        let parts: Vec<&str> = if trimmed.contains('-')
+            && trimmed
+                .split('-')
+                .all(|p| !p.is_empty() && p.chars().all(char::is_alphabetic))
+        {
+            trimmed.split('-').collect()
+        } else if trimmed.is_empty() {
+            Vec::new()
+        } else {
+            vec![trimmed]
+        };
+
+        for part in parts {
+            words.push(Word {
+                text: part.to_string(),
+                sentence_start,
+            });
+            sentence_start = false;
+        }
+        sentence_start = sentence_start || ends_sentence;
+    }
+    words
+}
+
+fn phrase_matches(words: &[Word], phrase: &[&str]) -> bool {
+    words.len() >= phrase.len()
+        && phrase
+            .iter()
+            .zip(words)
+            .all(|(p, w)| w.text.eq_ignore_ascii_case(p))
+}
+
+fn match_casing(words: &[Word]) -> Option<(usize, Casing)> {
+    CASINGS
+        .iter()
+        .find(|(phrase, _)| phrase_matches(words, phrase))
+        .map(|(phrase, casing)| (phrase.len(), *casing))
+}
+
+fn match_symbol(words: &[Word]) -> Option<(usize, &'static str, Spacing)> {
+    (1..=MAX_PHRASE_WORDS).rev().find_map(|len| {
+        SYMBOLS
+            .iter()
+            .find(|(phrase, ..)| phrase.len() == len && phrase_matches(words, phrase))
+            .map(|(phrase, symbol, spacing)| (phrase.len(), *symbol, *spacing))
+    })
+}
+
+fn push_word(tokens: &mut Vec<Token>, casing: &mut Option<(Casing, Vec<String>)>, word: &Word) {
+    match casing {
+        Some((_, parts)) => parts.push(word.text.to_lowercase()),
+        None => tokens.push(Token::word(uncapitalize_sentence(word))),
+    }
+}
+
+fn flush_casing(tokens: &mut Vec<Token>, casing: &mut Option<(Casing, Vec<String>)>) {
+    if let Some((casing, parts)) = casing.take() {
+        if !parts.is_empty() {
+            tokens.push(Token::word(apply_casing(casing, &parts)));
+        }
+    }
+}
+
+/// Undo sentence case ("Let" at the start of a sentence), keeping words
+/// that are capitalized on their own, such as `HTTP` or `FooBar`.
+fn uncapitalize_sentence(word: &Word) -> String {
+    let mut chars = word.text.chars();
+    let is_sentence_case = word.sentence_start
+        && chars.next().is_some_and(char::is_uppercase)
+        && chars.all(|c| !c.is_uppercase());
+    if is_sentence_case {
+        word.text.to_lowercase()
+    } else {
+        word.text.clone()
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn apply_casing(casing: Casing, parts: &[String]) -> String {
+    match casing {
+        Casing::Camel => parts
+            .iter()
+            .enumerate()
+            .map(|(i, part)| {
+                if i == 0 {
+                    part.clone()
+                } else {
+                    capitalize(part)
+                }
+            })
+            .collect(),
+        Casing::Pascal => parts.iter().map(|part| capitalize(part)).collect(),
+        Casing::Snake => parts.join("_"),
+        Casing::Kebab => parts.join("-"),
+        Casing::AllCaps => parts.join("_").to_uppercase(),
+    }
+}
+
+fn join(tokens: &[Token]) -> String {
+    let mut result = String::new();
+    let mut previous_space_after = false;
+    for token in tokens {
+        if !result.is_empty() && previous_space_after && token.space_before {
+            result.push(' ');
+        }
+        result.push_str(&token.text);
+        previous_space_after = token.space_after;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn code(text: &str) -> String {
+        CodeDictation::new().normalize(text)
+    }
+
+    #[test]
+    fn test_casing_commands() {
+        assert_eq!(code("camel case foo bar"), "fooBar");
+        assert_eq!(code("Pascal case http client."), "HttpClient");
+        assert_eq!(code("snake case user id"), "user_id");
+        assert_eq!(code("Kebab-case main menu"), "main-menu");
+        assert_eq!(code("all caps max retries"), "MAX_RETRIES");
+    }
+
+    #[test]
+    fn test_casing_stops_at_symbol() {
+        assert_eq!(
+            code("Let snake case retry count equals camel case max retries."),
+            "let retry_count = maxRetries"
+        );
+    }
+
+    #[test]
+    fn test_symbols() {
+        assert_eq!(code("std double colon env"), "std::env");
+        assert_eq!(code("if a equals equals b"), "if a == b");
+        assert_eq!(
+            code("fn main open paren close paren arrow result"),
+            "fn main() -> result"
+        );
+        assert_eq!(
+            code("self dot count plus equals one semicolon"),
+            "self.count += one;"
+        );
+        assert_eq!(code("bang done and and x not equals y"), "!done && x != y");
+    }
+
+    #[test]
+    fn test_drops_prose_punctuation_and_capitals() {
+        assert_eq!(code("Return value. Done?"), "return value done");
+        assert_eq!(code("Use HTTP and FooBar"), "use HTTP and FooBar");
+    }
+
+    #[test]
+    fn test_escape_word() {
+        assert_eq!(code("literal arrow key"), "arrow key");
+        assert_eq!(code("literal camel case"), "camel case");
+        assert_eq!(code("x equals literal"), "x = literal");
+    }
+}
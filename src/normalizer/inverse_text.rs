@@ -0,0 +1,1165 @@
+//! Inverse text normalization (ITN).
+//!
+//! Rewrites spoken-form numbers in written form, so "twenty five dollars"
+//! becomes "$25" and "march third two thousand twenty six" becomes
+//! "March 3, 2026". Cardinals, ordinals, decimals, currency, percentages,
+//! units, times and dates are recognized in English and Spanish.
+//!
+//! Single-digit numbers on their own ("one of them") and runs of numbers that
+//! don't form one value ("one two three") are left as words. A pair joined by
+//! "to", "past" or "or" is written the same way, so "ten to five" becomes
+//! "10 to 5" rather than "10 to five".
+
+use tracing::warn;
+
+use super::TranscriptionNormalizer;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Language {
+    English,
+    Spanish,
+}
+
+/// Grammatical role of a number word.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Word {
+    /// 0-9
+    Digit(u64),
+    /// Values that can't take a units digit: 10-19, and 21-29 in Spanish
+    Teen(u64),
+    /// 20, 30, ... 90
+    Tens(u64),
+    /// A complete hundreds value: "doscientos"
+    Hundreds(u64),
+    /// The "hundred" multiplier
+    Hundred,
+    /// thousand, million, billion
+    Scale(u64),
+}
+
+const ENGLISH_NUMBERS: &[(&str, Word)] = &[
+    ("zero", Word::Digit(0)),
+    ("one", Word::Digit(1)),
+    ("two", Word::Digit(2)),
+    ("three", Word::Digit(3)),
+    ("four", Word::Digit(4)),
+    ("five", Word::Digit(5)),
+    ("six", Word::Digit(6)),
+    ("seven", Word::Digit(7)),
+    ("eight", Word::Digit(8)),
+    ("nine", Word::Digit(9)),
+    ("ten", Word::Teen(10)),
+    ("eleven", Word::Teen(11)),
+    ("twelve", Word::Teen(12)),
+    ("thirteen", Word::Teen(13)),
+    ("fourteen", Word::Teen(14)),
+    ("fifteen", Word::Teen(15)),
+    ("sixteen", Word::Teen(16)),
+    ("seventeen", Word::Teen(17)),
+    ("eighteen", Word::Teen(18)),
+    ("nineteen", Word::Teen(19)),
+    ("twenty", Word::Tens(20)),
+    ("thirty", Word::Tens(30)),
+    ("forty", Word::Tens(40)),
+    ("fifty", Word::Tens(50)),
+    ("sixty", Word::Tens(60)),
+    ("seventy", Word::Tens(70)),
+    ("eighty", Word::Tens(80)),
+    ("ninety", Word::Tens(90)),
+    ("hundred", Word::Hundred),
+    ("thousand", Word::Scale(1_000)),
+    ("million", Word::Scale(1_000_000)),
+    ("billion", Word::Scale(1_000_000_000)),
+];
+
+const ENGLISH_ORDINALS: &[(&str, Word)] = &[
+    ("first", Word::Digit(1)),
+    ("second", Word::Digit(2)),
+    ("third", Word::Digit(3)),
+    ("fourth", Word::Digit(4)),
+    ("fifth", Word::Digit(5)),
+    ("sixth", Word::Digit(6)),
+    ("seventh", Word::Digit(7)),
+    ("eighth", Word::Digit(8)),
+    ("ninth", Word::Digit(9)),
+    ("tenth", Word::Teen(10)),
+    ("eleventh", Word::Teen(11)),
+    ("twelfth", Word::Teen(12)),
+    ("thirteenth", Word::Teen(13)),
+    ("fourteenth", Word::Teen(14)),
+    ("fifteenth", Word::Teen(15)),
+    ("sixteenth", Word::Teen(16)),
+    ("seventeenth", Word::Teen(17)),
+    ("eighteenth", Word::Teen(18)),
+    ("nineteenth", Word::Teen(19)),
+    ("twentieth", Word::Tens(20)),
+    ("thirtieth", Word::Tens(30)),
+    ("fortieth", Word::Tens(40)),
+    ("fiftieth", Word::Tens(50)),
+    ("sixtieth", Word::Tens(60)),
+    ("seventieth", Word::Tens(70)),
+    ("eightieth", Word::Tens(80)),
+    ("ninetieth", Word::Tens(90)),
+    ("hundredth", Word::Hundred),
+    ("thousandth", Word::Scale(1_000)),
+    ("millionth", Word::Scale(1_000_000)),
+];
+
+const SPANISH_NUMBERS: &[(&str, Word)] = &[
+    ("cero", Word::Digit(0)),
+    ("uno", Word::Digit(1)),
+    ("un", Word::Digit(1)),
+    ("una", Word::Digit(1)),
+    ("dos", Word::Digit(2)),
+    ("tres", Word::Digit(3)),
+    ("cuatro", Word::Digit(4)),
+    ("cinco", Word::Digit(5)),
+    ("seis", Word::Digit(6)),
+    ("siete", Word::Digit(7)),
+    ("ocho", Word::Digit(8)),
+    ("nueve", Word::Digit(9)),
+    ("diez", Word::Teen(10)),
+    ("once", Word::Teen(11)),
+    ("doce", Word::Teen(12)),
+    ("trece", Word::Teen(13)),
+    ("catorce", Word::Teen(14)),
+    ("quince", Word::Teen(15)),
+    ("dieciséis", Word::Teen(16)),
+    ("dieciseis", Word::Teen(16)),
+    ("diecisiete", Word::Teen(17)),
+    ("dieciocho", Word::Teen(18)),
+    ("diecinueve", Word::Teen(19)),
+    ("veinte", Word::Tens(20)),
+    ("veintiuno", Word::Teen(21)),
+    ("veintiún", Word::Teen(21)),
+    ("veintiuna", Word::Teen(21)),
+    ("veintidós", Word::Teen(22)),
+    ("veintidos", Word::Teen(22)),
+    ("veintitrés", Word::Teen(23)),
+    ("veintitres", Word::Teen(23)),
+    ("veinticuatro", Word::Teen(24)),
+    ("veinticinco", Word::Teen(25)),
+    ("veintiséis", Word::Teen(26)),
+    ("veintiseis", Word::Teen(26)),
+    ("veintisiete", Word::Teen(27)),
+    ("veintiocho", Word::Teen(28)),
+    ("veintinueve", Word::Teen(29)),
+    ("treinta", Word::Tens(30)),
+    ("cuarenta", Word::Tens(40)),
+    ("cincuenta", Word::Tens(50)),
+    ("sesenta", Word::Tens(60)),
+    ("setenta", Word::Tens(70)),
+    ("ochenta", Word::Tens(80)),
+    ("noventa", Word::Tens(90)),
+    ("cien", Word::Hundreds(100)),
+    ("ciento", Word::Hundreds(100)),
+    ("doscientos", Word::Hundreds(200)),
+    ("doscientas", Word::Hundreds(200)),
+    ("trescientos", Word::Hundreds(300)),
+    ("trescientas", Word::Hundreds(300)),
+    ("cuatrocientos", Word::Hundreds(400)),
+    ("cuatrocientas", Word::Hundreds(400)),
+    ("quinientos", Word::Hundreds(500)),
+    ("quinientas", Word::Hundreds(500)),
+    ("seiscientos", Word::Hundreds(600)),
+    ("seiscientas", Word::Hundreds(600)),
+    ("setecientos", Word::Hundreds(700)),
+    ("setecientas", Word::Hundreds(700)),
+    ("ochocientos", Word::Hundreds(800)),
+    ("ochocientas", Word::Hundreds(800)),
+    ("novecientos", Word::Hundreds(900)),
+    ("novecientas", Word::Hundreds(900)),
+    ("mil", Word::Scale(1_000)),
+    ("millón", Word::Scale(1_000_000)),
+    ("millon", Word::Scale(1_000_000)),
+    ("millones", Word::Scale(1_000_000)),
+];
+
+/// Spanish writes ordinals as words; only "primero" is needed for dates.
+const SPANISH_ORDINALS: &[(&str, Word)] = &[("primero", Word::Digit(1))];
+
+const ENGLISH_MONTHS: [&str; 12] = [
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+
+const SPANISH_MONTHS: [&str; 12] = [
+    "enero",
+    "febrero",
+    "marzo",
+    "abril",
+    "mayo",
+    "junio",
+    "julio",
+    "agosto",
+    "septiembre",
+    "octubre",
+    "noviembre",
+    "diciembre",
+];
+
+/// A unit or currency spoken after a number.
+struct Unit {
+    phrase: &'static str,
+    symbol: &'static str,
+    /// Written before the number: `$25`
+    prefix: bool,
+    /// Written without a space: `25%`
+    attached: bool,
+}
+
+const fn unit(phrase: &'static str, symbol: &'static str) -> Unit {
+    Unit {
+        phrase,
+        symbol,
+        prefix: false,
+        attached: false,
+    }
+}
+
+const fn attached(phrase: &'static str, symbol: &'static str) -> Unit {
+    Unit {
+        phrase,
+        symbol,
+        prefix: false,
+        attached: true,
+    }
+}
+
+const fn currency(phrase: &'static str, symbol: &'static str) -> Unit {
+    Unit {
+        phrase,
+        symbol,
+        prefix: true,
+        attached: true,
+    }
+}
+
+const ENGLISH_UNITS: &[Unit] = &[
+    currency("dollars", "$"),
+    currency("dollar", "$"),
+    currency("bucks", "$"),
+    currency("euros", "€"),
+    currency("euro", "€"),
+    currency("pounds sterling", "£"),
+    attached("percent", "%"),
+    attached("per cent", "%"),
+    attached("degrees celsius", "°C"),
+    attached("degrees fahrenheit", "°F"),
+    attached("degrees", "°"),
+    unit("kilometers per hour", "km/h"),
+    unit("kilometres per hour", "km/h"),
+    unit("miles per hour", "mph"),
+    unit("kilometers", "km"),
+    unit("kilometres", "km"),
+    unit("kilometer", "km"),
+    unit("kilometre", "km"),
+    unit("meters", "m"),
+    unit("metres", "m"),
+    unit("meter", "m"),
+    unit("metre", "m"),
+    unit("centimeters", "cm"),
+    unit("centimetres", "cm"),
+    unit("millimeters", "mm"),
+    unit("millimetres", "mm"),
+    unit("kilograms", "kg"),
+    unit("kilogram", "kg"),
+    unit("grams", "g"),
+    unit("liters", "l"),
+    unit("litres", "l"),
+    unit("milliliters", "ml"),
+    unit("millilitres", "ml"),
+    unit("kilobytes", "KB"),
+    unit("megabytes", "MB"),
+    unit("gigabytes", "GB"),
+    unit("terabytes", "TB"),
+];
+
+const SPANISH_UNITS: &[Unit] = &[
+    unit("dólares", "$"),
+    unit("dolares", "$"),
+    unit("dólar", "$"),
+    unit("dolar", "$"),
+    unit("euros", "€"),
+    unit("euro", "€"),
+    unit("por ciento", "%"),
+    attached("grados centígrados", "°C"),
+    attached("grados celsius", "°C"),
+    attached("grados", "°"),
+    unit("kilómetros por hora", "km/h"),
+    unit("kilometros por hora", "km/h"),
+    unit("kilómetros", "km"),
+    unit("kilometros", "km"),
+    unit("kilómetro", "km"),
+    unit("metros", "m"),
+    unit("metro", "m"),
+    unit("centímetros", "cm"),
+    unit("centimetros", "cm"),
+    unit("milímetros", "mm"),
+    unit("milimetros", "mm"),
+    unit("kilogramos", "kg"),
+    unit("kilos", "kg"),
+    unit("gramos", "g"),
+    unit("litros", "l"),
+    unit("mililitros", "ml"),
+    unit("megas", "MB"),
+    unit("megabytes", "MB"),
+    unit("gigas", "GB"),
+    unit("gigabytes", "GB"),
+];
+
+impl Language {
+    fn numbers(self) -> &'static [(&'static str, Word)] {
+        match self {
+            Language::English => ENGLISH_NUMBERS,
+            Language::Spanish => SPANISH_NUMBERS,
+        }
+    }
+
+    fn ordinals(self) -> &'static [(&'static str, Word)] {
+        match self {
+            Language::English => ENGLISH_ORDINALS,
+            Language::Spanish => SPANISH_ORDINALS,
+        }
+    }
+
+    fn units(self) -> &'static [Unit] {
+        match self {
+            Language::English => ENGLISH_UNITS,
+            Language::Spanish => SPANISH_UNITS,
+        }
+    }
+
+    fn months(self) -> &'static [&'static str; 12] {
+        match self {
+            Language::English => &ENGLISH_MONTHS,
+            Language::Spanish => &SPANISH_MONTHS,
+        }
+    }
+
+    /// Word joining parts of a number: "one hundred and five", "treinta y dos"
+    fn and_word(self) -> &'static str {
+        match self {
+            Language::English => "and",
+            Language::Spanish => "y",
+        }
+    }
+
+    /// Words between two numbers that are written alike: "ten to five"
+    fn pair_words(self) -> &'static [&'static str] {
+        match self {
+            Language::English => &["to", "past", "or"],
+            Language::Spanish => &["a", "o"],
+        }
+    }
+
+    fn decimal_words(self) -> &'static [&'static str] {
+        match self {
+            Language::English => &["point"],
+            Language::Spanish => &["coma", "punto"],
+        }
+    }
+
+    fn decimal_separator(self) -> char {
+        match self {
+            Language::English => '.',
+            Language::Spanish => ',',
+        }
+    }
+
+    fn group_separator(self) -> char {
+        match self {
+            Language::English => ',',
+            Language::Spanish => ' ',
+        }
+    }
+
+    /// Phrase between a currency amount and its cents: "and", "con"
+    fn cents_joiner(self) -> &'static str {
+        match self {
+            Language::English => "and",
+            Language::Spanish => "con",
+        }
+    }
+
+    fn cents_words(self) -> &'static [&'static str] {
+        match self {
+            Language::English => &["cents", "cent"],
+            Language::Spanish => &["céntimos", "centimos", "centavos"],
+        }
+    }
+
+    /// Look up a number word, returning whether it is an ordinal.
+    fn word(self, key: &str) -> Option<(Word, bool)> {
+        let find = |table: &[(&str, Word)]| {
+            table
+                .iter()
+                .find(|(word, _)| *word == key)
+                .map(|(_, word)| *word)
+        };
+        find(self.numbers())
+            .map(|word| (word, false))
+            .or_else(|| find(self.ordinals()).map(|word| (word, true)))
+    }
+
+    fn is_number_word(self, key: &str) -> bool {
+        self.word(key).is_some()
+    }
+
+    fn month(self, key: &str) -> Option<usize> {
+        self.months().iter().position(|month| *month == key)
+    }
+}
+
+/// A number parsed from the start of a word sequence.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Number {
+    value: u64,
+    /// Fractional digits after "point"
+    decimals: Option<String>,
+    ordinal: bool,
+    /// Words consumed
+    len: usize,
+}
+
+/// A recognized span and its written form.
+struct Match {
+    len: usize,
+    /// `None` leaves the span as spoken
+    text: Option<String>,
+}
+
+impl Match {
+    fn written(len: usize, text: String) -> Self {
+        Self {
+            len,
+            text: Some(text),
+        }
+    }
+}
+
+/// A word in the input with its byte range.
+struct Token {
+    start: usize,
+    end: usize,
+    key: String,
+    /// Only whitespace or a hyphen separates it from the previous token
+    joined: bool,
+}
+
+fn tokenize(text: &str) -> Vec<Token> {
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let is_word_char = |i: usize| {
+        let c = chars[i].1;
+        c.is_alphanumeric()
+            || c == '\''
+            // Keep dots inside abbreviations such as "p.m"
+            || (c == '.'
+                && i > 0
+                && chars[i - 1].1.is_alphanumeric()
+                && chars.get(i + 1).is_some_and(|(_, next)| next.is_alphanumeric()))
+    };
+
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut gap_start = 0;
+    let mut i = 0;
+    while i < chars.len() {
+        if !is_word_char(i) {
+            i += 1;
+            continue;
+        }
+
+        let start = chars[i].0;
+        while i < chars.len() && is_word_char(i) {
+            i += 1;
+        }
+        let end = chars.get(i).map_or(text.len(), |(offset, _)| *offset);
+
+        let gap = &text[gap_start..start];
+        let joined = !tokens.is_empty()
+            && gap.chars().all(|c| c.is_whitespace() || c == '-')
+            && gap.matches('-').count() <= 1;
+        tokens.push(Token {
+            start,
+            end,
+            key: text[start..end].to_lowercase(),
+            joined,
+        });
+        gap_start = end;
+    }
+    tokens
+}
+
+pub struct InverseTextNormalizer {
+    language: Language,
+}
+
+impl InverseTextNormalizer {
+    /// Create the stage for a language, falling back to English.
+    pub fn new(language: &str) -> Self {
+        let language = match language {
+            "en" => Language::English,
+            "es" => Language::Spanish,
+            other => {
+                if other != "auto" {
+                    warn!(
+                        "No inverse text normalization rules for '{}', using English",
+                        other
+                    );
+                }
+                Language::English
+            }
+        };
+        Self { language }
+    }
+
+    /// Parse a cardinal or ordinal number at the start of `keys`.
+    fn parse_number(&self, keys: &[&str]) -> Option<Number> {
+        let lang = self.language;
+        let mut total = 0;
+        let mut group = 0;
+        let mut last: Option<Word> = None;
+        let mut last_scale = u64::MAX;
+        let mut pending_and = false;
+        let mut len = 0;
+        let mut ordinal = false;
+
+        let mut i = 0;
+        while i < keys.len() {
+            let key = keys[i];
+            if key == lang.and_word() && last.is_some() && !pending_and {
+                pending_and = true;
+                i += 1;
+                continue;
+            }
+
+            let Some((word, is_ordinal)) = lang.word(key) else {
+                break;
+            };
+
+            let and_ok = match (lang, last) {
+                (Language::English, Some(Word::Hundred | Word::Scale(_))) => {
+                    matches!(word, Word::Digit(_) | Word::Teen(_) | Word::Tens(_))
+                }
+                (Language::Spanish, Some(Word::Tens(_))) => matches!(word, Word::Digit(1..)),
+                _ => false,
+            };
+            if pending_and && !and_ok {
+                break;
+            }
+
+            let valid = match word {
+                Word::Digit(0) => last.is_none(),
+                Word::Digit(_) => {
+                    group % 10 == 0
+                        && !matches!(last, Some(Word::Digit(_) | Word::Teen(_)))
+                        // Spanish needs "y" between tens and units
+                        && (lang == Language::English
+                            || pending_and
+                            || !matches!(last, Some(Word::Tens(_))))
+                }
+                Word::Teen(_) | Word::Tens(_) => {
+                    group % 100 == 0
+                        && !matches!(last, Some(Word::Digit(_) | Word::Teen(_) | Word::Tens(_)))
+                }
+                Word::Hundreds(_) => group == 0 && matches!(last, None | Some(Word::Scale(_))),
+                Word::Hundred => {
+                    (1..100).contains(&group)
+                        && matches!(last, Some(Word::Digit(_) | Word::Teen(_) | Word::Tens(_)))
+                }
+                Word::Scale(scale) => {
+                    scale < last_scale
+                        && (group > 0
+                            // "mil" on its own is a thousand
+                            || (lang == Language::Spanish && matches!(last, None | Some(Word::Scale(_)))))
+                }
+            };
+            if !valid {
+                break;
+            }
+
+            match word {
+                Word::Digit(value) | Word::Teen(value) | Word::Tens(value) => group += value,
+                Word::Hundreds(value) => group = value,
+                Word::Hundred => group *= 100,
+                Word::Scale(scale) => {
+                    total += group.max(1) * scale;
+                    group = 0;
+                    last_scale = scale;
+                }
+            }
+
+            last = Some(word);
+            pending_and = false;
+            i += 1;
+            len = i;
+
+            if is_ordinal {
+                ordinal = true;
+                break;
+            }
+        }
+
+        if len == 0 {
+            return None;
+        }
+
+        let mut number = Number {
+            value: total + group,
+            decimals: None,
+            ordinal,
+            len,
+        };
+
+        if !ordinal
+            && keys
+                .get(len)
+                .is_some_and(|key| lang.decimal_words().contains(key))
+        {
+            let digits: String = keys[len + 1..]
+                .iter()
+                .map_while(|key| match lang.word(key) {
+                    Some((Word::Digit(digit), false)) => char::from_digit(digit as u32, 10),
+                    _ => None,
+                })
+                .collect();
+            if !digits.is_empty() {
+                number.len += 1 + digits.len();
+                number.decimals = Some(digits);
+            }
+        }
+
+        Some(number)
+    }
+
+    fn format_number(&self, number: &Number) -> String {
+        let mut text = format_integer(number.value, self.language.group_separator());
+        if let Some(decimals) = &number.decimals {
+            text.push(self.language.decimal_separator());
+            text.push_str(decimals);
+        }
+        text
+    }
+
+    /// Longest unit phrase at the start of `keys`.
+    fn match_unit(&self, keys: &[&str]) -> Option<(usize, &'static Unit)> {
+        self.language
+            .units()
+            .iter()
+            .filter_map(|unit| {
+                let words: Vec<&str> = unit.phrase.split(' ').collect();
+                keys.starts_with(&words).then_some((words.len(), unit))
+            })
+            .max_by_key(|(len, _)| *len)
+    }
+
+    /// "and fifty cents" after a currency amount.
+    fn match_cents(&self, keys: &[&str]) -> Option<(usize, u64)> {
+        if keys.first() != Some(&self.language.cents_joiner()) {
+            return None;
+        }
+        let cents = self.parse_number(&keys[1..])?;
+        let after = 1 + cents.len;
+        (!cents.ordinal
+            && cents.decimals.is_none()
+            && cents.value < 100
+            && keys
+                .get(after)
+                .is_some_and(|key| self.language.cents_words().contains(key)))
+        .then_some((after + 1, cents.value))
+    }
+
+    /// Numbers with an optional unit or currency.
+    fn match_number(&self, keys: &[&str]) -> Option<Match> {
+        let lang = self.language;
+        let number = self.parse_number(keys)?;
+        let rest = &keys[number.len..];
+
+        if number.ordinal {
+            return (lang == Language::English && number.value >= 10).then(|| {
+                Match::written(
+                    number.len,
+                    format!("{}{}", number.value, ordinal_suffix(number.value)),
+                )
+            });
+        }
+
+        if let Some((unit_len, unit)) = self.match_unit(rest) {
+            let mut len = number.len + unit_len;
+            let mut amount = self.format_number(&number);
+
+            if number.decimals.is_none() && (unit.symbol == "$" || unit.symbol == "€") {
+                if let Some((cents_len, cents)) = self.match_cents(&keys[len..]) {
+                    len += cents_len;
+                    amount = format!("{}{}{:02}", amount, lang.decimal_separator(), cents);
+                }
+            }
+
+            let text = match (unit.prefix, unit.attached) {
+                (true, _) => format!("{}{}", unit.symbol, amount),
+                (false, true) => format!("{}{}", amount, unit.symbol),
+                (false, false) => format!("{} {}", amount, unit.symbol),
+            };
+            return Some(Match::written(len, text));
+        }
+
+        // "one two three" is a sequence of digits, not a single value
+        if rest.first().is_some_and(|key| lang.is_number_word(key)) {
+            let run = keys
+                .iter()
+                .take_while(|key| lang.is_number_word(key) || **key == lang.and_word())
+                .count();
+            return Some(Match {
+                len: run,
+                text: None,
+            });
+        }
+
+        if let Some(pair) = self.match_pair(&number, rest) {
+            return Some(pair);
+        }
+
+        Self::is_written(&number).then(|| Match::written(number.len, self.format_number(&number)))
+    }
+
+    /// Whether a plain number is written in digits on its own.
+    fn is_written(number: &Number) -> bool {
+        number.value >= 10 || number.decimals.is_some()
+    }
+
+    /// Two plain numbers joined by a pair word, written in digits when either
+    /// one would be. Pairs ending in a time, unit or digit run are left to
+    /// those matchers.
+    fn match_pair(&self, first: &Number, rest: &[&str]) -> Option<Match> {
+        let lang = self.language;
+        let (word, after) = rest.split_first()?;
+        if !lang.pair_words().contains(word) {
+            return None;
+        }
+
+        let second = self.parse_number(after)?;
+        let tail = &after[second.len..];
+        if second.ordinal
+            || self.match_unit(tail).is_some()
+            || tail.first().is_some_and(|key| lang.is_number_word(key))
+            || self.match_time(after, Some(word)).is_some()
+        {
+            return None;
+        }
+
+        (Self::is_written(first) || Self::is_written(&second)).then(|| {
+            Match::written(
+                first.len + 1 + second.len,
+                format!(
+                    "{} {} {}",
+                    self.format_number(first),
+                    word,
+                    self.format_number(&second)
+                ),
+            )
+        })
+    }
+
+    /// A four-digit year: "two thousand twenty six" or "nineteen eighty four".
+    fn parse_year(&self, keys: &[&str]) -> Option<(usize, u64)> {
+        let number = self.parse_number(keys)?;
+        if number.ordinal || number.decimals.is_some() {
+            return None;
+        }
+        if (1000..3000).contains(&number.value) {
+            return Some((number.len, number.value));
+        }
+        if self.language != Language::English || !(10..30).contains(&number.value) {
+            return None;
+        }
+
+        // Pairs of two-digit numbers
+        let rest = &keys[number.len..];
+        let (len, low) = match rest {
+            ["hundred", ..] => (1, 0),
+            ["oh", digit, ..] => match self.language.word(digit) {
+                Some((Word::Digit(value @ 1..), false)) => (2, value),
+                _ => return None,
+            },
+            _ => {
+                let low = self.parse_number(rest)?;
+                if low.ordinal || low.decimals.is_some() || !(10..100).contains(&low.value) {
+                    return None;
+                }
+                (low.len, low.value)
+            }
+        };
+        Some((number.len + len, number.value * 100 + low))
+    }
+
+    fn match_date(&self, keys: &[&str]) -> Option<Match> {
+        match self.language {
+            Language::English => self.match_english_date(keys),
+            Language::Spanish => self.match_spanish_date(keys),
+        }
+    }
+
+    /// "march third two thousand twenty six", "the third of march"
+    fn match_english_date(&self, keys: &[&str]) -> Option<Match> {
+        if let Some(month) = self.language.month(keys[0]) {
+            let day = self.parse_number(&keys[1..])?;
+            // "may" is too common a word to accept a bare cardinal after it
+            if day.decimals.is_some()
+                || !(1..=31).contains(&day.value)
+                || (month == 4 && !day.ordinal)
+            {
+                return None;
+            }
+
+            let mut len = 1 + day.len;
+            let mut text = format!("{} {}", capitalize(ENGLISH_MONTHS[month]), day.value);
+            if let Some((year_len, year)) = self.parse_year(&keys[len..]) {
+                len += year_len;
+                text.push_str(&format!(", {}", year));
+            }
+            return Some(Match::written(len, text));
+        }
+
+        let day = self.parse_number(keys)?;
+        if !day.ordinal || !(1..=31).contains(&day.value) {
+            return None;
+        }
+        match &keys[day.len..] {
+            ["of", month, ..] => {
+                let month = self.language.month(month)?;
+                Some(Match::written(
+                    day.len + 2,
+                    format!(
+                        "{}{} of {}",
+                        day.value,
+                        ordinal_suffix(day.value),
+                        capitalize(ENGLISH_MONTHS[month])
+                    ),
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    /// "tres de marzo de dos mil veintiséis"
+    fn match_spanish_date(&self, keys: &[&str]) -> Option<Match> {
+        let day = self.parse_number(keys)?;
+        if day.decimals.is_some() || !(1..=31).contains(&day.value) {
+            return None;
+        }
+
+        let ["de", month, ..] = &keys[day.len..] else {
+            return None;
+        };
+        let month = self.language.month(month)?;
+
+        let mut len = day.len + 2;
+        let mut text = format!("{} de {}", day.value, SPANISH_MONTHS[month]);
+        if keys.get(len) == Some(&"de") {
+            if let Some((year_len, year)) = self.parse_year(&keys[len + 1..]) {
+                len += 1 + year_len;
+                text.push_str(&format!(" de {}", year));
+            }
+        }
+        Some(Match::written(len, text))
+    }
+
+    /// A clock hour said as a single word.
+    fn parse_hour(&self, key: &str) -> Option<u64> {
+        match self.language.word(key)? {
+            (Word::Digit(hour @ 1..) | Word::Teen(hour @ 10..=12), false) => Some(hour),
+            _ => None,
+        }
+    }
+
+    /// Minutes past the hour, 1-59.
+    fn parse_minutes(&self, keys: &[&str]) -> Option<(usize, u64)> {
+        let minutes = self.parse_number(keys)?;
+        (!minutes.ordinal && minutes.decimals.is_none() && (1..60).contains(&minutes.value))
+            .then_some((minutes.len, minutes.value))
+    }
+
+    fn match_time(&self, keys: &[&str], previous: Option<&str>) -> Option<Match> {
+        match self.language {
+            Language::English => self.match_english_time(keys),
+            Language::Spanish => self.match_spanish_time(keys, previous),
+        }
+    }
+
+    /// "three thirty p m", "seven o'clock", "nine oh five am"
+    fn match_english_time(&self, keys: &[&str]) -> Option<Match> {
+        let hour = self.parse_hour(keys[0])?;
+
+        if keys.get(1) == Some(&"o'clock") {
+            return Some(Match::written(2, format!("{}:00", hour)));
+        }
+
+        let (minutes_len, minutes) = match &keys[1..] {
+            ["oh", digit, ..] => match self.language.word(digit) {
+                Some((Word::Digit(value @ 1..), false)) => (2, Some(value)),
+                _ => (0, None),
+            },
+            rest => match self.parse_minutes(rest) {
+                Some((len, value)) if value >= 10 => (len, Some(value)),
+                _ => (0, None),
+            },
+        };
+
+        let len = 1 + minutes_len;
+        let (suffix_len, suffix) = match &keys[len..] {
+            ["a", "m", ..] => (2, "AM"),
+            ["p", "m", ..] => (2, "PM"),
+            ["am", ..] => (1, "AM"),
+            ["pm", ..] => (1, "PM"),
+            // Keep the dotted style; the final dot is still in the text
+            ["a.m", ..] => (1, "a.m"),
+            ["p.m", ..] => (1, "p.m"),
+            _ => return None,
+        };
+
+        let time = match minutes {
+            Some(minutes) => format!("{}:{:02}", hour, minutes),
+            None => hour.to_string(),
+        };
+        Some(Match::written(
+            len + suffix_len,
+            format!("{} {}", time, suffix),
+        ))
+    }
+
+    /// "las tres y media", "la una en punto", "las cinco menos cuarto"
+    fn match_spanish_time(&self, keys: &[&str], previous: Option<&str>) -> Option<Match> {
+        if !matches!(previous, Some("la" | "las")) {
+            return None;
+        }
+        let hour = self.parse_hour(keys[0])?;
+        let previous_hour = if hour == 1 { 12 } else { hour - 1 };
+
+        let (len, hour, minutes) = match &keys[1..] {
+            ["en", "punto", ..] => (3, hour, 0),
+            ["y", "media", ..] => (3, hour, 30),
+            ["y", "cuarto", ..] => (3, hour, 15),
+            ["menos", "cuarto", ..] => (3, previous_hour, 45),
+            ["y", rest @ ..] => {
+                let (len, minutes) = self.parse_minutes(rest)?;
+                (2 + len, hour, minutes)
+            }
+            ["menos", rest @ ..] => {
+                let (len, minutes) = self.parse_minutes(rest)?;
+                if minutes > 30 {
+                    return None;
+                }
+                (2 + len, previous_hour, 60 - minutes)
+            }
+            _ => return None,
+        };
+
+        Some(Match::written(len, format!("{}:{:02}", hour, minutes)))
+    }
+}
+
+fn format_integer(value: u64, separator: char) -> String {
+    let digits = value.to_string();
+    if value < 10_000 {
+        return digits;
+    }
+
+    let mut text = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            text.push(separator);
+        }
+        text.push(digit);
+    }
+    text
+}
+
+fn ordinal_suffix(value: u64) -> &'static str {
+    match (value % 10, value % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+impl TranscriptionNormalizer for InverseTextNormalizer {
+    fn normalize(&self, raw_output: &str) -> String {
+        let tokens = tokenize(raw_output);
+        let mut output = String::with_capacity(raw_output.len());
+        let mut copied = 0;
+
+        let mut i = 0;
+        while i < tokens.len() {
+            // Phrases never continue across punctuation
+            let phrase_end = (i + 1..tokens.len())
+                .find(|&j| !tokens[j].joined)
+                .unwrap_or(tokens.len());
+            let keys: Vec<&str> = tokens[i..phrase_end]
+                .iter()
+                .map(|token| token.key.as_str())
+                .collect();
+            let previous = i
+                .checked_sub(1)
+                .filter(|_| tokens[i].joined)
+                .map(|j| tokens[j].key.as_str());
+
+            let found = self
+                .match_date(&keys)
+                .or_else(|| self.match_time(&keys, previous))
+                .or_else(|| self.match_number(&keys));
+
+            match found {
+                Some(Match {
+                    len,
+                    text: Some(text),
+                }) => {
+                    output.push_str(&raw_output[copied..tokens[i].start]);
+                    output.push_str(&text);
+                    copied = tokens[i + len - 1].end;
+                    i += len;
+                }
+                Some(Match { len, text: None }) => i += len,
+                None => i += 1,
+            }
+        }
+
+        output.push_str(&raw_output[copied..]);
+        output
+    }
+
+    fn name(&self) -> &'static str {
+        "itn"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn english(text: &str) -> String {
+        InverseTextNormalizer::new("en").normalize(text)
+    }
+
+    fn spanish(text: &str) -> String {
+        InverseTextNormalizer::new("es").normalize(text)
+    }
+
+    #[test]
+    fn test_english_cardinals() {
+        assert_eq!(english("I counted twenty five birds"), "I counted 25 birds");
+        assert_eq!(english("one hundred and five"), "105");
+        assert_eq!(english("Twenty-one people"), "21 people");
+        assert_eq!(english("two million three hundred thousand"), "2,300,000");
+        assert_eq!(english("three point one four"), "3.14");
+    }
+
+    #[test]
+    fn test_small_numbers_and_sequences_stay_words() {
+        assert_eq!(english("one of them is ready"), "one of them is ready");
+        assert_eq!(english("press one two three"), "press one two three");
+        assert_eq!(english("at first it worked"), "at first it worked");
+        assert_eq!(english("twenty, thirty"), "20, 30");
+    }
+
+    #[test]
+    fn test_number_pairs_are_written_alike() {
+        assert_eq!(english("ten to five"), "10 to 5");
+        assert_eq!(english("five to ten people"), "5 to 10 people");
+        assert_eq!(english("twenty past nine"), "20 past 9");
+        assert_eq!(english("one or two of them"), "one or two of them");
+        assert_eq!(english("ten to five p m"), "10 to 5 PM");
+        assert_eq!(english("ten to five dollars"), "10 to $5");
+        assert_eq!(spanish("de diez a cinco"), "de 10 a 5");
+    }
+
+    #[test]
+    fn test_english_ordinals() {
+        assert_eq!(english("the twenty first century"), "the 21st century");
+        assert_eq!(english("her twelfth birthday"), "her 12th birthday");
+        assert_eq!(english("the third of march"), "the 3rd of March");
+    }
+
+    #[test]
+    fn test_english_currency_and_units() {
+        assert_eq!(english("it costs twenty five dollars"), "it costs $25");
+        assert_eq!(english("five dollars and fifty cents"), "$5.50");
+        assert_eq!(english("three euros"), "€3");
+        assert_eq!(english("a five percent raise"), "a 5% raise");
+        assert_eq!(english("ten kilometers"), "10 km");
+        assert_eq!(english("twenty degrees celsius"), "20°C");
+    }
+
+    #[test]
+    fn test_english_times() {
+        assert_eq!(english("meet at three thirty p m"), "meet at 3:30 PM");
+        assert_eq!(english("at seven o'clock"), "at 7:00");
+        assert_eq!(english("nine oh five am"), "9:05 AM");
+        assert_eq!(english("by ten p.m. tonight"), "by 10 p.m. tonight");
+    }
+
+    #[test]
+    fn test_english_dates() {
+        assert_eq!(
+            english("march third two thousand twenty six"),
+            "March 3, 2026"
+        );
+        assert_eq!(
+            english("on july fourth nineteen seventy six."),
+            "on July 4, 1976."
+        );
+        assert_eq!(english("june twelve"), "June 12");
+        assert_eq!(english("you may five times"), "you may five times");
+    }
+
+    #[test]
+    fn test_spanish() {
+        assert_eq!(spanish("tengo treinta y dos años"), "tengo 32 años");
+        assert_eq!(spanish("cuesta veinticinco euros"), "cuesta 25 €");
+        assert_eq!(spanish("diez euros con cincuenta céntimos"), "10,50 €");
+        assert_eq!(
+            spanish("una subida del cinco por ciento"),
+            "una subida del 5 %"
+        );
+        assert_eq!(spanish("mil quinientos"), "1500");
+        assert_eq!(spanish("tres coma cinco"), "3,5");
+        assert_eq!(spanish("cien mil personas"), "100 000 personas");
+    }
+
+    #[test]
+    fn test_spanish_dates_and_times() {
+        assert_eq!(
+            spanish("el tres de marzo de dos mil veintiséis"),
+            "el 3 de marzo de 2026"
+        );
+        assert_eq!(spanish("el primero de mayo"), "el 1 de mayo");
+        assert_eq!(spanish("a las tres y media"), "a las 3:30");
+        assert_eq!(spanish("a las cinco menos cuarto"), "a las 4:45");
+        assert_eq!(spanish("la una en punto"), "la 1:00");
+    }
+
+    #[test]
+    fn test_unknown_language_uses_english() {
+        let itn = InverseTextNormalizer::new("auto");
+        assert_eq!(itn.normalize("forty two"), "42");
+    }
+}
@@ -0,0 +1,146 @@
+//! LLM cleanup pass through an OpenAI-compatible chat completions endpoint.
+//!
+//! The transcript is sent as the user message together with the configured
+//! system prompt, and the reply replaces the text. Any failure, including a
+//! timeout, keeps the text unchanged so dictation never blocks on the model.
+
+use anyhow::{anyhow, Context, Result};
+use serde_json::{json, Value};
+use std::time::Duration;
+use tracing::{debug, warn};
+
+use crate::config::LlmCleanupConfig;
+
+pub struct LlmCleanup {
+    client: reqwest::Client,
+    endpoint: String,
+    model: String,
+    api_key: Option<String>,
+    system_prompt: String,
+}
+
+impl LlmCleanup {
+    pub fn new(config: &LlmCleanupConfig) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_secs))
+            .build()
+            .context("Failed to create HTTP client for LLM cleanup")?;
+
+        Ok(Self {
+            client,
+            endpoint: config.endpoint.clone(),
+            model: config.model.clone(),
+            api_key: config.api_key.clone(),
+            system_prompt: config.system_prompt.clone(),
+        })
+    }
+
+    /// Rewrite text with the model.
+    pub async fn clean(&self, text: &str) -> Result<String> {
+        let mut request = self.client.post(&self.endpoint).json(&request_body(
+            &self.model,
+            &self.system_prompt,
+            text,
+        ));
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+
+        let response = request
+            .send()
+            .await
+            .with_context(|| format!("Failed to reach {}", self.endpoint))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(anyhow!("LLM endpoint returned {}: {}", status, body));
+        }
+
+        let body: Value = response
+            .json()
+            .await
+            .context("Invalid JSON from LLM endpoint")?;
+        parse_response(&body)
+    }
+
+    /// Rewrite text with the model, keeping the input if that fails.
+    pub async fn clean_or_original(&self, text: &str) -> String {
+        if text.trim().is_empty() {
+            return text.to_string();
+        }
+
+        match self.clean(text).await {
+            Ok(cleaned) => {
+                debug!("LLM cleanup: {:?} -> {:?}", text, cleaned);
+                cleaned
+            }
+            Err(e) => {
+                warn!("LLM cleanup failed, keeping unprocessed text: {:#}", e);
+                text.to_string()
+            }
+        }
+    }
+}
+
+fn request_body(model: &str, system_prompt: &str, text: &str) -> Value {
+    json!({
+        "model": model,
+        "messages": [
+            { "role": "system", "content": system_prompt },
+            { "role": "user", "content": text },
+        ],
+        "temperature": 0,
+        "stream": false,
+    })
+}
+
+fn parse_response(body: &Value) -> Result<String> {
+    let content = body["choices"][0]["message"]["content"]
+        .as_str()
+        .ok_or_else(|| anyhow!("LLM response has no message content"))?
+        .trim();
+
+    if content.is_empty() {
+        return Err(anyhow!("LLM returned an empty reply"));
+    }
+    Ok(content.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_body() {
+        let body = request_body("llama3.2", "Fix it.", "teh text");
+        assert_eq!(body["model"], "llama3.2");
+        assert_eq!(body["messages"][0]["role"], "system");
+        assert_eq!(body["messages"][0]["content"], "Fix it.");
+        assert_eq!(body["messages"][1]["content"], "teh text");
+    }
+
+    #[test]
+    fn test_parse_response() {
+        let body = json!({
+            "choices": [{ "message": { "role": "assistant", "content": " The text.\n" } }]
+        });
+        assert_eq!(parse_response(&body).unwrap(), "The text.");
+
+        assert!(parse_response(&json!({ "choices": [] })).is_err());
+        let empty = json!({ "choices": [{ "message": { "content": "  " } }] });
+        assert!(parse_response(&empty).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_endpoint_keeps_text() {
+        let cleanup = LlmCleanup::new(&LlmCleanupConfig {
+            enabled: true,
+            endpoint: "http://127.0.0.1:9/v1/chat/completions".to_string(),
+            timeout_secs: 2,
+            ..LlmCleanupConfig::default()
+        })
+        .unwrap();
+
+        assert_eq!(cleanup.clean_or_original("keep me").await, "keep me");
+    }
+}
@@ -1,3 +1,16 @@
+mod code_dictation;
+mod inverse_text;
+mod llm_cleanup;
+mod postprocess;
+mod snippets;
+mod spoken_punctuation;
 mod transcription_normalizer;
 
+pub use code_dictation::CodeDictation;
+pub use inverse_text::InverseTextNormalizer;
+pub use llm_cleanup::LlmCleanup;
+pub use postprocess::PostProcessor;
+pub(crate) use snippets::render_template;
+pub use snippets::SnippetExpander;
+pub use spoken_punctuation::SpokenPunctuation;
 pub use transcription_normalizer::TranscriptionNormalizer;
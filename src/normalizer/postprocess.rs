@@ -0,0 +1,444 @@
+//! User-configurable post-processing chain.
+//!
+//! Runs after the provider normalizer in `TranscriptionService`. Each
+//! `[[postprocess]]` step from the config becomes one `TranscriptionNormalizer`
+//! and the steps run in the configured order.
+
+use anyhow::{Context, Result};
+use regex::{Regex, RegexBuilder};
+
+use super::{CodeDictation, InverseTextNormalizer, SpokenPunctuation, TranscriptionNormalizer};
+use crate::config::{CaseMode, PostProcessStep, TrailingPunctuation, WhisperConfig};
+
+/// Characters that end a sentence.
+const SENTENCE_END: &[char] = &['.', '!', '?', '…'];
+
+/// Characters removed by `TrailingPunctuation::Remove`.
+const TRAILING_PUNCTUATION: &[char] = &['.', '!', '?', '…', ',', ';', ':'];
+
+/// Ordered chain of post-processing stages.
+#[derive(Default)]
+pub struct PostProcessor {
+    stages: Vec<Box<dyn TranscriptionNormalizer>>,
+}
+
+impl PostProcessor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build the chain from `[[postprocess]]` steps.
+    ///
+    /// `language` is the transcription language, used by language-specific
+    /// steps that don't set their own.
+    pub fn from_steps(steps: &[PostProcessStep], language: Option<&str>) -> Result<Self> {
+        let mut processor = Self::new();
+        for step in steps {
+            processor = processor.with_stage(build_stage(step, language.unwrap_or("en"))?);
+        }
+        Ok(processor)
+    }
+
+    /// Build the chain for a provider configuration: inverse text
+    /// normalization first if the profile enables `itn`, then the steps.
+    pub fn for_profile(whisper: &WhisperConfig, steps: &[PostProcessStep]) -> Result<Self> {
+        let language = whisper.language.as_deref();
+        let mut processor = Self::new();
+        if whisper.itn {
+            processor = processor.with_stage(Box::new(InverseTextNormalizer::new(
+                language.unwrap_or("en"),
+            )));
+        }
+        processor
+            .stages
+            .extend(Self::from_steps(steps, language)?.stages);
+        Ok(processor)
+    }
+
+    /// Build the chain for code dictation: code casing and symbols. Prose
+    /// steps such as sentence case are left out.
+    pub fn for_code() -> Self {
+        Self::new().with_stage(Box::new(CodeDictation::new()))
+    }
+
+    /// Append a stage to the end of the chain.
+    pub fn with_stage(mut self, stage: Box<dyn TranscriptionNormalizer>) -> Self {
+        self.stages.push(stage);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+
+    /// Run all stages in order.
+    pub fn process(&self, text: &str) -> String {
+        self.stages
+            .iter()
+            .fold(text.to_string(), |text, stage| stage.normalize(&text))
+    }
+
+    /// Run all stages and return the output after each one, for previews.
+    pub fn trace(&self, text: &str) -> Vec<(&'static str, String)> {
+        let mut current = text.to_string();
+        self.stages
+            .iter()
+            .map(|stage| {
+                current = stage.normalize(&current);
+                (stage.name(), current.clone())
+            })
+            .collect()
+    }
+}
+
+fn build_stage(step: &PostProcessStep, language: &str) -> Result<Box<dyn TranscriptionNormalizer>> {
+    Ok(match step {
+        PostProcessStep::Regex {
+            pattern,
+            replacement,
+            case_insensitive,
+        } => Box::new(RegexReplace::new(
+            pattern,
+            replacement.clone(),
+            *case_insensitive,
+        )?),
+        PostProcessStep::Fillers { words } => Box::new(FillerRemoval::new(words)?),
+        PostProcessStep::Case { mode, words } => Box::new(CaseRule::new(*mode, words)?),
+        PostProcessStep::TrailingPunctuation { policy } => {
+            Box::new(TrailingPunctuationPolicy { policy: *policy })
+        }
+        PostProcessStep::SpokenPunctuation {
+            language: step_language,
+            escape_word,
+        } => Box::new(SpokenPunctuation::new(
+            step_language.as_deref().unwrap_or(language),
+            escape_word.as_deref(),
+        )),
+    })
+}
+
+/// Collapse runs of spaces left behind by removals and trim the ends.
+fn tidy_spaces(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut previous_space = false;
+    for c in text.chars() {
+        if c == ' ' {
+            if !previous_space {
+                result.push(c);
+            }
+            previous_space = true;
+        } else {
+            previous_space = false;
+            result.push(c);
+        }
+    }
+    result.trim().to_string()
+}
+
+fn word_pattern(words: &[String]) -> String {
+    let alternatives: Vec<String> = words.iter().map(|w| regex::escape(w.trim())).collect();
+    format!(r"\b(?:{})\b", alternatives.join("|"))
+}
+
+struct RegexReplace {
+    regex: Regex,
+    replacement: String,
+}
+
+impl RegexReplace {
+    fn new(pattern: &str, replacement: String, case_insensitive: bool) -> Result<Self> {
+        let regex = RegexBuilder::new(pattern)
+            .case_insensitive(case_insensitive)
+            .build()
+            .with_context(|| format!("Invalid postprocess regex: {}", pattern))?;
+        Ok(Self { regex, replacement })
+    }
+}
+
+impl TranscriptionNormalizer for RegexReplace {
+    fn normalize(&self, raw_output: &str) -> String {
+        self.regex
+            .replace_all(raw_output, self.replacement.as_str())
+            .into_owned()
+    }
+
+    fn name(&self) -> &'static str {
+        "regex"
+    }
+}
+
+struct FillerRemoval {
+    regex: Option<Regex>,
+}
+
+impl FillerRemoval {
+    fn new(words: &[String]) -> Result<Self> {
+        if words.is_empty() {
+            return Ok(Self { regex: None });
+        }
+        // Also swallow the comma that usually follows a filler ("Um, so...")
+        let pattern = format!(r"(?i){}[,]?", word_pattern(words));
+        let regex = Regex::new(&pattern).context("Invalid filler word list")?;
+        Ok(Self { regex: Some(regex) })
+    }
+}
+
+impl TranscriptionNormalizer for FillerRemoval {
+    fn normalize(&self, raw_output: &str) -> String {
+        match &self.regex {
+            Some(regex) => tidy_spaces(&regex.replace_all(raw_output, "")),
+            None => raw_output.to_string(),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "fillers"
+    }
+}
+
+struct CaseRule {
+    mode: CaseMode,
+    words: Vec<(Regex, String)>,
+}
+
+impl CaseRule {
+    fn new(mode: CaseMode, words: &[String]) -> Result<Self> {
+        let words = words
+            .iter()
+            .map(|word| {
+                let regex = Regex::new(&format!(
+                    r"(?i){}",
+                    word_pattern(std::slice::from_ref(word))
+                ))
+                .context("Invalid case word")?;
+                Ok((regex, word.trim().to_string()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { mode, words })
+    }
+}
+
+fn sentence_case(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut capitalize = true;
+    for c in text.chars() {
+        if capitalize && c.is_alphabetic() {
+            result.extend(c.to_uppercase());
+            capitalize = false;
+        } else {
+            result.push(c);
+            if SENTENCE_END.contains(&c) || c == '\n' {
+                capitalize = true;
+            } else if !c.is_whitespace() && !matches!(c, '"' | '\'' | '(' | '[') {
+                capitalize = false;
+            }
+        }
+    }
+    result
+}
+
+impl TranscriptionNormalizer for CaseRule {
+    fn normalize(&self, raw_output: &str) -> String {
+        let mut text = match self.mode {
+            CaseMode::Keep => raw_output.to_string(),
+            CaseMode::Sentence => sentence_case(raw_output),
+            CaseMode::Lower => raw_output.to_lowercase(),
+            CaseMode::Upper => raw_output.to_uppercase(),
+        };
+        for (regex, word) in &self.words {
+            text = regex.replace_all(&text, word.as_str()).into_owned();
+        }
+        text
+    }
+
+    fn name(&self) -> &'static str {
+        "case"
+    }
+}
+
+struct TrailingPunctuationPolicy {
+    policy: TrailingPunctuation,
+}
+
+impl TranscriptionNormalizer for TrailingPunctuationPolicy {
+    fn normalize(&self, raw_output: &str) -> String {
+        let text = raw_output.trim_end();
+        match self.policy {
+            TrailingPunctuation::Keep => raw_output.to_string(),
+            TrailingPunctuation::Remove => text.trim_end_matches(TRAILING_PUNCTUATION).to_string(),
+            TrailingPunctuation::Ensure => {
+                if text.is_empty() || text.ends_with(SENTENCE_END) {
+                    text.to_string()
+                } else {
+                    format!("{}.", text.trim_end_matches(TRAILING_PUNCTUATION))
+                }
+            }
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "trailing_punctuation"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::default_filler_words;
+
+    fn process(steps: Vec<PostProcessStep>, input: &str) -> String {
+        PostProcessor::from_steps(&steps, None)
+            .unwrap()
+            .process(input)
+    }
+
+    #[test]
+    fn test_empty_chain_is_identity() {
+        let processor = PostProcessor::new();
+        assert!(processor.is_empty());
+        assert_eq!(processor.process(" as is "), " as is ");
+    }
+
+    #[test]
+    fn test_itn_runs_before_steps() {
+        let whisper = WhisperConfig {
+            itn: true,
+            ..WhisperConfig::default()
+        };
+        let steps = vec![PostProcessStep::TrailingPunctuation {
+            policy: TrailingPunctuation::Ensure,
+        }];
+        let processor = PostProcessor::for_profile(&whisper, &steps).unwrap();
+        assert_eq!(processor.process("twenty five dollars"), "$25.");
+
+        let processor = PostProcessor::for_profile(&WhisperConfig::default(), &[]).unwrap();
+        assert!(processor.is_empty());
+    }
+
+    #[test]
+    fn test_regex_replacement() {
+        let steps = vec![PostProcessStep::Regex {
+            pattern: r"\bgit hub\b".to_string(),
+            replacement: "GitHub".to_string(),
+            case_insensitive: true,
+        }];
+        assert_eq!(process(steps, "Push to Git Hub now"), "Push to GitHub now");
+
+        let invalid = vec![PostProcessStep::Regex {
+            pattern: "(".to_string(),
+            replacement: String::new(),
+            case_insensitive: false,
+        }];
+        assert!(PostProcessor::from_steps(&invalid, None).is_err());
+    }
+
+    #[test]
+    fn test_filler_removal() {
+        let steps = vec![PostProcessStep::Fillers {
+            words: default_filler_words(),
+        }];
+        assert_eq!(
+            process(steps, "Um, so I think uh we should ship it"),
+            "so I think we should ship it"
+        );
+    }
+
+    #[test]
+    fn test_filler_removal_keeps_words_containing_fillers() {
+        let steps = vec![PostProcessStep::Fillers {
+            words: default_filler_words(),
+        }];
+        assert_eq!(process(steps, "Umbrella error"), "Umbrella error");
+    }
+
+    #[test]
+    fn test_case_rules() {
+        let sentence = vec![PostProcessStep::Case {
+            mode: CaseMode::Sentence,
+            words: vec!["WisprArch".to_string()],
+        }];
+        assert_eq!(
+            process(sentence, "so wisprarch works. does it? yes"),
+            "So WisprArch works. Does it? Yes"
+        );
+
+        let lower = vec![PostProcessStep::Case {
+            mode: CaseMode::Lower,
+            words: Vec::new(),
+        }];
+        assert_eq!(process(lower, "Hello World"), "hello world");
+    }
+
+    #[test]
+    fn test_trailing_punctuation() {
+        let remove = vec![PostProcessStep::TrailingPunctuation {
+            policy: TrailingPunctuation::Remove,
+        }];
+        assert_eq!(process(remove, "search term."), "search term");
+
+        let ensure = vec![PostProcessStep::TrailingPunctuation {
+            policy: TrailingPunctuation::Ensure,
+        }];
+        assert_eq!(process(ensure.clone(), "hello,"), "hello.");
+        assert_eq!(process(ensure.clone(), "really?"), "really?");
+        assert_eq!(process(ensure, ""), "");
+    }
+
+    #[test]
+    fn test_steps_run_in_order_and_trace() {
+        let steps = vec![
+            PostProcessStep::Fillers {
+                words: default_filler_words(),
+            },
+            PostProcessStep::Case {
+                mode: CaseMode::Sentence,
+                words: Vec::new(),
+            },
+            PostProcessStep::TrailingPunctuation {
+                policy: TrailingPunctuation::Ensure,
+            },
+        ];
+        let processor = PostProcessor::from_steps(&steps, None).unwrap();
+        let trace = processor.trace("um, hello there");
+        assert_eq!(trace.len(), 3);
+        assert_eq!(trace[0], ("fillers", "hello there".to_string()));
+        assert_eq!(trace[2].1, "Hello there.");
+    }
+
+    #[test]
+    fn test_spoken_punctuation_uses_transcription_language() {
+        let steps = vec![PostProcessStep::SpokenPunctuation {
+            language: None,
+            escape_word: None,
+        }];
+        let german = PostProcessor::from_steps(&steps, Some("de")).unwrap();
+        assert_eq!(german.process("ja komma nein"), "ja, nein");
+    }
+
+    #[test]
+    fn test_steps_parse_from_toml() {
+        #[derive(serde::Deserialize)]
+        struct Wrapper {
+            postprocess: Vec<PostProcessStep>,
+        }
+
+        let parsed: Wrapper = toml::from_str(
+            r#"
+            [[postprocess]]
+            type = "fillers"
+
+            [[postprocess]]
+            type = "trailing_punctuation"
+            policy = "remove"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            parsed.postprocess[0],
+            PostProcessStep::Fillers {
+                words: default_filler_words()
+            }
+        );
+        assert_eq!(parsed.postprocess.len(), 2);
+    }
+}
@@ -0,0 +1,228 @@
+//! Snippet expansion.
+//!
+//! Replaces spoken trigger phrases from the `[snippets]` table, such as
+//! "insert signature" or "my email", with stored text. Templates may use
+//! `{date}`, `{time}` and `{clipboard}`. When the whole transcript is a
+//! trigger the result is just the expansion, without the surrounding
+//! punctuation the provider added.
+
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, Context, Result};
+use regex::{Captures, Regex, RegexBuilder};
+use tracing::warn;
+
+use super::TranscriptionNormalizer;
+use crate::text_io::read_clipboard_sync;
+
+/// Characters allowed between the words of a trigger and around a transcript
+/// that is only a trigger.
+const SEPARATORS: &[char] = &[',', '.', ';', ':', '!', '?', '…', '-'];
+
+type VariableResolver = Box<dyn Fn(&str) -> Option<String> + Send + Sync>;
+
+pub struct SnippetExpander {
+    regex: Option<Regex>,
+    templates: Vec<String>,
+    variable: VariableResolver,
+}
+
+impl SnippetExpander {
+    pub fn new(snippets: &BTreeMap<String, String>) -> Result<Self> {
+        // Longer triggers first so "my work email" wins over "my email".
+        let mut entries: Vec<(&String, &String)> = snippets.iter().collect();
+        entries.sort_by_key(|(trigger, _)| std::cmp::Reverse(trigger.split_whitespace().count()));
+
+        let mut alternatives = Vec::with_capacity(entries.len());
+        let mut templates = Vec::with_capacity(entries.len());
+        for (trigger, template) in entries {
+            let words: Vec<String> = trigger
+                .split(|c: char| c.is_whitespace() || SEPARATORS.contains(&c))
+                .filter(|w| !w.is_empty())
+                .map(regex::escape)
+                .collect();
+            if words.is_empty() {
+                return Err(anyhow!("Snippet trigger {:?} has no words", trigger));
+            }
+            alternatives.push(format!(r"({})", words.join(r"[\s,.;:!?…-]+")));
+            templates.push(template.clone());
+        }
+
+        let regex = if alternatives.is_empty() {
+            None
+        } else {
+            let pattern = format!(r"\b(?:{})\b", alternatives.join("|"));
+            Some(
+                RegexBuilder::new(&pattern)
+                    .case_insensitive(true)
+                    .build()
+                    .context("Failed to build snippet triggers")?,
+            )
+        };
+
+        Ok(Self {
+            regex,
+            templates,
+            variable: Box::new(builtin_variable),
+        })
+    }
+
+    /// Replace how template variables are resolved. Unknown variables
+    /// (`None`) are left in the text as written.
+    pub fn with_variables(
+        mut self,
+        resolver: impl Fn(&str) -> Option<String> + Send + Sync + 'static,
+    ) -> Self {
+        self.variable = Box::new(resolver);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.templates.is_empty()
+    }
+
+    fn render(&self, caps: &Captures) -> String {
+        let index = (1..caps.len())
+            .find(|&i| caps.get(i).is_some())
+            .map(|i| i - 1)
+            .unwrap_or_default();
+        render_template(&self.templates[index], &self.variable)
+    }
+}
+
+impl TranscriptionNormalizer for SnippetExpander {
+    fn normalize(&self, raw_output: &str) -> String {
+        let Some(regex) = &self.regex else {
+            return raw_output.to_string();
+        };
+
+        let bare = raw_output.trim_matches(|c: char| c.is_whitespace() || SEPARATORS.contains(&c));
+        if let Some(caps) = regex.captures(bare) {
+            if caps.get(0).is_some_and(|m| m.as_str().len() == bare.len()) {
+                return self.render(&caps);
+            }
+        }
+
+        regex
+            .replace_all(raw_output, |caps: &Captures| self.render(caps))
+            .into_owned()
+    }
+
+    fn name(&self) -> &'static str {
+        "snippets"
+    }
+}
+
+/// Replace `{name}` variables in a template. Variables the resolver doesn't
+/// know are kept as written.
+pub(crate) fn render_template(template: &str, variable: &dyn Fn(&str) -> Option<String>) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let name_len = after
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(after.len());
+        if name_len > 0 && after[name_len..].starts_with('}') {
+            let name = &after[..name_len];
+            match variable(name) {
+                Some(value) => result.push_str(&value),
+                None => result.push_str(&rest[start..start + name_len + 2]),
+            }
+            rest = &after[name_len + 1..];
+        } else {
+            result.push('{');
+            rest = after;
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+fn builtin_variable(name: &str) -> Option<String> {
+    let now = chrono::Local::now();
+    match name {
+        "date" => Some(now.format("%Y-%m-%d").to_string()),
+        "time" => Some(now.format("%H:%M").to_string()),
+        "clipboard" => Some(read_clipboard_sync().unwrap_or_else(|e| {
+            warn!("Snippet could not read the clipboard: {:#}", e);
+            String::new()
+        })),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expander(snippets: &[(&str, &str)]) -> SnippetExpander {
+        let map = snippets
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        SnippetExpander::new(&map)
+            .unwrap()
+            .with_variables(|name| match name {
+                "date" => Some("2026-03-03".to_string()),
+                "time" => Some("09:30".to_string()),
+                "clipboard" => Some("https://example.com".to_string()),
+                _ => None,
+            })
+    }
+
+    #[test]
+    fn test_whole_transcript_trigger_drops_punctuation() {
+        let snippets = expander(&[("insert signature", "Best,\nAna")]);
+        assert_eq!(snippets.normalize("Insert signature."), "Best,\nAna");
+        assert_eq!(snippets.normalize("insert, signature!"), "Best,\nAna");
+    }
+
+    #[test]
+    fn test_trigger_inside_sentence() {
+        let snippets = expander(&[("my email", "ana@example.com")]);
+        assert_eq!(
+            snippets.normalize("Send it to my email, please."),
+            "Send it to ana@example.com, please."
+        );
+        assert_eq!(snippets.normalize("Enemy emails"), "Enemy emails");
+    }
+
+    #[test]
+    fn test_longer_trigger_wins() {
+        let snippets = expander(&[
+            ("my email", "ana@example.com"),
+            ("my work email", "ana@work.example"),
+        ]);
+        assert_eq!(
+            snippets.normalize("Use my work email"),
+            "Use ana@work.example"
+        );
+    }
+
+    #[test]
+    fn test_template_variables() {
+        let snippets = expander(&[
+            ("stamp", "{date} {time}"),
+            ("paste link", "See {clipboard}"),
+            ("braces", "{unknown} {not a var} {"),
+        ]);
+        assert_eq!(snippets.normalize("stamp"), "2026-03-03 09:30");
+        assert_eq!(snippets.normalize("paste link"), "See https://example.com");
+        assert_eq!(snippets.normalize("braces"), "{unknown} {not a var} {");
+    }
+
+    #[test]
+    fn test_replacement_is_literal() {
+        let snippets = expander(&[("price", "$1 and ${2}")]);
+        assert_eq!(snippets.normalize("price"), "$1 and ${2}");
+    }
+
+    #[test]
+    fn test_empty_trigger_is_rejected() {
+        let map = BTreeMap::from([(" , ".to_string(), "x".to_string())]);
+        assert!(SnippetExpander::new(&map).is_err());
+        assert!(SnippetExpander::new(&BTreeMap::new()).unwrap().is_empty());
+    }
+}
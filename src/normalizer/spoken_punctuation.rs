@@ -0,0 +1,348 @@
+//! Spoken punctuation and layout commands.
+//!
+//! Turns dictated phrases such as "comma", "new line" or "open paren" into
+//! symbols and line breaks. Each language has its own vocabulary; saying the
+//! escape word ("literal" in English) before a phrase keeps it as text.
+
+use tracing::warn;
+
+use super::TranscriptionNormalizer;
+
+/// How a symbol is joined to its neighbours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Spacing {
+    /// Attaches to the previous word: `,` `.` `)`
+    Close,
+    /// Attaches to the next word: `(` opening quote
+    Open,
+    /// Joins both neighbours: `-`
+    Join,
+    /// Separated by spaces on both sides: ` - `
+    Spaced,
+    /// Starts a new line; surrounding spaces are dropped
+    Layout,
+}
+
+struct Command {
+    phrase: &'static str,
+    output: &'static str,
+    spacing: Spacing,
+}
+
+const fn cmd(phrase: &'static str, output: &'static str, spacing: Spacing) -> Command {
+    Command {
+        phrase,
+        output,
+        spacing,
+    }
+}
+
+const ENGLISH: &[Command] = &[
+    cmd("comma", ",", Spacing::Close),
+    cmd("period", ".", Spacing::Close),
+    cmd("full stop", ".", Spacing::Close),
+    cmd("question mark", "?", Spacing::Close),
+    cmd("exclamation mark", "!", Spacing::Close),
+    cmd("exclamation point", "!", Spacing::Close),
+    cmd("colon", ":", Spacing::Close),
+    cmd("semicolon", ";", Spacing::Close),
+    cmd("ellipsis", "...", Spacing::Close),
+    cmd("open paren", "(", Spacing::Open),
+    cmd("open parenthesis", "(", Spacing::Open),
+    cmd("close paren", ")", Spacing::Close),
+    cmd("close parenthesis", ")", Spacing::Close),
+    cmd("open bracket", "[", Spacing::Open),
+    cmd("close bracket", "]", Spacing::Close),
+    cmd("open quote", "\"", Spacing::Open),
+    cmd("begin quote", "\"", Spacing::Open),
+    cmd("close quote", "\"", Spacing::Close),
+    cmd("end quote", "\"", Spacing::Close),
+    cmd("hyphen", "-", Spacing::Join),
+    cmd("dash", "-", Spacing::Spaced),
+    cmd("new line", "\n", Spacing::Layout),
+    cmd("newline", "\n", Spacing::Layout),
+    cmd("new paragraph", "\n\n", Spacing::Layout),
+    cmd("bullet point", "\n- ", Spacing::Layout),
+];
+
+const GERMAN: &[Command] = &[
+    cmd("komma", ",", Spacing::Close),
+    cmd("punkt", ".", Spacing::Close),
+    cmd("fragezeichen", "?", Spacing::Close),
+    cmd("ausrufezeichen", "!", Spacing::Close),
+    cmd("doppelpunkt", ":", Spacing::Close),
+    cmd("semikolon", ";", Spacing::Close),
+    cmd("klammer auf", "(", Spacing::Open),
+    cmd("klammer zu", ")", Spacing::Close),
+    cmd("anführungszeichen auf", "\"", Spacing::Open),
+    cmd("anführungszeichen zu", "\"", Spacing::Close),
+    cmd("bindestrich", "-", Spacing::Join),
+    cmd("gedankenstrich", "-", Spacing::Spaced),
+    cmd("neue zeile", "\n", Spacing::Layout),
+    cmd("neuer absatz", "\n\n", Spacing::Layout),
+    cmd("aufzählungspunkt", "\n- ", Spacing::Layout),
+];
+
+const SPANISH: &[Command] = &[
+    cmd("coma", ",", Spacing::Close),
+    cmd("punto", ".", Spacing::Close),
+    cmd("punto y coma", ";", Spacing::Close),
+    cmd("dos puntos", ":", Spacing::Close),
+    cmd("signo de interrogación", "?", Spacing::Close),
+    cmd("signo de exclamación", "!", Spacing::Close),
+    cmd("abrir paréntesis", "(", Spacing::Open),
+    cmd("cerrar paréntesis", ")", Spacing::Close),
+    cmd("abrir comillas", "\"", Spacing::Open),
+    cmd("cerrar comillas", "\"", Spacing::Close),
+    cmd("guion", "-", Spacing::Join),
+    cmd("nueva línea", "\n", Spacing::Layout),
+    cmd("nuevo párrafo", "\n\n", Spacing::Layout),
+    cmd("viñeta", "\n- ", Spacing::Layout),
+];
+
+const FRENCH: &[Command] = &[
+    cmd("virgule", ",", Spacing::Close),
+    cmd("point", ".", Spacing::Close),
+    cmd("point virgule", ";", Spacing::Close),
+    cmd("deux points", ":", Spacing::Close),
+    cmd("point d'interrogation", "?", Spacing::Close),
+    cmd("point d'exclamation", "!", Spacing::Close),
+    cmd("ouvrir la parenthèse", "(", Spacing::Open),
+    cmd("fermer la parenthèse", ")", Spacing::Close),
+    cmd("ouvrir les guillemets", "\"", Spacing::Open),
+    cmd("fermer les guillemets", "\"", Spacing::Close),
+    cmd("trait d'union", "-", Spacing::Join),
+    cmd("tiret", "-", Spacing::Spaced),
+    cmd("à la ligne", "\n", Spacing::Layout),
+    cmd("nouvelle ligne", "\n", Spacing::Layout),
+    cmd("nouveau paragraphe", "\n\n", Spacing::Layout),
+    cmd("puce", "\n- ", Spacing::Layout),
+];
+
+/// Vocabulary and default escape word for a language code.
+fn vocabulary(language: &str) -> Option<(&'static [Command], &'static str)> {
+    match language {
+        "en" => Some((ENGLISH, "literal")),
+        "de" => Some((GERMAN, "wörtlich")),
+        "es" => Some((SPANISH, "literal")),
+        "fr" => Some((FRENCH, "littéral")),
+        _ => None,
+    }
+}
+
+/// Punctuation the recognizer may attach to dictated command words.
+const TOKEN_PUNCTUATION: &[char] = &['.', ',', '!', '?', ';', ':', '"'];
+
+pub struct SpokenPunctuation {
+    /// Commands as lowercase word sequences, longest first
+    commands: Vec<(Vec<String>, &'static Command)>,
+    escape_word: String,
+}
+
+impl SpokenPunctuation {
+    /// Create the stage for a language, falling back to English.
+    pub fn new(language: &str, escape_word: Option<&str>) -> Self {
+        let (vocabulary, default_escape) = vocabulary(language).unwrap_or_else(|| {
+            if language != "auto" {
+                warn!(
+                    "No spoken punctuation vocabulary for '{}', using English",
+                    language
+                );
+            }
+            (ENGLISH, "literal")
+        });
+
+        let mut commands: Vec<(Vec<String>, &'static Command)> = vocabulary
+            .iter()
+            .map(|command| {
+                let words = command.phrase.split(' ').map(str::to_string).collect();
+                (words, command)
+            })
+            .collect();
+        commands.sort_by_key(|(words, _)| std::cmp::Reverse(words.len()));
+
+        Self {
+            commands,
+            escape_word: escape_word.unwrap_or(default_escape).to_lowercase(),
+        }
+    }
+
+    /// Longest command starting at `tokens[start]`, with its length in words.
+    fn match_command(&self, keys: &[String], start: usize) -> Option<(usize, &'static Command)> {
+        self.commands.iter().find_map(|(words, command)| {
+            let end = start + words.len();
+            (end <= keys.len() && keys[start..end] == words[..]).then_some((words.len(), *command))
+        })
+    }
+}
+
+fn match_key(token: &str) -> String {
+    token.trim_matches(TOKEN_PUNCTUATION).to_lowercase()
+}
+
+/// Joins words and symbols with the spacing each symbol asks for.
+#[derive(Default)]
+struct Output {
+    text: String,
+    glue_next: bool,
+}
+
+impl Output {
+    fn needs_space(&self) -> bool {
+        !self.text.is_empty() && !self.glue_next && !self.text.ends_with('\n')
+    }
+
+    fn word(&mut self, word: &str) {
+        if self.needs_space() {
+            self.text.push(' ');
+        }
+        self.text.push_str(word);
+        self.glue_next = false;
+    }
+
+    fn command(&mut self, command: &Command) {
+        match command.spacing {
+            Spacing::Close | Spacing::Join => {
+                // The recognizer may already have written the symbol itself
+                if !self.text.ends_with(command.output) {
+                    self.text.push_str(command.output);
+                }
+                self.glue_next = command.spacing == Spacing::Join;
+            }
+            Spacing::Open | Spacing::Spaced => {
+                if self.needs_space() {
+                    self.text.push(' ');
+                }
+                self.text.push_str(command.output);
+                self.glue_next = command.spacing == Spacing::Open;
+            }
+            Spacing::Layout => {
+                self.text.truncate(self.text.trim_end_matches(' ').len());
+                let output = if self.text.is_empty() {
+                    command.output.trim_start_matches('\n')
+                } else {
+                    command.output
+                };
+                self.text.push_str(output);
+                self.glue_next = true;
+            }
+        }
+    }
+}
+
+impl TranscriptionNormalizer for SpokenPunctuation {
+    fn normalize(&self, raw_output: &str) -> String {
+        let tokens: Vec<&str> = raw_output.split_whitespace().collect();
+        let keys: Vec<String> = tokens.iter().map(|token| match_key(token)).collect();
+
+        let mut output = Output::default();
+        let mut i = 0;
+        while i < tokens.len() {
+            if keys[i] == self.escape_word {
+                if let Some((len, _)) = self.match_command(&keys, i + 1) {
+                    for token in &tokens[i + 1..i + 1 + len] {
+                        output.word(token);
+                    }
+                    i += 1 + len;
+                    continue;
+                }
+            }
+
+            match self.match_command(&keys, i) {
+                Some((len, command)) => {
+                    output.command(command);
+                    i += len;
+                }
+                None => {
+                    output.word(tokens[i]);
+                    i += 1;
+                }
+            }
+        }
+
+        output.text.trim_end_matches(' ').to_string()
+    }
+
+    fn name(&self) -> &'static str {
+        "spoken_punctuation"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn english(input: &str) -> String {
+        SpokenPunctuation::new("en", None).normalize(input)
+    }
+
+    #[test]
+    fn test_basic_punctuation() {
+        assert_eq!(
+            english("hello comma world period how are you question mark"),
+            "hello, world. how are you?"
+        );
+    }
+
+    #[test]
+    fn test_recognizer_punctuation_on_commands() {
+        assert_eq!(english("Hello, comma, world. Period."), "Hello, world.");
+    }
+
+    #[test]
+    fn test_brackets_and_quotes() {
+        assert_eq!(
+            english("call open paren x close paren and say open quote hi close quote"),
+            "call (x) and say \"hi\""
+        );
+        assert_eq!(
+            english("well hyphen known dash right"),
+            "well-known - right"
+        );
+    }
+
+    #[test]
+    fn test_layout_commands() {
+        assert_eq!(
+            english("shopping list colon bullet point milk bullet point eggs new paragraph thanks"),
+            "shopping list:\n- milk\n- eggs\n\nthanks"
+        );
+        assert_eq!(english("new line hello"), "hello");
+    }
+
+    #[test]
+    fn test_escape_word_keeps_phrase() {
+        assert_eq!(
+            english("type literal comma here comma please"),
+            "type comma here, please"
+        );
+        assert_eq!(english("literal translation"), "literal translation");
+    }
+
+    #[test]
+    fn test_other_languages() {
+        let german = SpokenPunctuation::new("de", None);
+        assert_eq!(
+            german.normalize("hallo komma welt neue zeile tschüss"),
+            "hallo, welt\ntschüss"
+        );
+
+        let spanish = SpokenPunctuation::new("es", None);
+        assert_eq!(
+            spanish.normalize("hola punto y coma adiós punto"),
+            "hola; adiós."
+        );
+
+        let french = SpokenPunctuation::new("fr", Some("verbatim"));
+        assert_eq!(
+            french.normalize("oui point d'interrogation verbatim virgule"),
+            "oui? virgule"
+        );
+    }
+
+    #[test]
+    fn test_unknown_language_uses_english() {
+        let stage = SpokenPunctuation::new("xx", None);
+        assert_eq!(stage.normalize("a comma b"), "a, b");
+    }
+}
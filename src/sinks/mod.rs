@@ -0,0 +1,600 @@
+//! Output sinks.
+//!
+//! Besides the clipboard and injection, every completed job is passed to the
+//! `[[sinks]]` outputs: a file the rendered template is appended to, a named
+//! pipe, a webhook receiving the job as JSON, or a command reading the text on
+//! stdin. Sinks run in the background and a failing sink only logs a warning.
+//! A job can pick its sinks by name; otherwise the enabled ones run.
+
+use crate::audio::{CompletedJob, JobOptions};
+use crate::config::{DictationMode, SinkConfig, SinkKind};
+use crate::global::expand_home;
+use crate::normalizer::render_template;
+use anyhow::{anyhow, bail, Context, Result};
+use futures_util::future::join_all;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tracing::{debug, warn};
+
+/// How long a FIFO reader gets to take a transcript.
+const FIFO_WRITE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A completed job as passed to sinks. Webhooks receive it as JSON.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SinkJob {
+    pub job_id: String,
+    pub history_id: i64,
+    pub text: String,
+    /// Transcript before post-processing
+    pub raw_text: String,
+    pub created_at: String,
+    pub mode: DictationMode,
+    pub language: Option<String>,
+    /// `[[apps]]` rule matched when the recording started
+    pub app: Option<String>,
+}
+
+impl SinkJob {
+    pub fn new(job: &CompletedJob, raw_text: &str, options: &JobOptions) -> Self {
+        Self {
+            job_id: job.job_id.clone(),
+            history_id: job.history_id,
+            text: job.text.clone(),
+            raw_text: raw_text.to_string(),
+            created_at: job.created_at.clone(),
+            mode: options.mode,
+            language: options.language.clone(),
+            app: options.app.clone(),
+        }
+    }
+
+    /// Value of a template variable.
+    fn variable(&self, name: &str) -> Option<String> {
+        let now = chrono::Local::now();
+        match name {
+            "text" => Some(self.text.clone()),
+            "raw_text" => Some(self.raw_text.clone()),
+            "job_id" => Some(self.job_id.clone()),
+            "history_id" => Some(self.history_id.to_string()),
+            "created_at" => Some(self.created_at.clone()),
+            "date" => Some(now.format("%Y-%m-%d").to_string()),
+            "time" => Some(now.format("%H:%M").to_string()),
+            "mode" => Some(self.mode.as_str().to_string()),
+            "language" => Some(self.language.clone().unwrap_or_default()),
+            "app" => Some(self.app.clone().unwrap_or_default()),
+            _ => None,
+        }
+    }
+
+    fn render(&self, template: &str) -> String {
+        render_template(template, &|name| self.variable(name))
+    }
+
+    /// Environment variables for command sinks.
+    fn env(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("WISPRARCH_JOB_ID", self.job_id.clone()),
+            ("WISPRARCH_HISTORY_ID", self.history_id.to_string()),
+            ("WISPRARCH_CREATED_AT", self.created_at.clone()),
+            ("WISPRARCH_RAW_TEXT", self.raw_text.clone()),
+            ("WISPRARCH_MODE", self.mode.as_str().to_string()),
+            (
+                "WISPRARCH_LANGUAGE",
+                self.language.clone().unwrap_or_default(),
+            ),
+            ("WISPRARCH_APP", self.app.clone().unwrap_or_default()),
+        ]
+    }
+}
+
+/// The configured sinks.
+#[derive(Debug, Clone, Default)]
+pub struct Sinks {
+    sinks: Arc<[SinkConfig]>,
+    client: reqwest::Client,
+}
+
+impl Sinks {
+    pub fn new(configs: &[SinkConfig]) -> Result<Self> {
+        let mut names = HashSet::new();
+        for config in configs {
+            if config.name.trim().is_empty() {
+                bail!("Every [[sinks]] entry needs a name");
+            }
+            if !names.insert(config.name.as_str()) {
+                bail!("Duplicate sink name {:?}", config.name);
+            }
+            match &config.kind {
+                SinkKind::File { path, .. } | SinkKind::Fifo { path, .. } if path.is_empty() => {
+                    bail!("Sink {:?} needs a path", config.name)
+                }
+                SinkKind::Webhook { url, .. } if url.is_empty() => {
+                    bail!("Sink {:?} needs a url", config.name)
+                }
+                SinkKind::Command { command, .. } if command.is_empty() => {
+                    bail!("Sink {:?} needs a command", config.name)
+                }
+                _ => {}
+            }
+        }
+
+        Ok(Self {
+            sinks: configs.into(),
+            client: reqwest::Client::new(),
+        })
+    }
+
+    /// Check that a job's sink names exist.
+    pub fn check_names(&self, names: &[String]) -> Result<(), String> {
+        match names
+            .iter()
+            .find(|name| !self.sinks.iter().any(|sink| &sink.name == *name))
+        {
+            Some(name) => Err(format!(
+                "Unknown sink '{}'. Configured sinks: {}",
+                name,
+                self.sinks
+                    .iter()
+                    .map(|sink| sink.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// The sinks a job runs: the named ones, or the enabled ones when the
+    /// job doesn't pick.
+    fn select(&self, names: Option<&[String]>) -> Vec<SinkConfig> {
+        self.sinks
+            .iter()
+            .filter(|sink| match names {
+                Some(names) => names.contains(&sink.name),
+                None => sink.enabled,
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Send a completed job to its sinks and return each sink's result.
+    pub async fn send(&self, names: Option<&[String]>, job: &SinkJob) -> Vec<(String, Result<()>)> {
+        let selected = self.select(names);
+        let results = join_all(
+            selected
+                .iter()
+                .map(|sink| run_sink(&self.client, &sink.kind, job)),
+        )
+        .await;
+        selected
+            .into_iter()
+            .map(|sink| sink.name)
+            .zip(results)
+            .collect()
+    }
+
+    /// Send a completed job to its sinks in the background.
+    pub fn dispatch(&self, names: Option<&[String]>, job: SinkJob) {
+        if self.select(names).is_empty() {
+            return;
+        }
+
+        let sinks = self.clone();
+        let names = names.map(<[String]>::to_vec);
+        tokio::spawn(async move {
+            for (name, result) in sinks.send(names.as_deref(), &job).await {
+                match result {
+                    Ok(()) => debug!("Sent job {} to sink {:?}", job.job_id, name),
+                    Err(e) => warn!("Sink {:?} failed: {:#}", name, e),
+                }
+            }
+        });
+    }
+}
+
+async fn run_sink(client: &reqwest::Client, kind: &SinkKind, job: &SinkJob) -> Result<()> {
+    match kind {
+        SinkKind::File { path, template } => {
+            let path = expand_home(path);
+            let content = job.render(template);
+            tokio::task::spawn_blocking(move || append_to_file(&path, &content))
+                .await
+                .context("File sink task panicked")?
+        }
+        SinkKind::Fifo { path, template } => {
+            let path = expand_home(path);
+            let content = job.render(template);
+            let write = tokio::task::spawn_blocking(move || write_to_fifo(&path, &content));
+            tokio::time::timeout(FIFO_WRITE_TIMEOUT, write)
+                .await
+                .map_err(|_| {
+                    anyhow!(
+                        "FIFO reader didn't take the transcript within {}s",
+                        FIFO_WRITE_TIMEOUT.as_secs()
+                    )
+                })?
+                .context("FIFO sink task panicked")?
+        }
+        SinkKind::Webhook {
+            url,
+            headers,
+            timeout_secs,
+        } => post_webhook(client, url, headers, *timeout_secs, job).await,
+        SinkKind::Command {
+            command,
+            args,
+            timeout_secs,
+        } => run_command(command, args, *timeout_secs, job).await,
+    }
+}
+
+fn append_to_file(path: &PathBuf, content: &str) -> Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(content.as_bytes()))
+        .with_context(|| format!("Failed to append to {}", path.display()))
+}
+
+fn write_to_fifo(path: &PathBuf, content: &str) -> Result<()> {
+    use std::os::fd::AsRawFd;
+    use std::os::unix::fs::{FileTypeExt, OpenOptionsExt};
+
+    let metadata = std::fs::metadata(path)
+        .with_context(|| format!("FIFO {} not found; create it with mkfifo", path.display()))?;
+    if !metadata.file_type().is_fifo() {
+        bail!("{} is not a FIFO", path.display());
+    }
+
+    // Open non-blocking, so a pipe nobody reads fails instead of hanging
+    let mut fifo = std::fs::OpenOptions::new()
+        .write(true)
+        .custom_flags(libc::O_NONBLOCK)
+        .open(path)
+        .map_err(|e| match e.raw_os_error() {
+            Some(libc::ENXIO) => anyhow!("No process is reading {}", path.display()),
+            _ => anyhow!("Failed to open {}: {}", path.display(), e),
+        })?;
+
+    // Then write blocking, so a slow reader gets the whole transcript
+    // instead of a write that stops with EAGAIN once the pipe is full
+    let fd = fifo.as_raw_fd();
+    // SAFETY: `fd` is owned by `fifo`, which is open for the whole block;
+    // F_GETFL and F_SETFL only read and set its status flags.
+    let cleared = unsafe {
+        let flags = libc::fcntl(fd, libc::F_GETFL);
+        flags >= 0 && libc::fcntl(fd, libc::F_SETFL, flags & !libc::O_NONBLOCK) >= 0
+    };
+    if !cleared {
+        return Err(std::io::Error::last_os_error())
+            .with_context(|| format!("Failed to configure {}", path.display()));
+    }
+
+    fifo.write_all(content.as_bytes())
+        .with_context(|| format!("Failed to write to {}", path.display()))
+}
+
+async fn post_webhook(
+    client: &reqwest::Client,
+    url: &str,
+    headers: &BTreeMap<String, String>,
+    timeout_secs: u64,
+    job: &SinkJob,
+) -> Result<()> {
+    let mut request = client
+        .post(url)
+        .timeout(Duration::from_secs(timeout_secs))
+        .json(job);
+    for (name, value) in headers {
+        request = request.header(name, value);
+    }
+
+    let response = request
+        .send()
+        .await
+        .with_context(|| format!("Failed to POST to {}", url))?;
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        bail!("{} returned {}: {}", url, status, body.trim());
+    }
+    Ok(())
+}
+
+async fn run_command(
+    command: &str,
+    args: &[String],
+    timeout_secs: u64,
+    job: &SinkJob,
+) -> Result<()> {
+    let mut child = Command::new(command)
+        .args(args)
+        .envs(job.env())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .with_context(|| format!("Failed to execute {}", command))?;
+
+    // Write the text while collecting output, both under the timeout, so a
+    // command that never reads stdin can't hang the sink
+    let stdin = child.stdin.take();
+    let write = async move {
+        let Some(mut stdin) = stdin else {
+            return Ok(());
+        };
+        match stdin.write_all(job.text.as_bytes()).await {
+            // The command exited without reading; its status says why
+            Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => Ok(()),
+            result => result,
+        }
+    };
+    let (written, output) = tokio::time::timeout(Duration::from_secs(timeout_secs), async {
+        tokio::join!(write, child.wait_with_output())
+    })
+    .await
+    .map_err(|_| anyhow!("{} timed out after {}s", command, timeout_secs))?;
+    let output = output.with_context(|| format!("Failed to wait for {}", command))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!(
+            "{} exited with {}: {}",
+            command,
+            output.status,
+            stderr.trim()
+        );
+    }
+    written.with_context(|| format!("Failed to write to {}", command))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job() -> SinkJob {
+        SinkJob {
+            job_id: "job-1".to_string(),
+            history_id: 42,
+            text: "Ship it.".to_string(),
+            raw_text: "ship it".to_string(),
+            created_at: "2026-03-03T09:30:00Z".to_string(),
+            mode: DictationMode::Prose,
+            language: Some("en".to_string()),
+            app: None,
+        }
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("wisprarch-sink-{}-{}", std::process::id(), name))
+    }
+
+    fn sinks(toml: &str) -> Sinks {
+        #[derive(serde::Deserialize)]
+        struct File {
+            sinks: Vec<SinkConfig>,
+        }
+        let file: File = toml::from_str(toml).unwrap();
+        Sinks::new(&file.sinks).unwrap()
+    }
+
+    #[test]
+    fn test_parse_sink_types() {
+        let sinks = sinks(
+            r#"
+            [[sinks]]
+            name = "notes"
+            type = "file"
+            path = "~/notes/inbox.md"
+            template = "- {text}\n"
+
+            [[sinks]]
+            name = "tracker"
+            type = "webhook"
+            url = "https://example.com/hook"
+            enabled = false
+            headers = { Authorization = "Bearer abc" }
+            "#,
+        );
+        assert!(matches!(
+            &sinks.sinks[0].kind,
+            SinkKind::File { template, .. } if template == "- {text}\n"
+        ));
+        assert!(matches!(
+            &sinks.sinks[1].kind,
+            SinkKind::Webhook { timeout_secs: 10, headers, .. } if headers.len() == 1
+        ));
+        assert!(sinks.sinks[0].enabled);
+        assert!(!sinks.sinks[1].enabled);
+    }
+
+    #[test]
+    fn test_selection() {
+        let sinks = sinks(
+            r#"
+            [[sinks]]
+            name = "notes"
+            type = "file"
+            path = "/tmp/notes.md"
+
+            [[sinks]]
+            name = "tracker"
+            type = "webhook"
+            url = "https://example.com/hook"
+            enabled = false
+            "#,
+        );
+        let names = |selected: Vec<SinkConfig>| {
+            selected
+                .into_iter()
+                .map(|sink| sink.name)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(sinks.select(None)), vec!["notes"]);
+        assert_eq!(
+            names(sinks.select(Some(&["tracker".to_string()]))),
+            vec!["tracker"]
+        );
+        assert!(sinks.select(Some(&[])).is_empty());
+        assert!(sinks.check_names(&["notes".to_string()]).is_ok());
+        assert!(sinks.check_names(&["jira".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_rejects_duplicate_names() {
+        let config = SinkConfig {
+            name: "notes".to_string(),
+            enabled: true,
+            kind: SinkKind::File {
+                path: "/tmp/notes.md".to_string(),
+                template: "{text}".to_string(),
+            },
+        };
+        assert!(Sinks::new(&[config.clone(), config]).is_err());
+    }
+
+    #[test]
+    fn test_template_variables() {
+        assert_eq!(
+            job().render("#{history_id} [{language}] {text} ({raw_text}) {unknown}"),
+            "#42 [en] Ship it. (ship it) {unknown}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_file_sink_appends() {
+        let path = temp_path("notes.md");
+        let _ = std::fs::remove_file(&path);
+        let kind = SinkKind::File {
+            path: path.to_string_lossy().into_owned(),
+            template: "- {text}\n".to_string(),
+        };
+        let client = reqwest::Client::new();
+        run_sink(&client, &kind, &job()).await.unwrap();
+        run_sink(&client, &kind, &job()).await.unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "- Ship it.\n- Ship it.\n"
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_fifo_sink_waits_for_a_slow_reader() {
+        let path = temp_path("slow.fifo");
+        let _ = std::fs::remove_file(&path);
+        let c_path = std::ffi::CString::new(path.to_string_lossy().into_owned()).unwrap();
+        assert_eq!(unsafe { libc::mkfifo(c_path.as_ptr(), 0o600) }, 0);
+
+        // Opening blocks until the sink opens the other end
+        let reader_path = path.clone();
+        let reader = std::thread::spawn(move || {
+            let mut fifo = std::fs::File::open(reader_path).unwrap();
+            std::thread::sleep(Duration::from_millis(200));
+            let mut received = String::new();
+            std::io::Read::read_to_string(&mut fifo, &mut received).unwrap();
+            received
+        });
+        std::thread::sleep(Duration::from_millis(100));
+
+        // Several times the pipe buffer, so the write has to wait for reads
+        let mut job = job();
+        job.text = "x".repeat(512 * 1024);
+        let kind = SinkKind::Fifo {
+            path: path.to_string_lossy().into_owned(),
+            template: "{text}".to_string(),
+        };
+        run_sink(&reqwest::Client::new(), &kind, &job)
+            .await
+            .unwrap();
+
+        assert_eq!(reader.join().unwrap(), job.text);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_fifo_sink_requires_a_fifo() {
+        let path = temp_path("not-a-fifo");
+        std::fs::write(&path, "").unwrap();
+        let kind = SinkKind::Fifo {
+            path: path.to_string_lossy().into_owned(),
+            template: "{text}".to_string(),
+        };
+        let err = run_sink(&reqwest::Client::new(), &kind, &job())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("not a FIFO"));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_command_sink_gets_text_and_env() {
+        let path = temp_path("command.txt");
+        let kind = SinkKind::Command {
+            command: "sh".to_string(),
+            args: vec![
+                "-c".to_string(),
+                format!(
+                    "cat > {0}; echo \" $WISPRARCH_HISTORY_ID $WISPRARCH_MODE\" >> {0}",
+                    path.display()
+                ),
+            ],
+            timeout_secs: 5,
+        };
+        run_sink(&reqwest::Client::new(), &kind, &job())
+            .await
+            .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "Ship it. 42 prose\n"
+        );
+        std::fs::remove_file(&path).unwrap();
+
+        let failing = SinkKind::Command {
+            command: "false".to_string(),
+            args: Vec::new(),
+            timeout_secs: 5,
+        };
+        assert!(run_sink(&reqwest::Client::new(), &failing, &job())
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_command_sink_times_out_without_reading_stdin() {
+        let kind = SinkKind::Command {
+            command: "sleep".to_string(),
+            args: vec!["5".to_string()],
+            timeout_secs: 1,
+        };
+        // More than the pipe holds, so writing has to wait for the command
+        let mut job = job();
+        job.text = "x".repeat(1024 * 1024);
+        let err = run_sink(&reqwest::Client::new(), &kind, &job)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("timed out"), "{:#}", err);
+
+        let exits = SinkKind::Command {
+            command: "sh".to_string(),
+            args: vec!["-c".to_string(), "echo 'no vault' >&2; exit 2".to_string()],
+            timeout_secs: 5,
+        };
+        let err = run_sink(&reqwest::Client::new(), &exits, &job)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("no vault"), "{:#}", err);
+    }
+}
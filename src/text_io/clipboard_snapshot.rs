@@ -0,0 +1,261 @@
+//! Clipboard snapshots for `restore_clipboard`.
+//!
+//! On Wayland the snapshot keeps every MIME type the clipboard offers, so a
+//! copy from a browser or office suite (HTML, image and plain text) comes
+//! back whole after a paste. Elsewhere only text is kept.
+
+use anyhow::{anyhow, Context, Result};
+use std::io::Write;
+use std::process::{Command, Stdio};
+use tracing::{debug, warn};
+use which::which;
+use wl_clipboard_rs::copy::{MimeSource, MimeType, Options, Source};
+
+use super::{copy_to_clipboard_sync, read_clipboard_sync};
+
+/// Targets Wayland clients advertise that aren't clipboard contents.
+const IGNORED_TYPES: &[&str] = &["SAVE_TARGETS", "TARGETS", "TIMESTAMP", "MULTIPLE"];
+
+/// Text types, tried in this order when the clipboard holds only text.
+const TEXT_TYPES: &[&str] = &[
+    "text/plain;charset=utf-8",
+    "text/plain",
+    "UTF8_STRING",
+    "STRING",
+];
+
+/// Clipboard contents saved before pasting a transcript.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClipboardSnapshot {
+    Empty,
+    /// Every content type the clipboard offered, restored together
+    Typed(Vec<TypedContents>),
+    Text(String),
+}
+
+/// Clipboard contents of one MIME type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypedContents {
+    pub mime_type: String,
+    pub data: Vec<u8>,
+}
+
+impl ClipboardSnapshot {
+    /// Save the current clipboard contents.
+    pub fn capture() -> Result<Self> {
+        if which("wl-paste").is_ok() && std::env::var("WAYLAND_DISPLAY").is_ok() {
+            return Self::capture_wayland();
+        }
+
+        match read_clipboard_sync() {
+            Ok(text) if text.is_empty() => Ok(Self::Empty),
+            Ok(text) => Ok(Self::Text(text)),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn capture_wayland() -> Result<Self> {
+        let output = Command::new("wl-paste")
+            .arg("--list-types")
+            .output()
+            .context("Failed to execute wl-paste")?;
+        // wl-paste fails with "Nothing is copied" for an empty clipboard
+        if !output.status.success() {
+            return Ok(Self::Empty);
+        }
+
+        let listed = String::from_utf8_lossy(&output.stdout);
+        let types: Vec<&str> = listed.lines().map(str::trim).collect();
+
+        let mut contents = Vec::new();
+        for mime_type in content_types(&types) {
+            let output = Command::new("wl-paste")
+                .args(["--no-newline", "--type", mime_type])
+                .output()
+                .context("Failed to execute wl-paste")?;
+            if !output.status.success() {
+                debug!(
+                    "Skipping clipboard type {}: {}",
+                    mime_type,
+                    String::from_utf8_lossy(&output.stderr).trim()
+                );
+                continue;
+            }
+            debug!(
+                "Saved {} bytes of {} from the clipboard",
+                output.stdout.len(),
+                mime_type
+            );
+            contents.push(TypedContents {
+                mime_type: mime_type.to_string(),
+                data: output.stdout,
+            });
+        }
+
+        if contents.is_empty() {
+            return Ok(Self::Empty);
+        }
+        Ok(Self::Typed(contents))
+    }
+
+    /// Put the saved contents back unless the clipboard no longer holds the
+    /// pasted text, e.g. because the user copied something since. Returns
+    /// whether the contents were restored.
+    pub fn restore_unless_changed(&self, pasted: &str) -> Result<bool> {
+        let unchanged = read_clipboard_sync()
+            .is_ok_and(|current| current.trim_end_matches('\n') == pasted.trim_end_matches('\n'));
+        if !unchanged {
+            return Ok(false);
+        }
+        self.restore()?;
+        Ok(true)
+    }
+
+    /// Put the saved contents back on the clipboard.
+    pub fn restore(&self) -> Result<()> {
+        match self {
+            Self::Empty => {
+                if which("wl-copy").is_ok() {
+                    Command::new("wl-copy")
+                        .arg("--clear")
+                        .status()
+                        .context("Failed to execute wl-copy")?;
+                }
+                Ok(())
+            }
+            Self::Text(text) => copy_to_clipboard_sync(text),
+            Self::Typed(contents) => match restore_all_types(contents) {
+                Ok(()) => Ok(()),
+                Err(e) => {
+                    warn!(
+                        "Failed to offer all clipboard types ({}), restoring only one",
+                        e
+                    );
+                    restore_one_type(contents)
+                }
+            },
+        }
+    }
+}
+
+/// Offer every saved type at once through the data-control protocol.
+fn restore_all_types(contents: &[TypedContents]) -> Result<()> {
+    let sources = contents
+        .iter()
+        .map(|typed| MimeSource {
+            source: Source::Bytes(typed.data.clone().into_boxed_slice()),
+            mime_type: MimeType::Specific(typed.mime_type.clone()),
+        })
+        .collect();
+    Options::new()
+        .copy_multi(sources)
+        .map_err(|e| anyhow!("{}", e))
+}
+
+/// Restore the most useful saved type with wl-copy, which offers one type.
+fn restore_one_type(contents: &[TypedContents]) -> Result<()> {
+    let types: Vec<&str> = contents.iter().map(|c| c.mime_type.as_str()).collect();
+    let Some(typed) = preferred_type(&types)
+        .and_then(|mime_type| contents.iter().find(|c| c.mime_type == mime_type))
+    else {
+        return Ok(());
+    };
+
+    let mut child = Command::new("wl-copy")
+        .args(["--type", &typed.mime_type])
+        .stdin(Stdio::piped())
+        .spawn()
+        .context("Failed to execute wl-copy")?;
+    if let Some(stdin) = child.stdin.as_mut() {
+        stdin
+            .write_all(&typed.data)
+            .context("Failed to write to wl-copy")?;
+    }
+    let status = child.wait().context("Failed to wait for wl-copy")?;
+    if !status.success() {
+        return Err(anyhow!("wl-copy exited with {}", status));
+    }
+    Ok(())
+}
+
+/// The offered types that hold clipboard contents, in the order offered.
+fn content_types<'a>(types: &[&'a str]) -> Vec<&'a str> {
+    let mut contents: Vec<&str> = Vec::new();
+    for t in types {
+        if !t.is_empty() && !IGNORED_TYPES.contains(t) && !contents.contains(t) {
+            contents.push(t);
+        }
+    }
+    contents
+}
+
+/// The single type to keep when not all can be restored: the first
+/// non-text type if there is one, otherwise the best text type.
+fn preferred_type<'a>(types: &[&'a str]) -> Option<&'a str> {
+    let contents = content_types(types);
+
+    let is_text = |t: &&str| t.starts_with("text/") || TEXT_TYPES.contains(t);
+    contents
+        .iter()
+        .find(|t| !is_text(t))
+        .or_else(|| {
+            TEXT_TYPES
+                .iter()
+                .find_map(|text| contents.iter().find(|t| *t == text))
+        })
+        .or_else(|| contents.first())
+        .copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefers_non_text_types() {
+        let types = ["text/plain", "image/png", "TARGETS"];
+        assert_eq!(preferred_type(&types), Some("image/png"));
+    }
+
+    #[test]
+    fn test_prefers_utf8_text() {
+        let types = [
+            "STRING",
+            "text/plain",
+            "text/plain;charset=utf-8",
+            "TIMESTAMP",
+        ];
+        assert_eq!(preferred_type(&types), Some("text/plain;charset=utf-8"));
+
+        let html = ["text/html", "STRING"];
+        assert_eq!(preferred_type(&html), Some("STRING"));
+    }
+
+    #[test]
+    fn test_keeps_every_content_type() {
+        let types = [
+            "text/html",
+            "image/png",
+            "TARGETS",
+            "text/plain;charset=utf-8",
+            "text/plain",
+            "text/html",
+            "SAVE_TARGETS",
+        ];
+        assert_eq!(
+            content_types(&types),
+            vec![
+                "text/html",
+                "image/png",
+                "text/plain;charset=utf-8",
+                "text/plain"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_no_contents() {
+        assert_eq!(preferred_type(&[]), None);
+        assert_eq!(preferred_type(&["TARGETS", ""]), None);
+    }
+}
@@ -1,5 +1,5 @@
 use anyhow::{anyhow, Context, Result};
-use arboard::Clipboard;
+use arboard::{Clipboard, LinuxClipboardKind, SetExtLinux};
 use std::io::Write;
 use std::process::{Command, Stdio};
 use std::sync::Arc;
@@ -7,6 +7,58 @@ use tokio::sync::{Mutex, RwLock};
 use tracing::{debug, info, warn};
 use which::which;
 
+use crate::config::{ClipboardTarget, PasteShortcut, TypingConfig};
+use typing::{run_type_command, TypeStep, Typist};
+
+mod clipboard_snapshot;
+mod typing;
+mod voice_commands;
+
+pub use clipboard_snapshot::ClipboardSnapshot;
+pub use voice_commands::{KeyAction, Utterance, VoiceCommand};
+
+/// Time an app gets to read a pasted run of text before typing continues.
+const PASTE_SETTLE_DELAY: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// Where and how text is copied.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CopyOptions {
+    pub target: ClipboardTarget,
+    /// Mark the copy with the `x-kde-passwordManagerHint` MIME type so
+    /// clipboard managers such as cliphist skip it
+    pub sensitive: bool,
+}
+
+impl CopyOptions {
+    /// These options, writing at least to the regular clipboard.
+    pub fn with_clipboard(self) -> Self {
+        let target = match self.target {
+            ClipboardTarget::Primary | ClipboardTarget::Both => ClipboardTarget::Both,
+            ClipboardTarget::Clipboard | ClipboardTarget::None => ClipboardTarget::Clipboard,
+        };
+        Self { target, ..self }
+    }
+
+    /// These options, writing only to the regular clipboard.
+    pub fn clipboard_only(self) -> Self {
+        Self {
+            target: ClipboardTarget::Clipboard,
+            ..self
+        }
+    }
+
+    fn selections(&self) -> Vec<LinuxClipboardKind> {
+        let mut selections = Vec::new();
+        if self.target.includes_clipboard() {
+            selections.push(LinuxClipboardKind::Clipboard);
+        }
+        if self.target.includes_primary() {
+            selections.push(LinuxClipboardKind::Primary);
+        }
+        selections
+    }
+}
+
 #[derive(Clone)]
 pub struct TextIoService {
     inner: Arc<TextIoInner>,
@@ -15,10 +67,11 @@ pub struct TextIoService {
 struct TextIoInner {
     clipboard: Mutex<Option<Clipboard>>,
     injection_method: RwLock<InjectionMethod>,
+    typing: TypingConfig,
 }
 
 impl TextIoService {
-    pub fn new(preferred_method: Option<&str>) -> Result<Self> {
+    pub fn new(preferred_method: Option<&str>, typing: TypingConfig) -> Result<Self> {
         let clipboard = match Clipboard::new() {
             Ok(cb) => Some(cb),
             Err(err) => {
@@ -35,6 +88,7 @@ impl TextIoService {
             inner: Arc::new(TextIoInner {
                 clipboard: Mutex::new(clipboard),
                 injection_method: RwLock::new(injection_method),
+                typing,
             }),
         })
     }
@@ -56,43 +110,73 @@ impl TextIoService {
     }
 
     pub async fn copy_to_clipboard(&self, text: &str) -> Result<()> {
+        self.copy_with(text, CopyOptions::default()).await
+    }
+
+    /// Copy text to the selections in `options`.
+    pub async fn copy_with(&self, text: &str, options: CopyOptions) -> Result<()> {
         if text.is_empty() {
             return Ok(());
         }
 
-        info!("Copying {} chars to clipboard", text.len());
-        debug!("Text to copy: {}", text);
+        for selection in options.selections() {
+            info!("Copying {} chars to {:?}", text.len(), selection);
+            debug!("Text to copy: {}", text);
 
-        let mut used_native = false;
+            let mut used_native = false;
 
-        {
-            let mut clipboard_guard = self.inner.clipboard.lock().await;
-            if let Some(clipboard) = clipboard_guard.as_mut() {
-                match clipboard.set_text(text) {
-                    Ok(_) => {
-                        used_native = true;
+            {
+                let mut clipboard_guard = self.inner.clipboard.lock().await;
+                if let Some(clipboard) = clipboard_guard.as_mut() {
+                    let mut set = clipboard.set().clipboard(selection);
+                    if options.sensitive {
+                        set = set.exclude_from_history();
                     }
-                    Err(err) => {
-                        warn!(
-                            "Primary clipboard backend failed ({}), disabling until restart",
-                            err
-                        );
-                        *clipboard_guard = None;
+                    match set.text(text) {
+                        Ok(_) => {
+                            used_native = true;
+                        }
+                        Err(err) => {
+                            warn!(
+                                "Primary clipboard backend failed ({}), disabling until restart",
+                                err
+                            );
+                            *clipboard_guard = None;
+                        }
                     }
+                } else {
+                    debug!("Native clipboard backend unavailable; using system clipboard tools");
                 }
-            } else {
-                debug!("Native clipboard backend unavailable; using system clipboard tools");
             }
-        }
 
-        if !used_native {
-            self.copy_with_system_backends(text).await?;
+            if !used_native {
+                if options.sensitive {
+                    warn!("System clipboard tools can't mark the copy as sensitive; clipboard managers may keep it");
+                }
+                self.copy_with_system_backends(text, selection).await?;
+            }
         }
 
         Ok(())
     }
 
     pub async fn inject_text(&self, text: &str) -> Result<()> {
+        let method = self.injection_method().await;
+        self.inject_text_with(text, method, None, false).await
+    }
+
+    /// Inject text with a specific method instead of the current one.
+    ///
+    /// In clipboard mode `paste_shortcut` is pressed to paste instead of
+    /// typing the clipboard contents. Text pasted while typing is marked as
+    /// `sensitive` for clipboard managers.
+    pub async fn inject_text_with(
+        &self,
+        text: &str,
+        method: InjectionMethod,
+        paste_shortcut: Option<PasteShortcut>,
+        sensitive: bool,
+    ) -> Result<()> {
         if text.is_empty() {
             return Ok(());
         }
@@ -100,17 +184,22 @@ impl TextIoService {
         info!("Injecting text: {} chars", text.len());
         debug!("Text to inject: {}", text);
 
-        let method = *self.inner.injection_method.read().await;
-        match method {
-            InjectionMethod::Wtype => {
-                self.try_with_clipboard_fallback(text, Self::inject_with_wtype)
-                    .await
+        match Typist::new(method, &self.inner.typing) {
+            Some(typist) => {
+                let copy = CopyOptions {
+                    sensitive,
+                    ..CopyOptions::default()
+                };
+                self.type_text(text, typist, paste_shortcut, copy).await
             }
-            InjectionMethod::Ydotool => {
-                self.try_with_clipboard_fallback(text, Self::inject_with_ydotool)
-                    .await
-            }
-            InjectionMethod::Clipboard => self.simulate_paste().await,
+            None => match paste_shortcut {
+                Some(shortcut) => {
+                    // Let modifier keys from the trigger shortcut be released
+                    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+                    self.send_key_with(method, KeyAction::Paste(shortcut)).await
+                }
+                None => self.simulate_paste().await,
+            },
         }
     }
 
@@ -118,30 +207,108 @@ impl TextIoService {
         self.simulate_paste().await
     }
 
-    async fn try_with_clipboard_fallback<F>(&self, text: &str, inject_fn: F) -> Result<()>
-    where
-        F: Fn(&str) -> Result<()>,
-    {
-        if let Err(err) = inject_fn(text) {
-            warn!(
-                "Direct text injection failed with {} – falling back to clipboard paste",
-                err
-            );
-            self.copy_to_clipboard(text).await?;
-            self.simulate_paste().await
-        } else {
-            Ok(())
+    /// Send a key action to the focused window.
+    ///
+    /// Uses the current injection method. In clipboard mode wtype or ydotool
+    /// is used if installed.
+    pub async fn send_key(&self, action: KeyAction) -> Result<()> {
+        let method = self.injection_method().await;
+        self.send_key_with(method, action).await
+    }
+
+    /// Send a key action using a specific injection method.
+    pub async fn send_key_with(&self, method: InjectionMethod, action: KeyAction) -> Result<()> {
+        let method = match method {
+            InjectionMethod::Clipboard => [
+                InjectionMethod::Wtype,
+                InjectionMethod::Ydotool,
+                InjectionMethod::Xdotool,
+            ]
+            .into_iter()
+            .find(|method| method.is_available())
+            .ok_or_else(|| anyhow!("Sending keys requires wtype, ydotool or xdotool"))?,
+            method => method,
+        };
+
+        let Some((program, args)) = key_command(method, action) else {
+            return Ok(());
+        };
+
+        info!("Sending {:?} with {}", action, program);
+        let output = Command::new(program)
+            .args(&args)
+            .output()
+            .with_context(|| format!("Failed to execute {}", program))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow!("{} failed: {}", program, stderr));
+        }
+
+        Ok(())
+    }
+
+    /// Type text step by step. Characters the backend can't type are pasted,
+    /// and if a step fails the rest of the text is pasted instead.
+    async fn type_text(
+        &self,
+        text: &str,
+        typist: Typist,
+        paste_shortcut: Option<PasteShortcut>,
+        copy: CopyOptions,
+    ) -> Result<()> {
+        let method = typist.method();
+        let shortcut = paste_shortcut.unwrap_or(PasteShortcut::CtrlV);
+        let steps = typist.plan(text);
+
+        for (i, step) in steps.iter().enumerate() {
+            let result = match step {
+                TypeStep::Type { program, args, .. } => run_type_command(program, args),
+                TypeStep::Key(action) => self.send_key_with(method, *action).await,
+                TypeStep::Paste(text) => {
+                    debug!(
+                        "Pasting {} chars {} can't type",
+                        text.len(),
+                        method.as_str()
+                    );
+                    self.copy_with(text, copy).await?;
+                    let result = self.send_key_with(method, KeyAction::Paste(shortcut)).await;
+                    // Let the app read the clipboard before it changes again
+                    tokio::time::sleep(PASTE_SETTLE_DELAY).await;
+                    result
+                }
+                TypeStep::Pause(delay) => {
+                    tokio::time::sleep(*delay).await;
+                    Ok(())
+                }
+            };
+
+            if let Err(err) = result {
+                let rest: String = steps[i..].iter().map(TypeStep::text).collect();
+                warn!(
+                    "Direct text injection failed with {} – falling back to clipboard paste",
+                    err
+                );
+                self.copy_with(&rest, copy).await?;
+                return self.simulate_paste().await;
+            }
         }
+
+        Ok(())
     }
 
-    async fn copy_with_system_backends(&self, text: &str) -> Result<()> {
+    async fn copy_with_system_backends(
+        &self,
+        text: &str,
+        selection: LinuxClipboardKind,
+    ) -> Result<()> {
         for backend in CLIPBOARD_BACKENDS {
             if which(backend.copy_cmd).is_err() {
                 continue;
             }
 
             let mut cmd = Command::new(backend.copy_cmd);
-            cmd.args(backend.copy_args);
+            cmd.args(backend.args(selection));
 
             if backend.use_stdin {
                 cmd.stdin(Stdio::piped());
@@ -170,39 +337,6 @@ impl TextIoService {
         ))
     }
 
-    fn inject_with_wtype(text: &str) -> Result<()> {
-        let output = Command::new("wtype")
-            .arg(text)
-            .output()
-            .context("Failed to execute wtype")?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(anyhow!("wtype failed: {}", stderr));
-        }
-
-        Ok(())
-    }
-
-    fn inject_with_ydotool(text: &str) -> Result<()> {
-        let output = Command::new("ydotool")
-            .arg("type")
-            .arg(text)
-            .output()
-            .context("Failed to execute ydotool")?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            warn!("ydotool failed: {}", stderr);
-            return Err(anyhow!(
-                "ydotool failed: {}. Make sure ydotoold is running",
-                stderr
-            ));
-        }
-
-        Ok(())
-    }
-
     async fn simulate_paste(&self) -> Result<()> {
         info!("Simulating paste from clipboard");
 
@@ -279,14 +413,25 @@ impl TextIoService {
 pub enum InjectionMethod {
     Wtype,
     Ydotool,
+    /// Typing on X11 and XWayland
+    Xdotool,
     Clipboard,
 }
 
 impl InjectionMethod {
+    /// All methods, in cycling order.
+    pub const ALL: [InjectionMethod; 4] = [
+        InjectionMethod::Clipboard,
+        InjectionMethod::Wtype,
+        InjectionMethod::Ydotool,
+        InjectionMethod::Xdotool,
+    ];
+
     pub fn as_str(&self) -> &'static str {
         match self {
             InjectionMethod::Wtype => "wtype",
             InjectionMethod::Ydotool => "ydotool",
+            InjectionMethod::Xdotool => "xdotool",
             InjectionMethod::Clipboard => "clipboard",
         }
     }
@@ -295,17 +440,14 @@ impl InjectionMethod {
         match s {
             "wtype" => Some(InjectionMethod::Wtype),
             "ydotool" => Some(InjectionMethod::Ydotool),
+            "xdotool" => Some(InjectionMethod::Xdotool),
             "clipboard" | "paste" => Some(InjectionMethod::Clipboard),
             _ => None,
         }
     }
 
     pub fn next_available(&self) -> Self {
-        let order = [
-            InjectionMethod::Clipboard,
-            InjectionMethod::Wtype,
-            InjectionMethod::Ydotool,
-        ];
+        let order = Self::ALL;
 
         let current_idx = order.iter().position(|m| m == self).unwrap_or(0);
 
@@ -319,11 +461,14 @@ impl InjectionMethod {
         InjectionMethod::Clipboard
     }
 
-    fn is_available(&self) -> bool {
+    pub fn is_available(&self) -> bool {
         match self {
             InjectionMethod::Clipboard => true,
             InjectionMethod::Wtype => which("wtype").is_ok(),
             InjectionMethod::Ydotool => which("ydotool").is_ok(),
+            InjectionMethod::Xdotool => {
+                which("xdotool").is_ok() && std::env::var("DISPLAY").is_ok()
+            }
         }
     }
 
@@ -342,6 +487,10 @@ impl InjectionMethod {
                     info!("Using wtype for text injection (per config)");
                     return InjectionMethod::Wtype;
                 }
+                "xdotool" if InjectionMethod::Xdotool.is_available() => {
+                    info!("Using xdotool for text injection (per config)");
+                    return InjectionMethod::Xdotool;
+                }
                 other => {
                     warn!(
                         "Unknown or unavailable input_method '{}', falling back to auto-detect",
@@ -356,6 +505,11 @@ impl InjectionMethod {
             return InjectionMethod::Clipboard;
         }
 
+        if std::env::var("WAYLAND_DISPLAY").is_err() && InjectionMethod::Xdotool.is_available() {
+            info!("Using xdotool for text injection (X11 detected)");
+            return InjectionMethod::Xdotool;
+        }
+
         if which("ydotool").is_ok() {
             info!("Using ydotool for text injection (auto-detected)");
             return InjectionMethod::Ydotool;
@@ -371,30 +525,110 @@ impl InjectionMethod {
     }
 }
 
+/// Program and arguments that send a key action with the given method.
+///
+/// Returns `None` when there is nothing to send or the method can't send keys.
+fn key_command(method: InjectionMethod, action: KeyAction) -> Option<(&'static str, Vec<String>)> {
+    let args: Vec<&str> = match (method, action) {
+        (_, KeyAction::Backspace(0)) | (InjectionMethod::Clipboard, _) => return None,
+        (InjectionMethod::Wtype, KeyAction::Enter) => vec!["-k", "Return"],
+        (InjectionMethod::Wtype, KeyAction::ShiftEnter) => {
+            vec!["-M", "shift", "-k", "Return", "-m", "shift"]
+        }
+        (InjectionMethod::Wtype, KeyAction::SelectAll) => vec!["-M", "ctrl", "a", "-m", "ctrl"],
+        (InjectionMethod::Wtype, KeyAction::Undo) => vec!["-M", "ctrl", "z", "-m", "ctrl"],
+        (InjectionMethod::Wtype, KeyAction::Backspace(count)) => ["-k", "BackSpace"].repeat(count),
+        (InjectionMethod::Wtype, KeyAction::Paste(PasteShortcut::CtrlV)) => {
+            vec!["-M", "ctrl", "v", "-m", "ctrl"]
+        }
+        (InjectionMethod::Wtype, KeyAction::Paste(PasteShortcut::CtrlShiftV)) => {
+            vec![
+                "-M", "ctrl", "-M", "shift", "v", "-m", "shift", "-m", "ctrl",
+            ]
+        }
+        // ydotool uses Linux input event codes: Enter=28, Ctrl=29, A=30, Z=44, Backspace=14,
+        // Shift=42, V=47
+        (InjectionMethod::Ydotool, KeyAction::Enter) => vec!["key", "28:1", "28:0"],
+        (InjectionMethod::Ydotool, KeyAction::ShiftEnter) => {
+            vec!["key", "42:1", "28:1", "28:0", "42:0"]
+        }
+        (InjectionMethod::Ydotool, KeyAction::SelectAll) => {
+            vec!["key", "29:1", "30:1", "30:0", "29:0"]
+        }
+        (InjectionMethod::Ydotool, KeyAction::Undo) => vec!["key", "29:1", "44:1", "44:0", "29:0"],
+        (InjectionMethod::Ydotool, KeyAction::Paste(PasteShortcut::CtrlV)) => {
+            vec!["key", "29:1", "47:1", "47:0", "29:0"]
+        }
+        (InjectionMethod::Ydotool, KeyAction::Paste(PasteShortcut::CtrlShiftV)) => {
+            vec!["key", "29:1", "42:1", "47:1", "47:0", "42:0", "29:0"]
+        }
+        (InjectionMethod::Ydotool, KeyAction::Backspace(count)) => {
+            let mut args = vec!["key"];
+            args.extend(["14:1", "14:0"].repeat(count));
+            args
+        }
+        (InjectionMethod::Xdotool, KeyAction::Enter) => vec!["key", "Return"],
+        (InjectionMethod::Xdotool, KeyAction::ShiftEnter) => vec!["key", "shift+Return"],
+        (InjectionMethod::Xdotool, KeyAction::SelectAll) => vec!["key", "ctrl+a"],
+        (InjectionMethod::Xdotool, KeyAction::Undo) => vec!["key", "ctrl+z"],
+        (InjectionMethod::Xdotool, KeyAction::Paste(PasteShortcut::CtrlV)) => {
+            vec!["key", "ctrl+v"]
+        }
+        (InjectionMethod::Xdotool, KeyAction::Paste(PasteShortcut::CtrlShiftV)) => {
+            vec!["key", "ctrl+shift+v"]
+        }
+        (InjectionMethod::Xdotool, KeyAction::Backspace(count)) => {
+            let mut args = vec!["key"];
+            args.extend(std::iter::repeat_n("BackSpace", count));
+            args
+        }
+    };
+
+    let program = match method {
+        InjectionMethod::Wtype => "wtype",
+        InjectionMethod::Xdotool => "xdotool",
+        _ => "ydotool",
+    };
+    Some((program, args.into_iter().map(str::to_string).collect()))
+}
+
 struct ClipboardBackend {
     name: &'static str,
     copy_cmd: &'static str,
     copy_args: &'static [&'static str],
+    primary_args: &'static [&'static str],
     use_stdin: bool,
 }
 
+impl ClipboardBackend {
+    fn args(&self, selection: LinuxClipboardKind) -> &'static [&'static str] {
+        match selection {
+            LinuxClipboardKind::Primary => self.primary_args,
+            _ => self.copy_args,
+        }
+    }
+}
+
 const CLIPBOARD_BACKENDS: &[ClipboardBackend] = &[
     ClipboardBackend {
         name: "wl-copy",
         copy_cmd: "wl-copy",
         copy_args: &[],
+        primary_args: &["--primary"],
         use_stdin: true,
     },
     ClipboardBackend {
         name: "xclip",
         copy_cmd: "xclip",
         copy_args: &["-selection", "clipboard"],
+        primary_args: &["-selection", "primary"],
         use_stdin: true,
     },
     ClipboardBackend {
         name: "xsel",
         copy_cmd: "xsel",
         copy_args: &["--clipboard", "--input"],
+        primary_args: &["--primary", "--input"],
         use_stdin: true,
     },
 ];
@@ -441,3 +675,134 @@ pub fn copy_to_clipboard_sync(text: &str) -> Result<()> {
         "No clipboard tool available. Please install wl-copy (Wayland), xclip, or xsel (X11)."
     ))
 }
+
+/// Commands that print the clipboard contents, in order of preference.
+const CLIPBOARD_READERS: &[(&str, &[&str])] = &[
+    ("wl-paste", &["--no-newline"]),
+    ("xclip", &["-selection", "clipboard", "-o"]),
+    ("xsel", &["--clipboard", "--output"]),
+];
+
+/// Read the clipboard text with the system clipboard tools.
+pub fn read_clipboard_sync() -> Result<String> {
+    for (program, args) in CLIPBOARD_READERS {
+        if which(program).is_err() {
+            continue;
+        }
+
+        if let Ok(output) = Command::new(program).args(*args).output() {
+            if output.status.success() {
+                return Ok(String::from_utf8_lossy(&output.stdout).into_owned());
+            }
+        }
+    }
+
+    Err(anyhow!(
+        "No clipboard tool available. Please install wl-paste (Wayland), xclip, or xsel (X11)."
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_command_wtype() {
+        let (program, args) = key_command(InjectionMethod::Wtype, KeyAction::Enter).unwrap();
+        assert_eq!(program, "wtype");
+        assert_eq!(args, vec!["-k", "Return"]);
+
+        let (_, args) = key_command(InjectionMethod::Wtype, KeyAction::Backspace(2)).unwrap();
+        assert_eq!(args, vec!["-k", "BackSpace", "-k", "BackSpace"]);
+    }
+
+    #[test]
+    fn test_key_command_ydotool() {
+        let (program, args) = key_command(InjectionMethod::Ydotool, KeyAction::Undo).unwrap();
+        assert_eq!(program, "ydotool");
+        assert_eq!(args, vec!["key", "29:1", "44:1", "44:0", "29:0"]);
+    }
+
+    #[test]
+    fn test_key_command_xdotool() {
+        let (program, args) = key_command(InjectionMethod::Xdotool, KeyAction::ShiftEnter).unwrap();
+        assert_eq!(program, "xdotool");
+        assert_eq!(args, vec!["key", "shift+Return"]);
+
+        let (_, args) = key_command(InjectionMethod::Xdotool, KeyAction::Backspace(2)).unwrap();
+        assert_eq!(args, vec!["key", "BackSpace", "BackSpace"]);
+    }
+
+    #[test]
+    fn test_next_available_cycles_from_xdotool() {
+        assert_eq!(
+            InjectionMethod::parse("xdotool"),
+            Some(InjectionMethod::Xdotool)
+        );
+        // Clipboard is always available, so cycling past the last method wraps to it
+        assert_eq!(
+            InjectionMethod::Xdotool.next_available(),
+            InjectionMethod::Clipboard
+        );
+    }
+
+    #[test]
+    fn test_key_command_paste_shortcut() {
+        let (_, args) = key_command(
+            InjectionMethod::Wtype,
+            KeyAction::Paste(PasteShortcut::CtrlShiftV),
+        )
+        .unwrap();
+        assert_eq!(
+            args,
+            vec!["-M", "ctrl", "-M", "shift", "v", "-m", "shift", "-m", "ctrl"]
+        );
+
+        let (_, args) = key_command(
+            InjectionMethod::Ydotool,
+            KeyAction::Paste(PasteShortcut::CtrlV),
+        )
+        .unwrap();
+        assert_eq!(args, vec!["key", "29:1", "47:1", "47:0", "29:0"]);
+    }
+
+    #[test]
+    fn test_copy_options_selections() {
+        let options = |target| CopyOptions {
+            target,
+            sensitive: false,
+        };
+        assert!(matches!(
+            options(ClipboardTarget::Both).selections()[..],
+            [LinuxClipboardKind::Clipboard, LinuxClipboardKind::Primary]
+        ));
+        assert!(options(ClipboardTarget::None).selections().is_empty());
+        assert_eq!(
+            options(ClipboardTarget::None).with_clipboard().target,
+            ClipboardTarget::Clipboard
+        );
+        assert_eq!(
+            options(ClipboardTarget::Primary).with_clipboard().target,
+            ClipboardTarget::Both
+        );
+    }
+
+    #[test]
+    fn test_clipboard_backend_primary_args() {
+        let xclip = &CLIPBOARD_BACKENDS[1];
+        assert_eq!(
+            xclip.args(LinuxClipboardKind::Primary),
+            &["-selection", "primary"]
+        );
+        assert_eq!(
+            xclip.args(LinuxClipboardKind::Clipboard),
+            &["-selection", "clipboard"]
+        );
+    }
+
+    #[test]
+    fn test_key_command_nothing_to_send() {
+        assert!(key_command(InjectionMethod::Clipboard, KeyAction::Enter).is_none());
+        assert!(key_command(InjectionMethod::Wtype, KeyAction::Backspace(0)).is_none());
+    }
+}
@@ -0,0 +1,299 @@
+//! Typed injection with wtype, ydotool and xdotool.
+//!
+//! Text is split into steps: chunks typed by the backend with pauses between
+//! them, newlines sent as Enter or Shift+Enter, and runs of characters the
+//! backend can't type, which are pasted from the clipboard instead.
+
+use anyhow::{anyhow, Context, Result};
+use std::process::Command;
+use std::time::Duration;
+
+use super::{InjectionMethod, KeyAction};
+use crate::config::{NewlineKey, TypingConfig};
+
+/// One step of typing a transcript.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum TypeStep {
+    /// Type with the backend program and arguments
+    Type {
+        text: String,
+        program: String,
+        args: Vec<String>,
+    },
+    Key(KeyAction),
+    /// Paste through the clipboard
+    Paste(String),
+    Pause(Duration),
+}
+
+impl TypeStep {
+    /// The part of the transcript this step enters.
+    pub(crate) fn text(&self) -> &str {
+        match self {
+            TypeStep::Type { text, .. } | TypeStep::Paste(text) => text,
+            TypeStep::Key(_) => "\n",
+            TypeStep::Pause(_) => "",
+        }
+    }
+}
+
+/// Plans how a typing backend enters text.
+pub(crate) struct Typist {
+    method: InjectionMethod,
+    program: String,
+    config: TypingConfig,
+}
+
+impl Typist {
+    /// A typist for `wtype`, `ydotool` or `xdotool`. Other methods don't type.
+    pub(crate) fn new(method: InjectionMethod, config: &TypingConfig) -> Option<Self> {
+        let program = match method {
+            InjectionMethod::Wtype => "wtype",
+            InjectionMethod::Ydotool => "ydotool",
+            InjectionMethod::Xdotool => "xdotool",
+            InjectionMethod::Clipboard => return None,
+        };
+        Some(Self {
+            method,
+            program: program.to_string(),
+            config: config.clone(),
+        })
+    }
+
+    /// Run a different program in place of the backend.
+    #[cfg(test)]
+    fn with_program(mut self, program: &str) -> Self {
+        self.program = program.to_string();
+        self
+    }
+
+    pub(crate) fn method(&self) -> InjectionMethod {
+        self.method
+    }
+
+    /// Whether the backend can type this character.
+    fn can_type(&self, c: char) -> bool {
+        match self.method {
+            // ydotool types through a US keymap
+            InjectionMethod::Ydotool => c.is_ascii_graphic() || c == ' ' || c == '\t',
+            _ => !c.is_control() || c == '\t',
+        }
+    }
+
+    /// Split text into steps.
+    pub(crate) fn plan(&self, text: &str) -> Vec<TypeStep> {
+        let mut steps = Vec::new();
+        let mut typed = String::new();
+        let mut pasted = String::new();
+
+        for c in text.chars() {
+            if c == '\r' {
+                continue;
+            }
+            if c == '\n' {
+                self.flush_typed(&mut steps, &mut typed);
+                flush_pasted(&mut steps, &mut pasted);
+                steps.push(TypeStep::Key(match self.config.newline {
+                    NewlineKey::Enter => KeyAction::Enter,
+                    NewlineKey::ShiftEnter => KeyAction::ShiftEnter,
+                }));
+            } else if self.can_type(c) {
+                flush_pasted(&mut steps, &mut pasted);
+                typed.push(c);
+            } else {
+                self.flush_typed(&mut steps, &mut typed);
+                pasted.push(c);
+            }
+        }
+        self.flush_typed(&mut steps, &mut typed);
+        flush_pasted(&mut steps, &mut pasted);
+
+        steps
+    }
+
+    fn flush_typed(&self, steps: &mut Vec<TypeStep>, typed: &mut String) {
+        if typed.is_empty() {
+            return;
+        }
+
+        let chars: Vec<char> = typed.chars().collect();
+        let chunk_size = match self.config.chunk_size {
+            0 => chars.len(),
+            size => size,
+        };
+        for (i, chunk) in chars.chunks(chunk_size).enumerate() {
+            if i > 0 && self.config.chunk_delay_ms > 0 {
+                steps.push(TypeStep::Pause(Duration::from_millis(
+                    self.config.chunk_delay_ms,
+                )));
+            }
+            let text: String = chunk.iter().collect();
+            steps.push(TypeStep::Type {
+                args: self.type_args(&text),
+                program: self.program.clone(),
+                text,
+            });
+        }
+        typed.clear();
+    }
+
+    fn type_args(&self, text: &str) -> Vec<String> {
+        let delay = self.config.key_delay_ms.to_string();
+        let mut args: Vec<&str> = match self.method {
+            InjectionMethod::Ydotool | InjectionMethod::Xdotool => vec!["type"],
+            _ => Vec::new(),
+        };
+        if self.config.key_delay_ms > 0 {
+            match self.method {
+                InjectionMethod::Ydotool => args.extend(["--key-delay", &delay]),
+                InjectionMethod::Xdotool => args.extend(["--delay", &delay]),
+                _ => args.extend(["-d", &delay]),
+            }
+        }
+        args.extend(["--", text]);
+        args.into_iter().map(str::to_string).collect()
+    }
+}
+
+fn flush_pasted(steps: &mut Vec<TypeStep>, pasted: &mut String) {
+    if !pasted.is_empty() {
+        steps.push(TypeStep::Paste(std::mem::take(pasted)));
+    }
+}
+
+/// Run a typing command, failing on a non-zero exit.
+pub(crate) fn run_type_command(program: &str, args: &[String]) -> Result<()> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .with_context(|| format!("Failed to execute {}", program))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!("{} failed: {}", program, stderr.trim()));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn typist(method: InjectionMethod, config: TypingConfig) -> Typist {
+        Typist::new(method, &config).unwrap()
+    }
+
+    fn typed(steps: &[TypeStep]) -> Vec<&str> {
+        steps
+            .iter()
+            .filter_map(|step| match step {
+                TypeStep::Type { text, .. } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_whole_text_in_one_call_by_default() {
+        let steps = typist(InjectionMethod::Wtype, TypingConfig::default()).plan("hello world");
+        assert_eq!(
+            steps,
+            vec![TypeStep::Type {
+                text: "hello world".to_string(),
+                program: "wtype".to_string(),
+                args: vec!["--".to_string(), "hello world".to_string()],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_chunks_with_delays() {
+        let config = TypingConfig {
+            chunk_size: 4,
+            key_delay_ms: 5,
+            chunk_delay_ms: 30,
+            ..TypingConfig::default()
+        };
+        let steps = typist(InjectionMethod::Ydotool, config).plan("abcdefghij");
+        assert_eq!(typed(&steps), vec!["abcd", "efgh", "ij"]);
+        assert_eq!(steps[1], TypeStep::Pause(Duration::from_millis(30)));
+        match &steps[0] {
+            TypeStep::Type { program, args, .. } => {
+                assert_eq!(program, "ydotool");
+                assert_eq!(args, &["type", "--key-delay", "5", "--", "abcd"]);
+            }
+            step => panic!("unexpected step {:?}", step),
+        }
+    }
+
+    #[test]
+    fn test_newlines_use_configured_key() {
+        let config = TypingConfig {
+            newline: NewlineKey::ShiftEnter,
+            ..TypingConfig::default()
+        };
+        let steps = typist(InjectionMethod::Wtype, config).plan("one\r\ntwo");
+        assert_eq!(steps[1], TypeStep::Key(KeyAction::ShiftEnter));
+        assert_eq!(typed(&steps), vec!["one", "two"]);
+    }
+
+    #[test]
+    fn test_ydotool_pastes_non_ascii() {
+        let steps = typist(InjectionMethod::Ydotool, TypingConfig::default()).plan("café ok");
+        assert_eq!(typed(&steps), vec!["caf", " ok"]);
+        assert_eq!(steps[1], TypeStep::Paste("é".to_string()));
+
+        let steps = typist(InjectionMethod::Wtype, TypingConfig::default()).plan("café ok");
+        assert_eq!(typed(&steps), vec!["café ok"]);
+    }
+
+    #[test]
+    fn test_step_text_covers_transcript() {
+        let config = TypingConfig {
+            chunk_size: 2,
+            chunk_delay_ms: 10,
+            ..TypingConfig::default()
+        };
+        let steps = typist(InjectionMethod::Ydotool, config).plan("añb\ncd");
+        let text: String = steps.iter().map(TypeStep::text).collect();
+        assert_eq!(text, "añb\ncd");
+    }
+
+    #[test]
+    fn test_xdotool_type_args() {
+        let config = TypingConfig {
+            key_delay_ms: 12,
+            ..TypingConfig::default()
+        };
+        let steps = typist(InjectionMethod::Xdotool, config).plan("café");
+        match &steps[0] {
+            TypeStep::Type { program, args, .. } => {
+                assert_eq!(program, "xdotool");
+                assert_eq!(args, &["type", "--delay", "12", "--", "café"]);
+            }
+            step => panic!("unexpected step {:?}", step),
+        }
+    }
+
+    #[test]
+    fn test_clipboard_method_does_not_type() {
+        assert!(Typist::new(InjectionMethod::Clipboard, &TypingConfig::default()).is_none());
+    }
+
+    #[test]
+    fn test_substituted_backend_command() {
+        let ok = typist(InjectionMethod::Wtype, TypingConfig::default()).with_program("true");
+        for step in ok.plan("hi") {
+            if let TypeStep::Type { program, args, .. } = step {
+                assert!(run_type_command(&program, &args).is_ok());
+            }
+        }
+
+        let failing = typist(InjectionMethod::Wtype, TypingConfig::default()).with_program("false");
+        let TypeStep::Type { program, args, .. } = &failing.plan("hi")[0] else {
+            panic!("expected a typing step");
+        };
+        assert!(run_type_command(program, args).is_err());
+    }
+}
@@ -0,0 +1,234 @@
+//! Voice editing commands at the start or end of an utterance.
+//!
+//! "Scratch that" deletes the previously injected transcription, or drops
+//! the rest of the utterance when said at its end. "Press enter", "select
+//! all" and "undo" send the matching keys to the focused window.
+
+use crate::config::PasteShortcut;
+
+/// A key action sent through the injection backend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyAction {
+    Enter,
+    /// Line break in chat apps where Enter sends the message
+    ShiftEnter,
+    SelectAll,
+    Undo,
+    /// Press backspace this many times
+    Backspace(usize),
+    /// Paste the clipboard with a key combination
+    Paste(PasteShortcut),
+}
+
+/// A recognized voice command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VoiceCommand {
+    /// Delete the previously injected transcription
+    ScratchThat,
+    Key(KeyAction),
+}
+
+const COMMANDS: &[(&str, VoiceCommand)] = &[
+    ("scratch that", VoiceCommand::ScratchThat),
+    ("delete that", VoiceCommand::ScratchThat),
+    ("press enter", VoiceCommand::Key(KeyAction::Enter)),
+    ("press return", VoiceCommand::Key(KeyAction::Enter)),
+    ("select all", VoiceCommand::Key(KeyAction::SelectAll)),
+    ("undo that", VoiceCommand::Key(KeyAction::Undo)),
+    ("undo", VoiceCommand::Key(KeyAction::Undo)),
+];
+
+/// An utterance split into leading commands, text and trailing commands.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Utterance {
+    pub leading: Vec<VoiceCommand>,
+    pub text: String,
+    pub trailing: Vec<VoiceCommand>,
+    /// A trailing "scratch that" dropped the text, and any commands between
+    /// it and the text
+    pub scratched: bool,
+}
+
+impl Utterance {
+    /// An utterance without commands.
+    pub fn plain(text: &str) -> Self {
+        Self {
+            text: text.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Split commands off the start and end of a transcript.
+    ///
+    /// Commands are only recognized as whole phrases at the edges, so
+    /// "undo" in the middle of a sentence stays text.
+    pub fn parse(text: &str) -> Self {
+        let tokens = token_spans(text);
+        let keys: Vec<String> = tokens
+            .iter()
+            .map(|&(start, end)| {
+                text[start..end]
+                    .trim_matches(|c: char| !c.is_alphanumeric())
+                    .to_lowercase()
+            })
+            .collect();
+
+        let mut first = 0;
+        let mut last = tokens.len();
+
+        let mut leading = Vec::new();
+        while let Some((len, command)) = match_prefix(&keys[first..last]) {
+            leading.push(command);
+            first += len;
+        }
+
+        let mut trailing = Vec::new();
+        while let Some((len, command)) = match_suffix(&keys[first..last]) {
+            trailing.insert(0, command);
+            last -= len;
+        }
+
+        // "... scratch that" takes back what was just said, not the
+        // previous transcription
+        let scratched = match trailing
+            .iter()
+            .rposition(|command| *command == VoiceCommand::ScratchThat)
+        {
+            Some(position) => {
+                trailing.drain(..=position);
+                true
+            }
+            None => false,
+        };
+
+        let text = if first < last && !scratched {
+            text[tokens[first].0..tokens[last - 1].1].to_string()
+        } else {
+            String::new()
+        };
+
+        Self {
+            leading,
+            text,
+            trailing,
+            scratched,
+        }
+    }
+
+    pub fn has_commands(&self) -> bool {
+        !self.leading.is_empty() || !self.trailing.is_empty() || self.scratched
+    }
+
+    /// True if there is neither text nor a command.
+    pub fn is_empty(&self) -> bool {
+        self.text.trim().is_empty() && !self.has_commands()
+    }
+}
+
+/// Byte ranges of whitespace-separated tokens.
+fn token_spans(text: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut start = None;
+    for (i, c) in text.char_indices() {
+        match (c.is_whitespace(), start) {
+            (true, Some(s)) => {
+                spans.push((s, i));
+                start = None;
+            }
+            (false, None) => start = Some(i),
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        spans.push((s, text.len()));
+    }
+    spans
+}
+
+/// Whether `keys` starts with the words of `phrase`.
+fn starts_with_phrase(keys: &[String], phrase: &str) -> bool {
+    let words: Vec<&str> = phrase.split(' ').collect();
+    keys.len() >= words.len() && keys.iter().zip(&words).all(|(key, word)| key == word)
+}
+
+fn phrase_len(phrase: &str) -> usize {
+    phrase.split(' ').count()
+}
+
+fn match_prefix(keys: &[String]) -> Option<(usize, VoiceCommand)> {
+    COMMANDS
+        .iter()
+        .find(|(phrase, _)| starts_with_phrase(keys, phrase))
+        .map(|(phrase, command)| (phrase_len(phrase), *command))
+}
+
+fn match_suffix(keys: &[String]) -> Option<(usize, VoiceCommand)> {
+    COMMANDS
+        .iter()
+        .map(|(phrase, command)| (phrase_len(phrase), phrase, command))
+        .find(|(len, phrase, _)| {
+            *len <= keys.len() && starts_with_phrase(&keys[keys.len() - len..], phrase)
+        })
+        .map(|(len, _, command)| (len, *command))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_text_has_no_commands() {
+        let utterance = Utterance::parse("I want to undo the last change.");
+        assert!(!utterance.has_commands());
+        assert_eq!(utterance.text, "I want to undo the last change.");
+    }
+
+    #[test]
+    fn test_trailing_command() {
+        let utterance = Utterance::parse("See you tomorrow. Press enter.");
+        assert_eq!(utterance.text, "See you tomorrow.");
+        assert_eq!(
+            utterance.trailing,
+            vec![VoiceCommand::Key(KeyAction::Enter)]
+        );
+    }
+
+    #[test]
+    fn test_leading_commands() {
+        let utterance = Utterance::parse("Scratch that. Select all, hello\nworld");
+        assert_eq!(
+            utterance.leading,
+            vec![
+                VoiceCommand::ScratchThat,
+                VoiceCommand::Key(KeyAction::SelectAll)
+            ]
+        );
+        assert_eq!(utterance.text, "hello\nworld");
+    }
+
+    #[test]
+    fn test_trailing_scratch_drops_the_text() {
+        let utterance = Utterance::parse("Hello world, press enter. Scratch that. Select all");
+        assert!(utterance.scratched);
+        assert!(utterance.text.is_empty());
+        assert_eq!(
+            utterance.trailing,
+            vec![VoiceCommand::Key(KeyAction::SelectAll)]
+        );
+        assert!(!utterance.is_empty());
+    }
+
+    #[test]
+    fn test_command_only() {
+        let utterance = Utterance::parse("Undo.");
+        assert_eq!(utterance.leading, vec![VoiceCommand::Key(KeyAction::Undo)]);
+        assert!(utterance.text.is_empty());
+        assert!(!utterance.is_empty());
+    }
+
+    #[test]
+    fn test_empty() {
+        assert!(Utterance::parse("   ").is_empty());
+        assert!(Utterance::plain("").is_empty());
+    }
+}
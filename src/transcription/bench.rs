@@ -0,0 +1,305 @@
+//! Provider benchmarking over a folder of reference recordings.
+//!
+//! Each `<name>.wav` in the folder is paired with a `<name>.txt` reference
+//! transcript. Every target provider transcribes every file, and the results
+//! are summarized as word error rate, real-time factor and latency percentiles.
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+use super::test_provider_with_config;
+use crate::audio::wav_duration_secs;
+use crate::config::WhisperConfig;
+
+/// A WAV file with its reference transcript.
+#[derive(Debug, Clone)]
+pub struct BenchSample {
+    pub audio_path: PathBuf,
+    pub reference: String,
+    pub duration_secs: f64,
+}
+
+/// A named provider configuration to benchmark.
+#[derive(Debug, Clone)]
+pub struct BenchTarget {
+    pub name: String,
+    pub whisper: WhisperConfig,
+}
+
+/// Result of transcribing a single sample.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchSampleResult {
+    pub file: String,
+    pub success: bool,
+    pub hypothesis: Option<String>,
+    pub error: Option<String>,
+    pub wer: Option<f64>,
+    pub latency_secs: f64,
+    pub audio_secs: f64,
+}
+
+/// Aggregated results for one target.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchReport {
+    pub target: String,
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    pub samples: usize,
+    pub failures: usize,
+    /// Corpus-level word error rate (total edits / total reference words)
+    pub wer: Option<f64>,
+    /// Total processing time divided by total audio duration
+    pub rtf: Option<f64>,
+    pub latency_p50_secs: Option<f64>,
+    pub latency_p90_secs: Option<f64>,
+    pub latency_p99_secs: Option<f64>,
+    pub results: Vec<BenchSampleResult>,
+}
+
+/// Collect `.wav` files with a matching `.txt` reference from a directory.
+///
+/// WAV files without a reference transcript are skipped with a warning.
+pub fn load_samples(dir: &Path) -> Result<Vec<BenchSample>> {
+    if !dir.is_dir() {
+        return Err(anyhow!("Benchmark directory not found: {}", dir.display()));
+    }
+
+    let mut audio_files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read {}", dir.display()))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .and_then(|ext| ext.to_str())
+                .map(|ext| ext.eq_ignore_ascii_case("wav"))
+                .unwrap_or(false)
+        })
+        .collect();
+    audio_files.sort();
+
+    let mut samples = Vec::new();
+    for audio_path in audio_files {
+        let reference_path = audio_path.with_extension("txt");
+        if !reference_path.exists() {
+            warn!("Skipping {:?}: no reference transcript", audio_path);
+            continue;
+        }
+
+        let reference = std::fs::read_to_string(&reference_path)
+            .with_context(|| format!("Failed to read {}", reference_path.display()))?;
+        let duration_secs = wav_duration_secs(&audio_path)?;
+
+        samples.push(BenchSample {
+            audio_path,
+            reference: reference.trim().to_string(),
+            duration_secs,
+        });
+    }
+
+    Ok(samples)
+}
+
+/// Benchmark one target over all samples.
+pub async fn run_target(target: &BenchTarget, samples: &[BenchSample]) -> Result<BenchReport> {
+    info!(
+        "Benchmarking '{}' over {} samples",
+        target.name,
+        samples.len()
+    );
+
+    let mut results = Vec::with_capacity(samples.len());
+    for sample in samples {
+        let outcome =
+            test_provider_with_config(&target.whisper, Some(sample.audio_path.as_path())).await?;
+
+        let wer = outcome
+            .transcription
+            .as_deref()
+            .map(|hypothesis| word_error_rate(&sample.reference, hypothesis));
+
+        results.push(BenchSampleResult {
+            file: sample
+                .audio_path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            success: outcome.success,
+            hypothesis: outcome.transcription,
+            error: outcome.error,
+            wer,
+            latency_secs: outcome.duration_secs,
+            audio_secs: sample.duration_secs,
+        });
+    }
+
+    Ok(summarize(target, samples, results))
+}
+
+fn summarize(
+    target: &BenchTarget,
+    samples: &[BenchSample],
+    results: Vec<BenchSampleResult>,
+) -> BenchReport {
+    let mut edits = 0usize;
+    let mut reference_words = 0usize;
+    let mut processing_secs = 0.0;
+    let mut audio_secs = 0.0;
+    let mut latencies = Vec::new();
+
+    for (sample, result) in samples.iter().zip(&results) {
+        let Some(hypothesis) = result.hypothesis.as_deref().filter(|_| result.success) else {
+            continue;
+        };
+
+        let reference = normalize_words(&sample.reference);
+        edits += edit_distance(&reference, &normalize_words(hypothesis));
+        reference_words += reference.len();
+        processing_secs += result.latency_secs;
+        audio_secs += result.audio_secs;
+        latencies.push(result.latency_secs);
+    }
+
+    latencies.sort_by(|a, b| a.total_cmp(b));
+
+    BenchReport {
+        target: target.name.clone(),
+        provider: target.whisper.provider.clone(),
+        model: target.whisper.model.clone(),
+        samples: results.len(),
+        failures: results.iter().filter(|r| !r.success).count(),
+        wer: (reference_words > 0).then(|| edits as f64 / reference_words as f64),
+        rtf: (audio_secs > 0.0).then(|| processing_secs / audio_secs),
+        latency_p50_secs: percentile(&latencies, 50.0),
+        latency_p90_secs: percentile(&latencies, 90.0),
+        latency_p99_secs: percentile(&latencies, 99.0),
+        results,
+    }
+}
+
+/// Word error rate of a hypothesis against a reference.
+///
+/// Both texts are lowercased and stripped of punctuation before comparison.
+pub fn word_error_rate(reference: &str, hypothesis: &str) -> f64 {
+    let reference = normalize_words(reference);
+    let hypothesis = normalize_words(hypothesis);
+
+    if reference.is_empty() {
+        return if hypothesis.is_empty() { 0.0 } else { 1.0 };
+    }
+
+    edit_distance(&reference, &hypothesis) as f64 / reference.len() as f64
+}
+
+fn normalize_words(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(|word| {
+            word.chars()
+                .filter(|c| c.is_alphanumeric() || *c == '\'')
+                .flat_map(char::to_lowercase)
+                .collect::<String>()
+        })
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+/// Levenshtein distance over words.
+fn edit_distance(reference: &[String], hypothesis: &[String]) -> usize {
+    let mut previous: Vec<usize> = (0..=hypothesis.len()).collect();
+    let mut current = vec![0; hypothesis.len() + 1];
+
+    for (i, ref_word) in reference.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, hyp_word) in hypothesis.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ref_word != hyp_word);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[hypothesis.len()]
+}
+
+/// Nearest-rank percentile of sorted values.
+fn percentile(sorted: &[f64], pct: f64) -> Option<f64> {
+    if sorted.is_empty() {
+        return None;
+    }
+
+    let rank = ((pct / 100.0) * sorted.len() as f64).ceil() as usize;
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_word_error_rate() {
+        assert_eq!(word_error_rate("hello world", "Hello, world!"), 0.0);
+        assert_eq!(
+            word_error_rate("the cat sat", "the cat sat down"),
+            1.0 / 3.0
+        );
+        assert_eq!(word_error_rate("the cat sat", "a cat"), 2.0 / 3.0);
+        assert_eq!(word_error_rate("", ""), 0.0);
+        assert_eq!(word_error_rate("", "noise"), 1.0);
+    }
+
+    #[test]
+    fn test_percentile() {
+        let values = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0];
+        assert_eq!(percentile(&values, 50.0), Some(5.0));
+        assert_eq!(percentile(&values, 90.0), Some(9.0));
+        assert_eq!(percentile(&values, 99.0), Some(10.0));
+        assert_eq!(percentile(&[], 50.0), None);
+    }
+
+    #[test]
+    fn test_summarize_skips_failures() {
+        let target = BenchTarget {
+            name: "default".to_string(),
+            whisper: WhisperConfig::default(),
+        };
+        let samples = vec![
+            BenchSample {
+                audio_path: PathBuf::from("a.wav"),
+                reference: "one two three four".to_string(),
+                duration_secs: 4.0,
+            },
+            BenchSample {
+                audio_path: PathBuf::from("b.wav"),
+                reference: "five six".to_string(),
+                duration_secs: 2.0,
+            },
+        ];
+        let results = vec![
+            BenchSampleResult {
+                file: "a.wav".to_string(),
+                success: true,
+                hypothesis: Some("one two three".to_string()),
+                error: None,
+                wer: Some(0.25),
+                latency_secs: 1.0,
+                audio_secs: 4.0,
+            },
+            BenchSampleResult {
+                file: "b.wav".to_string(),
+                success: false,
+                hypothesis: None,
+                error: Some("offline".to_string()),
+                wer: None,
+                latency_secs: 0.5,
+                audio_secs: 2.0,
+            },
+        ];
+
+        let report = summarize(&target, &samples, results);
+        assert_eq!(report.samples, 2);
+        assert_eq!(report.failures, 1);
+        assert_eq!(report.wer, Some(0.25));
+        assert_eq!(report.rtf, Some(0.25));
+        assert_eq!(report.latency_p50_secs, Some(1.0));
+    }
+}
@@ -0,0 +1,139 @@
+//! Detection of text that speech models produce from silence.
+//!
+//! Whisper tends to answer near-silent clips with phrases from video
+//! subtitles, such as "Thank you for watching" or "Please subscribe". A
+//! transcript is suspect when the recording had almost no speech, or when it
+//! is a known hallucination and the recording was too short to plausibly
+//! contain it.
+
+use crate::audio::SpeechStats;
+use crate::config::{HallucinationAction, HallucinationConfig};
+
+#[derive(Debug, Clone)]
+pub struct HallucinationFilter {
+    enabled: bool,
+    action: HallucinationAction,
+    min_speech_secs: f32,
+    blocklist_speech_secs: f32,
+    /// Normalized blocklist phrases
+    blocklist: Vec<String>,
+}
+
+impl Default for HallucinationFilter {
+    fn default() -> Self {
+        Self::new(&HallucinationConfig::default())
+    }
+}
+
+impl HallucinationFilter {
+    pub fn new(config: &HallucinationConfig) -> Self {
+        Self {
+            enabled: config.enabled,
+            action: config.action,
+            min_speech_secs: config.min_speech_secs,
+            blocklist_speech_secs: config.blocklist_speech_secs,
+            blocklist: config
+                .blocklist
+                .iter()
+                .map(|phrase| normalize(phrase))
+                .filter(|phrase| !phrase.is_empty())
+                .collect(),
+        }
+    }
+
+    pub fn action(&self) -> HallucinationAction {
+        self.action
+    }
+
+    /// Why a transcript looks hallucinated, or `None` if it looks fine.
+    ///
+    /// Without speech stats (e.g. for queued jobs) only the blocklist applies.
+    pub fn check(&self, text: &str, speech: Option<&SpeechStats>) -> Option<String> {
+        if !self.enabled || text.trim().is_empty() {
+            return None;
+        }
+
+        if let Some(speech) = speech {
+            if speech.speech_secs < self.min_speech_secs {
+                return Some(format!(
+                    "only {:.2}s of speech in {:.1}s recording",
+                    speech.speech_secs, speech.duration_secs
+                ));
+            }
+        }
+
+        let normalized = normalize(text);
+        if !self.blocklist.contains(&normalized) {
+            return None;
+        }
+
+        match speech {
+            Some(speech) if speech.speech_secs >= self.blocklist_speech_secs => None,
+            Some(speech) => Some(format!(
+                "known hallucination \"{}\" with {:.2}s of speech",
+                normalized, speech.speech_secs
+            )),
+            None => Some(format!("known hallucination \"{}\"", normalized)),
+        }
+    }
+}
+
+/// Lowercase words without punctuation.
+fn normalize(text: &str) -> String {
+    text.split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn speech(speech_secs: f32) -> SpeechStats {
+        SpeechStats {
+            duration_secs: 3.0,
+            speech_secs,
+            peak_rms: 0.1,
+        }
+    }
+
+    #[test]
+    fn test_silent_recording_is_suspect() {
+        let filter = HallucinationFilter::default();
+        assert!(filter
+            .check("Anything at all.", Some(&speech(0.1)))
+            .is_some());
+        assert!(filter
+            .check("Anything at all.", Some(&speech(2.0)))
+            .is_none());
+    }
+
+    #[test]
+    fn test_blocklist_needs_enough_speech() {
+        let filter = HallucinationFilter::default();
+        assert!(filter
+            .check("Thank you for watching!", Some(&speech(0.8)))
+            .is_some());
+        assert!(filter.check(" please subscribe", None).is_some());
+        assert!(filter.check("Thanks for watching, Anna.", None).is_none());
+    }
+
+    #[test]
+    fn test_short_replies_are_not_blocklisted() {
+        let filter = HallucinationFilter::default();
+        for reply in ["Thank you.", "Okay.", "So.", "Bye!", "You."] {
+            assert!(filter.check(reply, Some(&speech(0.5))).is_none(), "{reply}");
+        }
+    }
+
+    #[test]
+    fn test_disabled_filter() {
+        let filter = HallucinationFilter::new(&HallucinationConfig {
+            enabled: false,
+            ..HallucinationConfig::default()
+        });
+        assert!(filter.check("you", Some(&speech(0.0))).is_none());
+    }
+}
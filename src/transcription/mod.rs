@@ -6,16 +6,23 @@ use tracing::info;
 use crate::config::{Config, WhisperConfig};
 use crate::normalizer::TranscriptionNormalizer;
 
+mod hallucination;
 mod transcription_service;
 
+pub mod bench;
+
 pub mod providers;
 
+pub mod upload;
+
 pub use providers::{
-    AssemblyAIProvider, GroqProvider, OpenAIProvider, OpenAIWhisperCliProvider, ParakeetModel,
-    ParakeetProvider, TranscriptionProvider, WhisperCppProvider,
+    AssemblyAIProvider, CommandProvider, GroqProvider, OpenAIProvider, OpenAIWhisperCliProvider,
+    ParakeetModel, ParakeetProvider, ProviderCapabilities, ProviderLocality, TranscriptionProvider,
+    WhisperCppProvider,
 };
 
-pub use transcription_service::TranscriptionService;
+pub use hallucination::HallucinationFilter;
+pub use transcription_service::{TranscribeOptions, Transcript, TranscriptionService};
 
 pub struct Transcriber {
     provider: Box<dyn TranscriptionProvider>,
@@ -63,8 +70,15 @@ impl Transcriber {
                     config.model_path,
                 )?)
             }
+            "command" => Box::new(CommandProvider::new(
+                config.command_path,
+                config.command_args,
+                config.model,
+                config.command_options,
+                config.timeout_secs,
+            )?),
             _ => bail!(
-                "Unknown transcription provider '{}'. Supported: groq, parakeet-v2, parakeet-v3, openai-api, openai-cli, whisper-cpp, assembly-ai",
+                "Unknown transcription provider '{}'. Supported: groq, parakeet-v2, parakeet-v3, openai-api, openai-cli, whisper-cpp, assembly-ai, command",
                 provider_name
             ),
         };
@@ -75,19 +89,38 @@ impl Transcriber {
     }
 
     pub async fn transcribe(&self, audio_path: &PathBuf) -> Result<String> {
+        self.transcribe_with_language(audio_path, None).await
+    }
+
+    /// Transcribe with an optional per-job language override.
+    pub async fn transcribe_with_language(
+        &self,
+        audio_path: &PathBuf,
+        language: Option<&str>,
+    ) -> Result<String> {
+        let language = language.unwrap_or(&self.language);
         info!(
-            "Transcribing audio file: {:?} with {}",
+            "Transcribing audio file: {:?} with {} ({})",
             audio_path,
-            self.provider.name()
+            self.provider.name(),
+            language
         );
         self.provider
-            .transcribe(audio_path.as_path(), &self.language)
+            .transcribe(audio_path.as_path(), language)
             .await
     }
 
     pub fn normalizer(&self) -> Result<Box<dyn TranscriptionNormalizer>> {
         self.provider.normalizer()
     }
+
+    pub fn capabilities(&self) -> ProviderCapabilities {
+        self.provider.capabilities()
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.provider.name()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -98,6 +131,9 @@ pub struct ProviderConfig {
     pub command_path: Option<String>,
     pub api_endpoint: Option<String>,
     pub api_key: Option<String>,
+    pub command_args: Vec<String>,
+    pub command_options: serde_json::Map<String, serde_json::Value>,
+    pub timeout_secs: Option<u64>,
 }
 
 impl Default for ProviderConfig {
@@ -109,6 +145,9 @@ impl Default for ProviderConfig {
             command_path: None,
             api_endpoint: None,
             api_key: None,
+            command_args: Vec::new(),
+            command_options: serde_json::Map::new(),
+            timeout_secs: None,
         }
     }
 }
@@ -122,10 +161,32 @@ impl From<&WhisperConfig> for ProviderConfig {
             command_path: whisper.command_path.clone(),
             api_endpoint: whisper.api_endpoint.clone(),
             api_key: whisper.api_key.clone(),
+            command_args: whisper.command_args.clone(),
+            command_options: whisper.command_options.clone(),
+            timeout_secs: whisper.timeout_secs,
         }
     }
 }
 
+/// Transcription result exchanged with external engines and API clients.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptResult {
+    /// The transcribed text
+    pub text: String,
+    /// Detected or requested language code
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Audio duration in seconds, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_secs: Option<f64>,
+    /// Provider output before post-processing, when it differs from `text`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_text: Option<String>,
+    /// History entry the transcript was saved as
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub history_id: Option<i64>,
+}
+
 // ============================================================================
 // Provider status and validation
 // ============================================================================
@@ -139,6 +200,7 @@ pub enum ProviderStatus {
         provider: String,
         model: Option<String>,
         language: Option<String>,
+        capabilities: ProviderCapabilities,
     },
     /// Provider is configured but validation failed
     ConfigError { provider: String, error: String },
@@ -182,10 +244,11 @@ pub fn get_provider_status_from_config(whisper: &WhisperConfig) -> Result<Provid
     // Try to initialize the provider to verify it works
     let provider_config = ProviderConfig::from(whisper);
     match Transcriber::with_provider(&provider, provider_config) {
-        Ok(_) => Ok(ProviderStatus::Ready {
+        Ok(transcriber) => Ok(ProviderStatus::Ready {
             provider,
             model: whisper.model.clone(),
             language: whisper.language.clone(),
+            capabilities: transcriber.capabilities(),
         }),
         Err(e) => Ok(ProviderStatus::ConfigError {
             provider,
@@ -194,8 +257,31 @@ pub fn get_provider_status_from_config(whisper: &WhisperConfig) -> Result<Provid
     }
 }
 
+/// Look up the capabilities of a provider by name without initializing it.
+pub fn provider_capabilities(provider: &str) -> Option<ProviderCapabilities> {
+    match provider {
+        "groq" | "groq-api" => Some(GroqProvider::default_capabilities()),
+        "parakeet-v2" => Some(ParakeetModel::V2English.capabilities()),
+        "parakeet-v3" => Some(ParakeetModel::V3Multilingual.capabilities()),
+        "assembly-ai" => Some(AssemblyAIProvider::default_capabilities()),
+        "openai-api" => Some(OpenAIProvider::default_capabilities()),
+        "openai-cli" => Some(OpenAIWhisperCliProvider::default_capabilities()),
+        "whisper-cpp" => Some(WhisperCppProvider::default_capabilities()),
+        "command" => Some(CommandProvider::default_capabilities()),
+        _ => None,
+    }
+}
+
 /// Validate provider configuration and return an error message if invalid.
 pub fn validate_provider_config(provider: &str, whisper: &WhisperConfig) -> Option<String> {
+    if let (Some(capabilities), Some(language)) =
+        (provider_capabilities(provider), whisper.language.as_deref())
+    {
+        if let Err(error) = capabilities.check_language(language) {
+            return Some(format!("{} for {}", error, provider));
+        }
+    }
+
     match provider {
         "groq" | "groq-api" => {
             if whisper.api_key.is_none() {
@@ -235,6 +321,13 @@ pub fn validate_provider_config(provider: &str, whisper: &WhisperConfig) -> Opti
                 None
             }
         }
+        "command" => {
+            if whisper.command_path.is_none() {
+                Some("Command path required for command provider".to_string())
+            } else {
+                None
+            }
+        }
         _ => Some(format!("Unknown provider: {}", provider)),
     }
 }
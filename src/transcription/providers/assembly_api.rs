@@ -6,7 +6,7 @@ use std::pin::Pin;
 use std::time::Duration;
 use tracing::{debug, error, info};
 
-use super::TranscriptionProvider;
+use super::{ProviderCapabilities, ProviderLocality, TranscriptionProvider};
 use crate::normalizer::TranscriptionNormalizer;
 
 /// Response from the upload endpoint
@@ -69,6 +69,14 @@ impl AssemblyAIProvider {
         })
     }
 
+    pub fn default_capabilities() -> ProviderCapabilities {
+        ProviderCapabilities {
+            timestamps: true,
+            locality: ProviderLocality::Cloud,
+            ..ProviderCapabilities::local()
+        }
+    }
+
     /// Upload audio file to AssemblyAI and get a URL
     async fn upload_audio(&self, audio_path: &Path) -> Result<String> {
         let upload_url = format!("{}/upload", self.base_url);
@@ -262,6 +270,10 @@ impl TranscriptionProvider for AssemblyAIProvider {
         !self.api_key.is_empty()
     }
 
+    fn capabilities(&self) -> ProviderCapabilities {
+        Self::default_capabilities()
+    }
+
     fn transcribe<'a>(
         &'a self,
         audio_path: &'a Path,
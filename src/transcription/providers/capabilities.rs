@@ -0,0 +1,223 @@
+use serde::{Deserialize, Serialize};
+
+/// Languages supported by Parakeet TDT v3.
+pub const PARAKEET_V3_LANGUAGES: &[&str] = &[
+    "bg", "hr", "cs", "da", "nl", "en", "et", "fi", "fr", "de", "el", "hu", "it", "lv", "lt", "mt",
+    "pl", "pt", "ro", "sk", "sl", "es", "sv", "ru", "uk",
+];
+
+/// Where a provider runs inference.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProviderLocality {
+    /// Audio never leaves the machine
+    Local,
+    /// Audio is uploaded to a remote service
+    Cloud,
+}
+
+impl ProviderLocality {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProviderLocality::Local => "local",
+            ProviderLocality::Cloud => "cloud",
+        }
+    }
+}
+
+/// Static description of what a transcription provider supports.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProviderCapabilities {
+    /// Supported ISO 639-1 language codes (`None` means any language)
+    pub languages: Option<Vec<String>>,
+    /// Whether `language = "auto"` is supported
+    pub auto_language: bool,
+    /// Maximum audio upload size in bytes
+    pub max_upload_bytes: Option<u64>,
+    /// Maximum audio duration in seconds
+    pub max_duration_secs: Option<u64>,
+    /// Whether the engine can produce timestamps
+    pub timestamps: bool,
+    /// Whether the engine can translate to English
+    pub translation: bool,
+    /// Whether the engine accepts an initial prompt
+    pub prompts: bool,
+    /// Whether the engine supports streaming transcription
+    pub streaming: bool,
+    /// Local or cloud inference
+    pub locality: ProviderLocality,
+}
+
+impl ProviderCapabilities {
+    /// Capabilities of a local engine that accepts any language and
+    /// advertises no optional features.
+    pub fn local() -> Self {
+        Self {
+            languages: None,
+            auto_language: true,
+            max_upload_bytes: None,
+            max_duration_secs: None,
+            timestamps: false,
+            translation: false,
+            prompts: false,
+            streaming: false,
+            locality: ProviderLocality::Local,
+        }
+    }
+
+    /// Capabilities of a cloud Whisper-compatible API.
+    pub fn whisper_api(max_upload_bytes: u64) -> Self {
+        Self {
+            max_upload_bytes: Some(max_upload_bytes),
+            timestamps: true,
+            translation: true,
+            prompts: true,
+            locality: ProviderLocality::Cloud,
+            ..Self::local()
+        }
+    }
+
+    /// Capabilities of a local Whisper model.
+    pub fn whisper_local() -> Self {
+        Self {
+            timestamps: true,
+            translation: true,
+            prompts: true,
+            ..Self::local()
+        }
+    }
+
+    pub fn with_languages(mut self, languages: &[&str]) -> Self {
+        self.languages = Some(languages.iter().map(|l| l.to_string()).collect());
+        self
+    }
+
+    pub fn is_local(&self) -> bool {
+        self.locality == ProviderLocality::Local
+    }
+
+    /// Whether the given language code (or `auto`) is supported.
+    pub fn supports_language(&self, language: &str) -> bool {
+        if language.is_empty() || language == "auto" {
+            return self.auto_language;
+        }
+
+        match &self.languages {
+            Some(languages) => languages.iter().any(|l| l == language),
+            None => true,
+        }
+    }
+
+    /// Check a language code and return a user-facing error if unsupported.
+    ///
+    /// `auto` is always accepted: it is the config default, and providers
+    /// without detection transcribe in their own language.
+    pub fn check_language(&self, language: &str) -> Result<(), String> {
+        if language.is_empty() || language == "auto" || self.supports_language(language) {
+            return Ok(());
+        }
+
+        Err(format!(
+            "Language '{}' is not supported (supported: {})",
+            language,
+            self.languages_display()
+        ))
+    }
+
+    /// Check an audio file size against the upload limit.
+    pub fn check_upload_size(&self, size_bytes: u64) -> Result<(), String> {
+        match self.max_upload_bytes {
+            Some(max) if size_bytes > max => Err(format!(
+                "Audio is {:.1} MB but the provider accepts at most {:.1} MB",
+                size_bytes as f64 / 1_000_000.0,
+                max as f64 / 1_000_000.0
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Check an audio duration against the provider limit.
+    pub fn check_duration(&self, duration_secs: f64) -> Result<(), String> {
+        match self.max_duration_secs {
+            Some(max) if duration_secs > max as f64 => Err(format!(
+                "Audio is {:.0}s long but the provider accepts at most {}s",
+                duration_secs, max
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Comma-separated language list, or "any".
+    pub fn languages_display(&self) -> String {
+        match &self.languages {
+            Some(languages) => languages.join(", "),
+            None => "any".to_string(),
+        }
+    }
+
+    /// Short list of the optional features the provider supports.
+    pub fn features(&self) -> Vec<&'static str> {
+        let mut features = Vec::new();
+        if self.timestamps {
+            features.push("timestamps");
+        }
+        if self.translation {
+            features.push("translation");
+        }
+        if self.prompts {
+            features.push("prompts");
+        }
+        if self.streaming {
+            features.push("streaming");
+        }
+        features
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_supports_language() {
+        let any = ProviderCapabilities::local();
+        assert!(any.supports_language("de"));
+        assert!(any.supports_language("auto"));
+
+        let english = ProviderCapabilities {
+            auto_language: false,
+            ..ProviderCapabilities::local()
+        }
+        .with_languages(&["en"]);
+        assert!(english.supports_language("en"));
+        assert!(!english.supports_language("de"));
+        assert!(!english.supports_language("auto"));
+        assert!(english.check_language("de").unwrap_err().contains("en"));
+        assert!(english.check_language("auto").is_ok());
+        assert!(english.check_language("").is_ok());
+    }
+
+    #[test]
+    fn test_upload_and_duration_limits() {
+        let caps = ProviderCapabilities::whisper_api(25_000_000);
+        assert!(caps.check_upload_size(1_000).is_ok());
+        assert!(caps.check_upload_size(30_000_000).is_err());
+        assert!(caps.check_duration(3600.0).is_ok());
+
+        let limited = ProviderCapabilities {
+            max_duration_secs: Some(60),
+            ..ProviderCapabilities::local()
+        };
+        assert!(limited.check_duration(61.0).is_err());
+    }
+
+    #[test]
+    fn test_features() {
+        assert!(ProviderCapabilities::local().features().is_empty());
+        assert_eq!(
+            ProviderCapabilities::whisper_local().features(),
+            vec!["timestamps", "translation", "prompts"]
+        );
+        assert!(!ProviderCapabilities::whisper_api(1).is_local());
+    }
+}
@@ -0,0 +1,317 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tracing::{debug, error, info};
+use which::which;
+
+use super::{ProviderCapabilities, TranscriptionProvider};
+use crate::normalizer::TranscriptionNormalizer;
+use crate::transcription::TranscriptResult;
+
+const DEFAULT_TIMEOUT_SECS: u64 = 120;
+
+/// Maximum number of stderr bytes included in error messages.
+const STDERR_TAIL_BYTES: usize = 2048;
+
+/// JSON request written to the external command's stdin.
+#[derive(Debug, Serialize)]
+struct CommandRequest<'a> {
+    audio_path: &'a Path,
+    language: &'a str,
+    model: Option<&'a str>,
+    options: &'a Map<String, Value>,
+}
+
+/// Transcription provider that delegates to a user-specified executable.
+///
+/// The executable receives a JSON request on stdin (`audio_path`, `language`,
+/// `model`, `options`) and must print a JSON `TranscriptResult` on stdout.
+/// Anything written to stderr is captured and logged.
+pub struct CommandProvider {
+    command_path: PathBuf,
+    args: Vec<String>,
+    model: Option<String>,
+    options: Map<String, Value>,
+    timeout: Duration,
+}
+
+impl CommandProvider {
+    pub fn new(
+        command_path: Option<String>,
+        args: Vec<String>,
+        model: Option<String>,
+        options: Map<String, Value>,
+        timeout_secs: Option<u64>,
+    ) -> Result<Self> {
+        let command = command_path.context("command_path is required for command provider")?;
+
+        let custom_path = PathBuf::from(&command);
+        let command_path = if custom_path.exists() {
+            custom_path
+        } else {
+            which(&command)
+                .with_context(|| format!("Transcription command not found: {}", command))?
+        };
+
+        let timeout = Duration::from_secs(timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS));
+
+        info!(
+            "Using external transcription command {:?} (timeout {}s)",
+            command_path,
+            timeout.as_secs()
+        );
+
+        Ok(Self {
+            command_path,
+            args,
+            model,
+            options,
+            timeout,
+        })
+    }
+
+    /// Capabilities of an external engine are unknown, so nothing is rejected.
+    pub fn default_capabilities() -> ProviderCapabilities {
+        ProviderCapabilities::local()
+    }
+}
+
+impl TranscriptionProvider for CommandProvider {
+    fn name(&self) -> &'static str {
+        "External Command"
+    }
+
+    fn is_available(&self) -> bool {
+        self.command_path.exists()
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        Self::default_capabilities()
+    }
+
+    fn normalizer(&self) -> Result<Box<dyn TranscriptionNormalizer>> {
+        Ok(Box::new(CommandNormalizer::new()))
+    }
+
+    fn transcribe<'a>(
+        &'a self,
+        audio_path: &'a Path,
+        language: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<String>> + Send + 'a>> {
+        Box::pin(async move {
+            info!(
+                "Transcribing audio file via {:?}: {:?}",
+                self.command_path, audio_path
+            );
+
+            let request = CommandRequest {
+                audio_path,
+                language,
+                model: self.model.as_deref(),
+                options: &self.options,
+            };
+            let payload = serde_json::to_vec(&request).context("Failed to encode request")?;
+
+            let mut child = Command::new(&self.command_path)
+                .args(&self.args)
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .kill_on_drop(true)
+                .spawn()
+                .with_context(|| format!("Failed to execute {:?}", self.command_path))?;
+
+            // Write the request while collecting output, both under the
+            // timeout, so a command that never reads stdin can't hang us
+            let stdin = child.stdin.take();
+            let write = async move {
+                let Some(mut stdin) = stdin else {
+                    return Ok(());
+                };
+                match stdin.write_all(&payload).await {
+                    // The command exited without reading; its status says why
+                    Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => Ok(()),
+                    result => result,
+                }
+            };
+            let (written, output) = tokio::time::timeout(self.timeout, async {
+                tokio::join!(write, child.wait_with_output())
+            })
+            .await
+            .map_err(|_| {
+                anyhow::anyhow!(
+                    "Transcription command timed out after {}s",
+                    self.timeout.as_secs()
+                )
+            })?;
+            let output = output.context("Failed to wait for transcription command")?;
+
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if !stderr.trim().is_empty() {
+                debug!("Transcription command stderr: {}", stderr.trim());
+            }
+
+            if !output.status.success() {
+                error!("Transcription command failed: {}", output.status);
+                return Err(anyhow::anyhow!(
+                    "Transcription command failed ({}): {}",
+                    output.status,
+                    stderr_tail(&stderr)
+                ));
+            }
+
+            written.context("Failed to write request to command stdin")?;
+
+            let result: TranscriptResult = serde_json::from_slice(&output.stdout)
+                .context("Failed to parse transcription command output as JSON")?;
+
+            info!("Transcription complete: {} chars", result.text.len());
+
+            Ok(result.text)
+        })
+    }
+}
+
+fn stderr_tail(stderr: &str) -> &str {
+    let trimmed = stderr.trim();
+    if trimmed.len() <= STDERR_TAIL_BYTES {
+        return trimmed;
+    }
+
+    let mut start = trimmed.len() - STDERR_TAIL_BYTES;
+    while !trimmed.is_char_boundary(start) {
+        start += 1;
+    }
+    &trimmed[start..]
+}
+
+struct CommandNormalizer;
+
+impl CommandNormalizer {
+    fn new() -> Self {
+        Self
+    }
+}
+
+impl TranscriptionNormalizer for CommandNormalizer {
+    fn normalize(&self, raw_output: &str) -> String {
+        raw_output.trim().to_string()
+    }
+
+    fn name(&self) -> &'static str {
+        "CommandNormalizer"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shell_provider(script: &str, timeout_secs: Option<u64>) -> CommandProvider {
+        CommandProvider::new(
+            Some("sh".to_string()),
+            vec!["-c".to_string(), script.to_string()],
+            Some("tiny".to_string()),
+            Map::new(),
+            timeout_secs,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_command_provider_requires_path() {
+        let result = CommandProvider::new(None, Vec::new(), None, Map::new(), None);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_command_provider_missing_binary() {
+        let result = CommandProvider::new(
+            Some("/nonexistent/wisprarch-engine".to_string()),
+            Vec::new(),
+            None,
+            Map::new(),
+            None,
+        );
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_command_provider_reads_json_result() {
+        let provider = shell_provider(
+            r#"grep -q '"language":"de"' && echo '{"text":"  hallo welt  "}'"#,
+            None,
+        );
+
+        let text = provider
+            .transcribe(Path::new("/tmp/audio.wav"), "de")
+            .await
+            .unwrap();
+        assert_eq!(text, "  hallo welt  ");
+    }
+
+    #[tokio::test]
+    async fn test_command_provider_reports_stderr_on_failure() {
+        let provider = shell_provider("cat >/dev/null; echo 'model exploded' >&2; exit 3", None);
+
+        let err = provider
+            .transcribe(Path::new("/tmp/audio.wav"), "en")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("model exploded"));
+    }
+
+    #[tokio::test]
+    async fn test_command_provider_timeout() {
+        let provider = shell_provider("sleep 5", Some(0));
+
+        let err = provider
+            .transcribe(Path::new("/tmp/audio.wav"), "en")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("timed out"));
+    }
+
+    #[tokio::test]
+    async fn test_command_provider_timeout_without_reading_stdin() {
+        // Never reads its input, so a large request fills the pipe
+        let mut provider = shell_provider("sleep 5", Some(1));
+        provider
+            .options
+            .insert("padding".to_string(), Value::from("x".repeat(1024 * 1024)));
+
+        let err = provider
+            .transcribe(Path::new("/tmp/audio.wav"), "en")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("timed out"));
+    }
+
+    #[tokio::test]
+    async fn test_command_provider_reports_exit_before_reading() {
+        let mut provider = shell_provider("echo 'bad model' >&2; exit 3", None);
+        provider
+            .options
+            .insert("padding".to_string(), Value::from("x".repeat(1024 * 1024)));
+
+        let err = provider
+            .transcribe(Path::new("/tmp/audio.wav"), "en")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("bad model"), "{:#}", err);
+    }
+
+    #[test]
+    fn test_stderr_tail_truncates() {
+        let long = "x".repeat(STDERR_TAIL_BYTES + 10);
+        assert_eq!(stderr_tail(&long).len(), STDERR_TAIL_BYTES);
+        assert_eq!(stderr_tail("  short  "), "short");
+    }
+}
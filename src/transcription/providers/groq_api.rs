@@ -6,7 +6,7 @@ use std::path::Path;
 use std::pin::Pin;
 use tracing::{debug, error, info, warn};
 
-use super::TranscriptionProvider;
+use super::{ProviderCapabilities, TranscriptionProvider};
 use crate::normalizer::TranscriptionNormalizer;
 
 #[derive(Debug, Deserialize)]
@@ -48,6 +48,11 @@ impl GroqProvider {
             model,
         })
     }
+
+    /// Groq accepts uploads up to 25 MB on the free tier.
+    pub fn default_capabilities() -> ProviderCapabilities {
+        ProviderCapabilities::whisper_api(25_000_000)
+    }
 }
 
 impl TranscriptionProvider for GroqProvider {
@@ -59,6 +64,10 @@ impl TranscriptionProvider for GroqProvider {
         !self.api_key.is_empty()
     }
 
+    fn capabilities(&self) -> ProviderCapabilities {
+        Self::default_capabilities()
+    }
+
     fn transcribe<'a>(
         &'a self,
         audio_path: &'a Path,
@@ -6,6 +6,8 @@ use std::pin::Pin;
 use crate::normalizer::TranscriptionNormalizer;
 
 pub mod assembly_api;
+pub mod capabilities;
+pub mod command;
 pub mod groq_api;
 pub mod openai_api;
 pub mod openai_cli;
@@ -13,6 +15,8 @@ pub mod parakeet;
 pub mod whisper_cpp;
 
 pub use assembly_api::AssemblyAIProvider;
+pub use capabilities::{ProviderCapabilities, ProviderLocality};
+pub use command::CommandProvider;
 pub use groq_api::GroqProvider;
 pub use openai_api::OpenAIProvider;
 pub use openai_cli::OpenAIWhisperCliProvider;
@@ -24,6 +28,9 @@ pub trait TranscriptionProvider: Send + Sync {
 
     fn is_available(&self) -> bool;
 
+    /// Describe languages, limits and optional features of this provider.
+    fn capabilities(&self) -> ProviderCapabilities;
+
     fn normalizer(&self) -> Result<Box<dyn TranscriptionNormalizer>>;
 
     fn transcribe<'a>(
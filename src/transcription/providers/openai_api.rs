@@ -6,7 +6,7 @@ use std::path::Path;
 use std::pin::Pin;
 use tracing::{debug, error, info};
 
-use super::TranscriptionProvider;
+use super::{ProviderCapabilities, TranscriptionProvider};
 use crate::normalizer::TranscriptionNormalizer;
 
 #[derive(Debug, Deserialize)]
@@ -48,6 +48,11 @@ impl OpenAIProvider {
             model,
         })
     }
+
+    /// The OpenAI audio API accepts uploads up to 25 MB.
+    pub fn default_capabilities() -> ProviderCapabilities {
+        ProviderCapabilities::whisper_api(25_000_000)
+    }
 }
 
 impl TranscriptionProvider for OpenAIProvider {
@@ -59,6 +64,10 @@ impl TranscriptionProvider for OpenAIProvider {
         !self.api_key.is_empty()
     }
 
+    fn capabilities(&self) -> ProviderCapabilities {
+        Self::default_capabilities()
+    }
+
     fn transcribe<'a>(
         &'a self,
         audio_path: &'a Path,
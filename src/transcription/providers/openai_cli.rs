@@ -6,7 +6,7 @@ use std::process::Command;
 use tracing::{error, info};
 use which::which;
 
-use super::TranscriptionProvider;
+use super::{ProviderCapabilities, TranscriptionProvider};
 use crate::normalizer::TranscriptionNormalizer;
 
 pub struct OpenAIWhisperCliProvider {
@@ -54,6 +54,10 @@ impl OpenAIWhisperCliProvider {
             model,
         })
     }
+
+    pub fn default_capabilities() -> ProviderCapabilities {
+        ProviderCapabilities::whisper_local()
+    }
 }
 
 impl TranscriptionProvider for OpenAIWhisperCliProvider {
@@ -65,6 +69,10 @@ impl TranscriptionProvider for OpenAIWhisperCliProvider {
         self.command_path.exists()
     }
 
+    fn capabilities(&self) -> ProviderCapabilities {
+        Self::default_capabilities()
+    }
+
     fn normalizer(&self) -> Result<Box<dyn TranscriptionNormalizer>> {
         Ok(Box::new(OpenAIWhisperNormalizer::new()))
     }
@@ -4,7 +4,8 @@ use std::path::{Path, PathBuf};
 use std::pin::Pin;
 use tracing::{debug, info};
 
-use super::TranscriptionProvider;
+use super::capabilities::PARAKEET_V3_LANGUAGES;
+use super::{ProviderCapabilities, TranscriptionProvider};
 use crate::global;
 use crate::normalizer::TranscriptionNormalizer;
 
@@ -40,6 +41,27 @@ impl ParakeetModel {
             Self::V3Multilingual => "Parakeet v3 (Multilingual)",
         }
     }
+
+    /// Languages and limits of this model variant.
+    ///
+    /// Parakeet detects the language itself and cannot be told which one to
+    /// use, so v2 only accepts English and v3 its 25 European languages.
+    pub fn capabilities(&self) -> ProviderCapabilities {
+        let base = ProviderCapabilities {
+            timestamps: true,
+            max_duration_secs: Some(24 * 60),
+            ..ProviderCapabilities::local()
+        };
+
+        match self {
+            Self::V2English => ProviderCapabilities {
+                auto_language: false,
+                ..base
+            }
+            .with_languages(&["en"]),
+            Self::V3Multilingual => base.with_languages(PARAKEET_V3_LANGUAGES),
+        }
+    }
 }
 
 /// Parakeet speech-to-text provider using parakeet-rs
@@ -130,6 +152,10 @@ impl TranscriptionProvider for ParakeetProvider {
         self.model_path.exists()
     }
 
+    fn capabilities(&self) -> ProviderCapabilities {
+        self.model_type.capabilities()
+    }
+
     fn transcribe<'a>(
         &'a self,
         audio_path: &'a Path,
@@ -7,7 +7,7 @@ use std::process::{Command, Stdio};
 use tracing::{debug, error, info, warn};
 use which::which;
 
-use super::TranscriptionProvider;
+use super::{ProviderCapabilities, TranscriptionProvider};
 use crate::normalizer::TranscriptionNormalizer;
 
 pub struct WhisperCppProvider {
@@ -48,6 +48,10 @@ impl WhisperCppProvider {
             model,
         })
     }
+
+    pub fn default_capabilities() -> ProviderCapabilities {
+        ProviderCapabilities::whisper_local()
+    }
 }
 
 impl TranscriptionProvider for WhisperCppProvider {
@@ -59,6 +63,10 @@ impl TranscriptionProvider for WhisperCppProvider {
         self.command_path.exists()
     }
 
+    fn capabilities(&self) -> ProviderCapabilities {
+        Self::default_capabilities()
+    }
+
     fn transcribe<'a>(
         &'a self,
         audio_path: &'a Path,
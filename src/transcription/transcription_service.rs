@@ -1,14 +1,75 @@
-use anyhow::Result;
+use anyhow::{anyhow, bail, Context, Result};
+use std::collections::HashMap;
 use std::path::PathBuf;
-use tracing::{debug, info};
+use std::sync::{Arc, Mutex};
+use tracing::{debug, info, warn};
 
-use super::Transcriber;
-use crate::normalizer::TranscriptionNormalizer;
+use super::{HallucinationFilter, ProviderCapabilities, ProviderConfig, Transcriber};
+use crate::audio::SpeechStats;
+use crate::config::{Config, DictationMode, PostProcessStep, WhisperConfig};
+use crate::normalizer::{LlmCleanup, PostProcessor, SnippetExpander, TranscriptionNormalizer};
+use crate::text_io::Utterance;
+use crate::usage::{QuotaState, UsageTracker};
+
+/// Output of the transcription pipeline.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transcript {
+    /// Final text after post-processing and LLM cleanup
+    pub text: String,
+    /// Normalized provider output before post-processing
+    pub raw_text: String,
+    /// Provider that transcribed the audio
+    pub provider: &'static str,
+    /// Why the provider output looks like a hallucination. `text` is then
+    /// `raw_text`, since post-processing and LLM cleanup were skipped.
+    pub hallucination: Option<String>,
+    /// Voice commands split off `raw_text`; its text is `text`
+    pub utterance: Utterance,
+}
+
+/// Per-job choices for the transcription pipeline.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TranscribeOptions<'a> {
+    /// Language override (default: from the provider config)
+    pub language: Option<&'a str>,
+    pub mode: DictationMode,
+    /// `[[apps]]` rule whose post-processing chain replaces the default one
+    pub app: Option<&'a str>,
+    /// Filter checked against the provider output before post-processing
+    pub hallucination: Option<&'a HallucinationFilter>,
+    /// Speech measured while recording, for the hallucination filter
+    pub speech: Option<&'a SpeechStats>,
+    /// Split voice commands off the provider output before post-processing
+    pub voice_commands: bool,
+}
+
+/// A language override and the `[[apps]]` rule whose steps it applies to.
+type LanguageChain = (String, Option<String>);
 
 /// Service that orchestrates transcription and normalization
 pub struct TranscriptionService {
     transcriber: Transcriber,
     normalizer: Box<dyn TranscriptionNormalizer>,
+    /// User-configured steps that run after the provider normalizer
+    postprocessor: PostProcessor,
+    /// Replaces `postprocessor` and LLM cleanup for code dictation
+    code_processor: PostProcessor,
+    /// Chains of `[[apps]]` rules that set their own `postprocess`
+    app_processors: HashMap<String, PostProcessor>,
+    /// Provider config and steps the chains above were built from, to
+    /// rebuild them for a job's language override
+    whisper: Option<WhisperConfig>,
+    steps: Vec<PostProcessStep>,
+    app_steps: HashMap<String, Vec<PostProcessStep>>,
+    /// Chains rebuilt for a language override, by language and app rule
+    language_processors: Mutex<HashMap<LanguageChain, Arc<PostProcessor>>>,
+    /// Optional chat model rewrite after the post-processing steps
+    cleanup: Option<LlmCleanup>,
+    /// `[snippets]`, expanded last so LLM cleanup can't rewrite them
+    snippets: Option<Arc<SnippetExpander>>,
+    usage: Option<UsageTracker>,
+    /// Used instead of the primary provider once its hard quota is exceeded
+    fallback: Option<Box<TranscriptionService>>,
 }
 
 impl TranscriptionService {
@@ -19,27 +80,357 @@ impl TranscriptionService {
         Ok(Self {
             transcriber,
             normalizer,
+            postprocessor: PostProcessor::new(),
+            code_processor: PostProcessor::for_code(),
+            app_processors: HashMap::new(),
+            whisper: None,
+            steps: Vec::new(),
+            app_steps: HashMap::new(),
+            language_processors: Mutex::new(HashMap::new()),
+            cleanup: None,
+            snippets: None,
+            usage: None,
+            fallback: None,
         })
     }
 
+    /// Create the service for `[whisper]`, with usage tracking and the
+    /// `[usage].fallback_profile` provider if one is configured.
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let mut service = Self::tracked(&config.whisper, config)?;
+
+        if let Some(profile) = config.usage.fallback_profile.as_deref() {
+            let whisper = config
+                .profiles
+                .get(profile)
+                .ok_or_else(|| anyhow!("Fallback profile '{}' not found in [profiles]", profile))?;
+            service.fallback = Some(Box::new(Self::tracked(whisper, config)?));
+        }
+
+        Ok(service)
+    }
+
+    fn tracked(whisper: &WhisperConfig, config: &Config) -> Result<Self> {
+        let provider = whisper
+            .provider
+            .as_deref()
+            .ok_or_else(|| anyhow!("No transcription provider configured. Set [whisper].provider in ~/.config/wisprarch/config.toml"))?;
+
+        let transcriber = Transcriber::with_provider(provider, ProviderConfig::from(whisper))?;
+        let enforce_quotas = !transcriber.capabilities().is_local();
+
+        let mut service = Self::new(transcriber)?;
+        service.postprocessor = PostProcessor::for_profile(whisper, &config.postprocess)?;
+        for rule in &config.apps {
+            if let Some(steps) = &rule.postprocess {
+                service.app_processors.insert(
+                    rule.name.clone(),
+                    PostProcessor::for_profile(whisper, steps)?,
+                );
+                service.app_steps.insert(rule.name.clone(), steps.clone());
+            }
+        }
+        service.whisper = Some(whisper.clone());
+        service.steps = config.postprocess.clone();
+        if config.llm_cleanup.enabled {
+            service.cleanup = Some(LlmCleanup::new(&config.llm_cleanup)?);
+        }
+        let snippets = SnippetExpander::new(&config.snippets)?;
+        if !snippets.is_empty() {
+            service.snippets = Some(Arc::new(snippets));
+        }
+        service.usage = Some(UsageTracker::new(
+            provider,
+            whisper.model.clone(),
+            config.usage.clone(),
+            enforce_quotas,
+        ));
+        Ok(service)
+    }
+
     /// Transcribe audio file and return normalized text
     pub async fn transcribe(&self, audio_path: &PathBuf) -> Result<String> {
+        self.transcribe_with_language(audio_path, None).await
+    }
+
+    /// Transcribe audio file with an optional language override
+    pub async fn transcribe_with_language(
+        &self,
+        audio_path: &PathBuf,
+        language: Option<&str>,
+    ) -> Result<String> {
+        let options = TranscribeOptions {
+            language,
+            ..TranscribeOptions::default()
+        };
+        self.transcribe_detailed(audio_path, options)
+            .await
+            .map(|transcript| transcript.text)
+    }
+
+    /// Transcribe audio file, returning both the final and the raw text
+    pub async fn transcribe_detailed(
+        &self,
+        audio_path: &PathBuf,
+        options: TranscribeOptions<'_>,
+    ) -> Result<Transcript> {
         info!("Starting transcription pipeline for: {:?}", audio_path);
 
+        if let Some(usage) = &self.usage {
+            match usage.check_quota().await {
+                QuotaState::Ok => {}
+                QuotaState::SoftExceeded(message) => warn!("{}", message),
+                QuotaState::HardExceeded(message) => match &self.fallback {
+                    Some(fallback) => {
+                        warn!("{}, using fallback provider", message);
+                        return Box::pin(fallback.transcribe_detailed(audio_path, options)).await;
+                    }
+                    None => bail!(message),
+                },
+            }
+        }
+
         // Step 1: Get raw transcription
         debug!("Getting raw transcription");
-        let raw_transcription = self.transcriber.transcribe(audio_path).await?;
+        let result = self
+            .transcriber
+            .transcribe_with_language(audio_path, options.language)
+            .await;
+
+        if let Some(usage) = &self.usage {
+            usage.record(audio_path, result.is_ok()).await;
+        }
+        let raw_transcription = result?;
 
         // Step 2: Normalize the transcription
         debug!("Normalizing transcription output");
         let normalized = self.normalizer.normalize(&raw_transcription);
 
+        // Check the provider's own words, before post-processing or an LLM
+        // can rewrite a hallucination into something that passes the filter
+        let hallucination = options
+            .hallucination
+            .and_then(|filter| filter.check(&normalized, options.speech));
+        if let Some(reason) = hallucination {
+            debug!("Skipping post-processing of likely hallucination");
+            return Ok(Transcript {
+                text: normalized.clone(),
+                utterance: Utterance::plain(&normalized),
+                raw_text: normalized,
+                provider: self.transcriber.name(),
+                hallucination: Some(reason),
+            });
+        }
+
+        // Step 3: Split off voice commands while they are still the
+        // provider's words; post-processing or cleanup could reword them
+        let mut utterance = if options.voice_commands {
+            Utterance::parse(&normalized)
+        } else {
+            Utterance::plain(&normalized)
+        };
+        utterance.text = self.post_process(&utterance.text, &options).await?;
+
         info!(
             "Transcription pipeline complete: {} chars -> {} chars",
             raw_transcription.len(),
-            normalized.len()
+            utterance.text.len()
+        );
+
+        Ok(Transcript {
+            text: utterance.text.clone(),
+            utterance,
+            raw_text: normalized,
+            provider: self.transcriber.name(),
+            hallucination: None,
+        })
+    }
+
+    /// Post-process, clean up and expand the text of a transcript.
+    async fn post_process(&self, text: &str, options: &TranscribeOptions<'_>) -> Result<String> {
+        if text.trim().is_empty() {
+            return Ok(String::new());
+        }
+
+        // Step 4: Run the configured post-processing chain, or the code
+        // dictation chain for code mode
+        let mut text = match options.mode {
+            DictationMode::Prose => match self.language_processor(options)? {
+                Some(processor) => processor.process(text),
+                None => options
+                    .app
+                    .and_then(|app| self.app_processors.get(app))
+                    .unwrap_or(&self.postprocessor)
+                    .process(text),
+            },
+            DictationMode::Code => self.code_processor.process(text),
+        };
+
+        // Step 5: Optional LLM cleanup, keeping the text on failure
+        if let (DictationMode::Prose, Some(cleanup)) = (options.mode, &self.cleanup) {
+            debug!("Running LLM cleanup");
+            text = cleanup.clean_or_original(&text).await;
+        }
+
+        // Step 6: Expand snippets. `{clipboard}` runs a clipboard reader, so
+        // keep it off the async runtime.
+        if let Some(snippets) = &self.snippets {
+            debug!("Expanding snippets");
+            let snippets = Arc::clone(snippets);
+            text = tokio::task::spawn_blocking(move || snippets.normalize(&text))
+                .await
+                .context("Snippet expansion panicked")?;
+        }
+
+        Ok(text)
+    }
+
+    /// The prose chain rebuilt for a job's language override, so ITN and
+    /// spoken punctuation follow the language that was transcribed. `None`
+    /// when the prebuilt chain already fits. Chains are built once per
+    /// language and app rule.
+    fn language_processor(
+        &self,
+        options: &TranscribeOptions<'_>,
+    ) -> Result<Option<Arc<PostProcessor>>> {
+        let (Some(whisper), Some(language)) = (&self.whisper, options.language) else {
+            return Ok(None);
+        };
+        if whisper.language.as_deref() == Some(language) {
+            return Ok(None);
+        }
+
+        let app = options.app.filter(|app| self.app_steps.contains_key(*app));
+        let key = (language.to_string(), app.map(str::to_string));
+        let mut processors = self
+            .language_processors
+            .lock()
+            .map_err(|_| anyhow!("Post-processing cache poisoned"))?;
+        if let Some(processor) = processors.get(&key) {
+            return Ok(Some(Arc::clone(processor)));
+        }
+
+        let steps = app
+            .and_then(|app| self.app_steps.get(app))
+            .unwrap_or(&self.steps);
+        let whisper = WhisperConfig {
+            language: Some(language.to_string()),
+            ..whisper.clone()
+        };
+        let processor = Arc::new(PostProcessor::for_profile(&whisper, steps)?);
+        processors.insert(key, Arc::clone(&processor));
+        Ok(Some(processor))
+    }
+
+    /// Name of the primary provider
+    pub fn provider_name(&self) -> &'static str {
+        self.transcriber.name()
+    }
+
+    /// Capabilities of the underlying provider
+    pub fn capabilities(&self) -> ProviderCapabilities {
+        self.transcriber.capabilities()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Service whose provider always answers `text`, with a spoken
+    /// punctuation step following `[whisper].language = "en"`.
+    fn service(text: &str) -> TranscriptionService {
+        let transcriber = Transcriber::with_provider(
+            "command",
+            ProviderConfig {
+                command_path: Some("sh".to_string()),
+                command_args: vec![
+                    "-c".to_string(),
+                    format!("cat >/dev/null; echo '{{\"text\":\"{}\"}}'", text),
+                ],
+                ..ProviderConfig::default()
+            },
+        )
+        .unwrap();
+        let whisper = WhisperConfig {
+            language: Some("en".to_string()),
+            ..WhisperConfig::default()
+        };
+        let steps = vec![PostProcessStep::SpokenPunctuation {
+            language: None,
+            escape_word: None,
+        }];
+
+        let mut service = TranscriptionService::new(transcriber).unwrap();
+        service.postprocessor = PostProcessor::for_profile(&whisper, &steps).unwrap();
+        service.whisper = Some(whisper);
+        service.steps = steps;
+        service
+    }
+
+    #[tokio::test]
+    async fn test_language_override_reaches_postprocessing() {
+        let service = service("hallo komma welt");
+        let audio = PathBuf::from("/tmp/audio.wav");
+
+        let english = service
+            .transcribe_detailed(&audio, TranscribeOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(english.text, "hallo komma welt");
+
+        let options = TranscribeOptions {
+            language: Some("de"),
+            ..TranscribeOptions::default()
+        };
+        let german = service.transcribe_detailed(&audio, options).await.unwrap();
+        assert_eq!(german.text, "hallo, welt");
+    }
+
+    #[tokio::test]
+    async fn test_voice_commands_are_split_before_postprocessing() {
+        let service = service("see you comma press enter");
+        let audio = PathBuf::from("/tmp/audio.wav");
+
+        let options = TranscribeOptions {
+            voice_commands: true,
+            ..TranscribeOptions::default()
+        };
+        let transcript = service.transcribe_detailed(&audio, options).await.unwrap();
+        assert_eq!(transcript.text, "see you,");
+        assert_eq!(transcript.utterance.text, "see you,");
+        assert_eq!(
+            transcript.utterance.trailing,
+            vec![crate::text_io::VoiceCommand::Key(
+                crate::text_io::KeyAction::Enter
+            )]
         );
+        assert_eq!(transcript.raw_text, "see you comma press enter");
+
+        let plain = service
+            .transcribe_detailed(&audio, TranscribeOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(plain.text, "see you, press enter");
+        assert!(!plain.utterance.has_commands());
+    }
+
+    #[tokio::test]
+    async fn test_language_chains_are_built_once() {
+        let service = service("hallo komma welt");
+        let options = TranscribeOptions {
+            language: Some("de"),
+            ..TranscribeOptions::default()
+        };
+
+        let first = service.language_processor(&options).unwrap().unwrap();
+        let second = service.language_processor(&options).unwrap().unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
 
-        Ok(normalized)
+        let english = TranscribeOptions {
+            language: Some("en"),
+            ..TranscribeOptions::default()
+        };
+        assert!(service.language_processor(&english).unwrap().is_none());
     }
 }
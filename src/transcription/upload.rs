@@ -0,0 +1,202 @@
+//! Audio uploaded through `POST /transcribe`.
+//!
+//! Uploads are written to a temporary file. WAV is passed to the provider
+//! as is; FLAC, Ogg and MP3 are first converted to 16 kHz mono WAV with
+//! ffmpeg, because the local engines and usage tracking only read WAV.
+
+use anyhow::{bail, Context, Result};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tokio::process::Command;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+/// Container formats accepted for upload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioFormat {
+    Wav,
+    Flac,
+    Ogg,
+    Mp3,
+}
+
+impl AudioFormat {
+    /// Detect the format from the first bytes of the file.
+    pub fn detect(data: &[u8]) -> Option<Self> {
+        match data {
+            [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'A', b'V', b'E', ..] => Some(Self::Wav),
+            [b'f', b'L', b'a', b'C', ..] => Some(Self::Flac),
+            [b'O', b'g', b'g', b'S', ..] => Some(Self::Ogg),
+            [b'I', b'D', b'3', ..] => Some(Self::Mp3),
+            // MPEG audio frame sync
+            [0xFF, second, ..] if second & 0xE0 == 0xE0 => Some(Self::Mp3),
+            _ => None,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Wav => "wav",
+            Self::Flac => "flac",
+            Self::Ogg => "ogg",
+            Self::Mp3 => "mp3",
+        }
+    }
+
+    /// Whether the format must be converted to WAV with ffmpeg.
+    pub fn needs_conversion(&self) -> bool {
+        *self != Self::Wav
+    }
+}
+
+/// An uploaded recording on disk. The files are removed on drop, except
+/// the WAV once [`UploadedAudio::keep`] was called.
+#[derive(Debug)]
+pub struct UploadedAudio {
+    format: AudioFormat,
+    path: PathBuf,
+    files: Vec<PathBuf>,
+    keep: bool,
+}
+
+impl UploadedAudio {
+    /// Write the upload to a temporary file.
+    pub async fn save(data: &[u8], format: AudioFormat) -> Result<Self> {
+        let path = PathBuf::from(format!(
+            "/tmp/wisprarch_upload_{}.{}",
+            Uuid::new_v4().simple(),
+            format.extension()
+        ));
+        tokio::fs::write(&path, data)
+            .await
+            .with_context(|| format!("Failed to write upload to {:?}", path))?;
+
+        Ok(Self {
+            format,
+            files: vec![path.clone()],
+            path,
+            keep: false,
+        })
+    }
+
+    /// Convert the upload to 16 kHz mono WAV if it isn't WAV already.
+    pub async fn into_wav(mut self) -> Result<Self> {
+        if !self.format.needs_conversion() {
+            return Ok(self);
+        }
+
+        let wav_path = self.path.with_extension("wav");
+        self.files.push(wav_path.clone());
+        debug!("Converting {:?} to {:?}", self.path, wav_path);
+
+        let output = Command::new("ffmpeg")
+            .args(["-hide_banner", "-loglevel", "error", "-nostdin", "-i"])
+            .arg(&self.path)
+            .args(["-ac", "1", "-ar", "16000", "-y"])
+            .arg(&wav_path)
+            .stdin(Stdio::null())
+            .output()
+            .await
+            .context("Failed to run ffmpeg")?;
+        if !output.status.success() {
+            bail!(
+                "ffmpeg could not decode the {} upload: {}",
+                self.format.extension(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+
+        self.path = wav_path;
+        self.format = AudioFormat::Wav;
+        Ok(self)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Keep the WAV at [`UploadedAudio::path`] on disk, e.g. when history
+    /// refers to it. The original of a converted upload is still removed.
+    pub fn keep(&mut self) {
+        self.keep = true;
+    }
+}
+
+impl Drop for UploadedAudio {
+    fn drop(&mut self) {
+        for file in &self.files {
+            if self.keep && *file == self.path {
+                continue;
+            }
+            if let Err(e) = std::fs::remove_file(file) {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    warn!("Failed to remove upload {:?}: {}", file, e);
+                }
+            }
+        }
+    }
+}
+
+/// Whether ffmpeg is installed for converting compressed uploads.
+pub fn can_convert() -> bool {
+    which::which("ffmpeg").is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_format() {
+        assert_eq!(
+            AudioFormat::detect(b"RIFF\x24\x08\x00\x00WAVEfmt "),
+            Some(AudioFormat::Wav)
+        );
+        assert_eq!(
+            AudioFormat::detect(b"fLaC\x00\x00\x00\x22"),
+            Some(AudioFormat::Flac)
+        );
+        assert_eq!(AudioFormat::detect(b"OggS\x00\x02"), Some(AudioFormat::Ogg));
+        assert_eq!(AudioFormat::detect(b"ID3\x04\x00"), Some(AudioFormat::Mp3));
+        assert_eq!(
+            AudioFormat::detect(&[0xFF, 0xFB, 0x90, 0x64]),
+            Some(AudioFormat::Mp3)
+        );
+        assert_eq!(AudioFormat::detect(b"RIFF\x24\x08\x00\x00AVI "), None);
+        assert_eq!(AudioFormat::detect(b"{\"text\":"), None);
+        assert_eq!(AudioFormat::detect(b""), None);
+    }
+
+    #[tokio::test]
+    async fn test_upload_is_removed_on_drop() {
+        let upload = UploadedAudio::save(b"RIFF\x24\x08\x00\x00WAVE", AudioFormat::Wav)
+            .await
+            .unwrap()
+            .into_wav()
+            .await
+            .unwrap();
+        let path = upload.path().to_path_buf();
+        assert!(path.exists());
+        drop(upload);
+        assert!(!path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_keep_leaves_only_the_wav() {
+        let mut upload = UploadedAudio::save(b"fLaC\x00\x00\x00\x22", AudioFormat::Flac)
+            .await
+            .unwrap();
+        // Stand in for the ffmpeg conversion
+        let original = upload.path().to_path_buf();
+        let wav = original.with_extension("wav");
+        std::fs::write(&wav, b"RIFF\x24\x08\x00\x00WAVE").unwrap();
+        upload.files.push(wav.clone());
+        upload.path = wav.clone();
+
+        upload.keep();
+        drop(upload);
+        assert!(!original.exists());
+        assert!(wav.exists());
+        std::fs::remove_file(wav).unwrap();
+    }
+}
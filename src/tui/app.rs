@@ -16,11 +16,21 @@ use ratatui::{
 use std::io;
 
 use super::theme::Theme;
+use crate::config::{Config, WhisperConfig};
 use crate::models::{ModelRegistry, ModelStatus};
+use crate::transcription::{provider_capabilities, ProviderCapabilities};
+
+/// Cloud providers listed in the TUI, with their default model.
+const CLOUD_PROVIDERS: &[(&str, &str, &str)] = &[
+    ("groq", "Groq Cloud", "whisper-large-v3-turbo"),
+    ("openai-api", "OpenAI Cloud", "whisper-1"),
+    ("assembly-ai", "AssemblyAI", "best"),
+];
 
 struct App {
     theme: Theme,
     registry: ModelRegistry,
+    whisper: WhisperConfig,
     selected_index: usize,
     list_state: ListState,
     should_quit: bool,
@@ -34,12 +44,38 @@ impl App {
         Self {
             theme: Theme::default(),
             registry: ModelRegistry::new(),
+            whisper: Config::load().map(|c| c.whisper).unwrap_or_default(),
             selected_index: 0,
             list_state,
             should_quit: false,
         }
     }
 
+    fn capabilities(&self) -> Option<ProviderCapabilities> {
+        self.whisper
+            .provider
+            .as_deref()
+            .and_then(provider_capabilities)
+    }
+
+    fn status_line(&self) -> String {
+        let provider = self.whisper.provider.as_deref().unwrap_or("<not set>");
+        let language = self.whisper.language.as_deref().unwrap_or("en");
+
+        match self.capabilities() {
+            Some(caps) => match caps.check_language(language) {
+                Ok(()) => format!(
+                    " Ready │ Provider: {} ({}) │ Lang: {} ",
+                    provider,
+                    caps.locality.as_str(),
+                    language
+                ),
+                Err(error) => format!(" {} │ Provider: {} ", error, provider),
+            },
+            None => format!(" Unknown provider: {} ", provider),
+        }
+    }
+
     fn next(&mut self) {
         let len = self.registry.models.len() + 2;
         self.selected_index = (self.selected_index + 1) % len;
@@ -133,7 +169,7 @@ fn ui(frame: &mut Frame, app: &mut App) {
         .split(chunks[1]);
 
     render_model_list(frame, app, main_chunks[0], &theme);
-    render_cloud_providers(frame, main_chunks[1], &theme);
+    render_cloud_providers(frame, app, main_chunks[1], &theme);
 
     let actions = Paragraph::new(" [d] Download  [x] Delete  [Enter] Select  [q] Quit ")
         .style(Style::default().fg(theme.muted))
@@ -147,7 +183,7 @@ fn ui(frame: &mut Frame, app: &mut App) {
         );
     frame.render_widget(actions, chunks[2]);
 
-    let status = Paragraph::new(" Ready │ Provider: Groq │ Lang: en ")
+    let status = Paragraph::new(app.status_line())
         .style(Style::default().fg(theme.muted).bg(theme.selection));
     frame.render_widget(status, chunks[3]);
 }
@@ -208,24 +244,41 @@ fn render_model_list(frame: &mut Frame, app: &mut App, area: Rect, theme: &Theme
     frame.render_stateful_widget(list, area, &mut app.list_state);
 }
 
-fn render_cloud_providers(frame: &mut Frame, area: Rect, theme: &Theme) {
-    let items = vec![
-        ListItem::new(Line::from(vec![
-            Span::styled(" 󰄬 ", Style::default().fg(theme.success)),
-            Span::styled("Groq Cloud", Style::default().fg(theme.fg)),
-            Span::styled(
-                "  (whisper-large-v3-turbo)",
-                Style::default().fg(theme.muted),
-            ),
-            Span::styled("  󰌆 API Key", Style::default().fg(theme.success)),
-        ])),
-        ListItem::new(Line::from(vec![
-            Span::styled("   ", Style::default()),
-            Span::styled("OpenAI Cloud", Style::default().fg(theme.fg)),
-            Span::styled("  (whisper-1)", Style::default().fg(theme.muted)),
-            Span::styled("  󰌆 API Key", Style::default().fg(theme.muted)),
-        ])),
-    ];
+fn render_cloud_providers(frame: &mut Frame, app: &App, area: Rect, theme: &Theme) {
+    let current = app.whisper.provider.as_deref();
+
+    let items: Vec<ListItem> = CLOUD_PROVIDERS
+        .iter()
+        .filter_map(|(id, name, default_model)| {
+            let caps = provider_capabilities(id).filter(|caps| !caps.is_local())?;
+            let active = current == Some(*id);
+            let model = if active {
+                app.whisper.model.as_deref().unwrap_or(default_model)
+            } else {
+                default_model
+            };
+            let key_style = if active && app.whisper.api_key.is_some() {
+                Style::default().fg(theme.success)
+            } else {
+                Style::default().fg(theme.muted)
+            };
+
+            Some(ListItem::new(Line::from(vec![
+                if active {
+                    Span::styled(" 󰄬 ", Style::default().fg(theme.success))
+                } else {
+                    Span::styled("   ", Style::default())
+                },
+                Span::styled(*name, Style::default().fg(theme.fg)),
+                Span::styled(format!("  ({})", model), Style::default().fg(theme.muted)),
+                Span::styled("  󰌆 API Key", key_style),
+                Span::styled(
+                    format!("  {}", caps.features().join(" · ")),
+                    Style::default().fg(theme.muted),
+                ),
+            ])))
+        })
+        .collect();
 
     let list = List::new(items).block(
         Block::default()
@@ -65,6 +65,19 @@ impl Indicator {
         Ok(())
     }
 
+    /// Show a desktop notification via `notify-send` (best effort).
+    pub async fn notify(&self, summary: &str, body: &str) -> Result<()> {
+        info!("{}: {} chars", summary, body.len());
+        if let Err(e) = tokio::process::Command::new("notify-send")
+            .args(["--app-name=WisprArch", summary, body])
+            .status()
+            .await
+        {
+            warn!("Failed to send notification: {}", e);
+        }
+        Ok(())
+    }
+
     pub async fn show_error(&self, error: &str) -> Result<()> {
         warn!("Error: {}", error);
         Ok(())
@@ -0,0 +1,362 @@
+//! Usage and cost tracking for transcription providers.
+//!
+//! Every transcription request is recorded with its provider, model and audio
+//! duration. This module builds daily/monthly rollups with estimated costs and
+//! evaluates the soft and hard quotas from `[usage]`. It is used by the
+//! transcription service, the CLI and the REST API.
+
+use crate::config::{Config, QuotaPeriod, UsageConfig};
+use crate::db::{self, UsageEvent, UsageRollupRow};
+use anyhow::{anyhow, Context, Result};
+use chrono::{Datelike, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tracing::{debug, error, warn};
+
+/// Rollup granularity.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Granularity {
+    #[default]
+    Daily,
+    Monthly,
+}
+
+impl Granularity {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "daily" | "day" => Some(Granularity::Daily),
+            "monthly" | "month" => Some(Granularity::Monthly),
+            _ => None,
+        }
+    }
+
+    fn period_format(&self) -> &'static str {
+        match self {
+            Granularity::Daily => "%Y-%m-%d",
+            Granularity::Monthly => "%Y-%m",
+        }
+    }
+}
+
+/// Usage of one provider/model in one period.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageSummary {
+    pub period: String,
+    pub provider: String,
+    pub model: Option<String>,
+    pub requests: i64,
+    pub failures: i64,
+    pub audio_minutes: f64,
+    /// Estimated cost in USD (`None` if no price is configured)
+    pub estimated_cost_usd: Option<f64>,
+}
+
+impl UsageSummary {
+    fn from_row(row: UsageRollupRow, config: &UsageConfig) -> Self {
+        let audio_minutes = row.audio_secs / 60.0;
+        let estimated_cost_usd = price_per_minute(config, &row.provider, row.model.as_deref())
+            .map(|price| price * audio_minutes);
+        Self {
+            period: row.period,
+            provider: row.provider,
+            model: row.model,
+            requests: row.requests,
+            failures: row.failures,
+            audio_minutes,
+            estimated_cost_usd,
+        }
+    }
+}
+
+/// Whether usage in the current period is within the configured quotas.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "state", content = "message", rename_all = "snake_case")]
+pub enum QuotaState {
+    Ok,
+    SoftExceeded(String),
+    HardExceeded(String),
+}
+
+/// Usage of a provider in the current quota period.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuotaReport {
+    pub provider: String,
+    pub period: QuotaPeriod,
+    /// First day of the current period (YYYY-MM-DD)
+    pub period_start: String,
+    pub audio_minutes: f64,
+    pub estimated_cost_usd: f64,
+    pub state: QuotaState,
+}
+
+/// Price per audio minute for a provider, preferring `provider/model` keys.
+pub fn price_per_minute(config: &UsageConfig, provider: &str, model: Option<&str>) -> Option<f64> {
+    model
+        .and_then(|model| config.prices.get(&format!("{}/{}", provider, model)))
+        .or_else(|| config.prices.get(provider))
+        .copied()
+}
+
+/// Record a transcription request.
+pub fn record(provider: &str, model: Option<&str>, audio_secs: f64, success: bool) -> Result<()> {
+    let conn = db::init_db()?;
+    db::insert_usage_event(
+        &conn,
+        &UsageEvent {
+            provider: provider.to_string(),
+            model: model.map(str::to_string),
+            audio_secs,
+            success,
+        },
+    )?;
+    Ok(())
+}
+
+/// Usage rollups with estimated costs, newest period first.
+pub fn rollup(
+    config: &UsageConfig,
+    granularity: Granularity,
+    since: Option<&str>,
+) -> Result<Vec<UsageSummary>> {
+    if let Some(since) = since {
+        NaiveDate::parse_from_str(since, "%Y-%m-%d")
+            .map_err(|_| anyhow!("Invalid date '{}', expected YYYY-MM-DD", since))?;
+    }
+
+    let conn = db::init_db()?;
+    let rows = db::get_usage_rollup(&conn, granularity.period_format(), since, None)?;
+    Ok(rows
+        .into_iter()
+        .map(|row| UsageSummary::from_row(row, config))
+        .collect())
+}
+
+/// Usage of the given provider in the current quota period.
+pub fn quota_report(config: &UsageConfig, provider: &str) -> Result<QuotaReport> {
+    let period_start = period_start(config.quota_period, Local::now().date_naive());
+    let since = period_start.format("%Y-%m-%d").to_string();
+
+    let conn = db::init_db()?;
+    let rows = db::get_usage_rollup(&conn, "%Y-%m-%d", Some(&since), Some(provider))?;
+
+    let mut audio_minutes = 0.0;
+    let mut estimated_cost_usd = 0.0;
+    for summary in rows
+        .into_iter()
+        .map(|row| UsageSummary::from_row(row, config))
+    {
+        audio_minutes += summary.audio_minutes;
+        estimated_cost_usd += summary.estimated_cost_usd.unwrap_or(0.0);
+    }
+
+    Ok(QuotaReport {
+        provider: provider.to_string(),
+        period: config.quota_period,
+        period_start: since,
+        audio_minutes,
+        estimated_cost_usd,
+        state: evaluate_quota(config, audio_minutes, estimated_cost_usd),
+    })
+}
+
+/// Compare usage against the configured quotas. Hard quotas take precedence.
+pub fn evaluate_quota(config: &UsageConfig, audio_minutes: f64, cost_usd: f64) -> QuotaState {
+    let exceeded = |limit: Option<f64>, used: f64| limit.filter(|limit| used >= *limit);
+    let period = match config.quota_period {
+        QuotaPeriod::Day => "daily",
+        QuotaPeriod::Month => "monthly",
+    };
+
+    if let Some(limit) = exceeded(config.hard_quota_minutes, audio_minutes) {
+        return QuotaState::HardExceeded(format!(
+            "Hard {} quota of {:.0} audio minutes reached ({:.1} used)",
+            period, limit, audio_minutes
+        ));
+    }
+    if let Some(limit) = exceeded(config.hard_quota_usd, cost_usd) {
+        return QuotaState::HardExceeded(format!(
+            "Hard {} budget of ${:.2} reached (${:.2} used)",
+            period, limit, cost_usd
+        ));
+    }
+    if let Some(limit) = exceeded(config.soft_quota_minutes, audio_minutes) {
+        return QuotaState::SoftExceeded(format!(
+            "Soft {} quota of {:.0} audio minutes exceeded ({:.1} used)",
+            period, limit, audio_minutes
+        ));
+    }
+    if let Some(limit) = exceeded(config.soft_quota_usd, cost_usd) {
+        return QuotaState::SoftExceeded(format!(
+            "Soft {} budget of ${:.2} exceeded (${:.2} used)",
+            period, limit, cost_usd
+        ));
+    }
+
+    QuotaState::Ok
+}
+
+fn period_start(period: QuotaPeriod, today: NaiveDate) -> NaiveDate {
+    match period {
+        QuotaPeriod::Day => today,
+        QuotaPeriod::Month => today.with_day(1).unwrap_or(today),
+    }
+}
+
+/// Records requests for one provider and enforces its quotas.
+///
+/// Quotas only apply to cloud providers; local usage is recorded but never
+/// limited.
+#[derive(Debug, Clone)]
+pub struct UsageTracker {
+    provider: String,
+    model: Option<String>,
+    config: UsageConfig,
+    enforce_quotas: bool,
+}
+
+impl UsageTracker {
+    pub fn new(
+        provider: impl Into<String>,
+        model: Option<String>,
+        config: UsageConfig,
+        enforce_quotas: bool,
+    ) -> Self {
+        Self {
+            provider: provider.into(),
+            model,
+            config,
+            enforce_quotas,
+        }
+    }
+
+    /// Current quota state. Database errors are logged and treated as `Ok`.
+    pub async fn check_quota(&self) -> QuotaState {
+        if !self.enforce_quotas {
+            return QuotaState::Ok;
+        }
+
+        let config = self.config.clone();
+        let provider = self.provider.clone();
+        match tokio::task::spawn_blocking(move || quota_report(&config, &provider)).await {
+            Ok(Ok(report)) => report.state,
+            Ok(Err(e)) => {
+                error!("Failed to check usage quota: {:?}", e);
+                QuotaState::Ok
+            }
+            Err(e) => {
+                error!("Usage quota task panicked: {:?}", e);
+                QuotaState::Ok
+            }
+        }
+    }
+
+    /// Record a request for the given recording. Failures are only logged.
+    pub async fn record(&self, audio_path: &Path, success: bool) {
+        let audio_secs = crate::audio::wav_duration_secs(audio_path).unwrap_or_else(|e| {
+            warn!("Could not determine audio duration for usage: {}", e);
+            0.0
+        });
+
+        let provider = self.provider.clone();
+        let model = self.model.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            record(&provider, model.as_deref(), audio_secs, success)
+        })
+        .await
+        .context("Usage task panicked");
+
+        match result.and_then(|r| r) {
+            Ok(()) => debug!(
+                "Recorded {:.1}s of {} usage (success={})",
+                audio_secs, self.provider, success
+            ),
+            Err(e) => error!("Failed to record usage: {:?}", e),
+        }
+    }
+}
+
+/// Quota report for the provider configured in `[whisper]`.
+pub fn current_quota_report(config: &Config) -> Result<Option<QuotaReport>> {
+    match config.whisper.provider.as_deref() {
+        Some(provider) => quota_report(&config.usage, provider).map(Some),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> UsageConfig {
+        let mut config = UsageConfig::default();
+        config.prices.insert("groq".to_string(), 0.001);
+        config
+            .prices
+            .insert("openai-api/whisper-1".to_string(), 0.006);
+        config
+    }
+
+    #[test]
+    fn test_price_per_minute_prefers_model() {
+        let config = config();
+        assert_eq!(
+            price_per_minute(&config, "groq", Some("turbo")),
+            Some(0.001)
+        );
+        assert_eq!(
+            price_per_minute(&config, "openai-api", Some("whisper-1")),
+            Some(0.006)
+        );
+        assert_eq!(price_per_minute(&config, "openai-api", None), None);
+    }
+
+    #[test]
+    fn test_summary_cost() {
+        let row = UsageRollupRow {
+            period: "2025-01".to_string(),
+            provider: "groq".to_string(),
+            model: None,
+            requests: 4,
+            failures: 1,
+            audio_secs: 600.0,
+        };
+        let summary = UsageSummary::from_row(row, &config());
+        assert_eq!(summary.audio_minutes, 10.0);
+        assert_eq!(summary.estimated_cost_usd, Some(0.01));
+    }
+
+    #[test]
+    fn test_evaluate_quota() {
+        let mut config = UsageConfig {
+            soft_quota_minutes: Some(100.0),
+            hard_quota_minutes: Some(200.0),
+            ..UsageConfig::default()
+        };
+        assert_eq!(evaluate_quota(&config, 50.0, 0.0), QuotaState::Ok);
+        assert!(matches!(
+            evaluate_quota(&config, 150.0, 0.0),
+            QuotaState::SoftExceeded(_)
+        ));
+        assert!(matches!(
+            evaluate_quota(&config, 250.0, 0.0),
+            QuotaState::HardExceeded(_)
+        ));
+
+        config.hard_quota_usd = Some(5.0);
+        assert!(matches!(
+            evaluate_quota(&config, 10.0, 5.0),
+            QuotaState::HardExceeded(_)
+        ));
+    }
+
+    #[test]
+    fn test_period_start() {
+        let today = NaiveDate::from_ymd_opt(2025, 3, 17).unwrap();
+        assert_eq!(period_start(QuotaPeriod::Day, today), today);
+        assert_eq!(
+            period_start(QuotaPeriod::Month, today),
+            NaiveDate::from_ymd_opt(2025, 3, 1).unwrap()
+        );
+    }
+}